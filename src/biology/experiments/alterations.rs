@@ -4,18 +4,14 @@ use crate::simulation::common::*;
 use crate::util::get_from_range;
 
 use rand::Rng;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::convert::TryInto;
 
 pub type GenomeAlterationTypeKey = String;
 pub type ExecuteGenomeAlterationFn<A> = dyn Fn(&[&CompiledFramedGenome], &[A]) -> Vec<A>;
 pub type PrepareAlterationParamsFn<A> = dyn Fn(&[&CompiledFramedGenome]) -> Vec<A>;
 
-// TODO: build out this concept and weights
-// #[derive(Clone)]
-// pub struct AlterationManifest {
-//     pub alterations: Vec<GenomeAlterationImplementation>,
-// }
-
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AlterationManifestEntry {
     pub key: String,
     pub weight: usize,
@@ -42,17 +38,56 @@ pub struct GenomeAlterationImplementation {
 #[derive(Clone)]
 pub struct CompiledAlterationSet {
     pub alterations: Vec<GenomeAlterationImplementation>,
+
+    // relative odds of each alteration being chosen, parallel to alterations
+    pub weights: Vec<usize>,
 }
 
 impl CompiledAlterationSet {
     pub fn new(alterations: Vec<GenomeAlterationImplementation>) -> CompiledAlterationSet {
         let mut set = CompiledAlterationSet {
+            weights: vec![1; alterations.len()],
             alterations: alterations.clone(),
         };
         set.normalize();
         set
     }
 
+    pub fn from_entries(entries: &[AlterationManifestEntry]) -> Self {
+        let keys = entries.iter().map(|e| e.key.clone()).collect::<Vec<_>>();
+        let mut set = Self::from_keys(&keys);
+        set.weights = set
+            .alterations
+            .iter()
+            .map(|a| entries.iter().find(|e| e.key == a.key).unwrap().weight)
+            .collect();
+        set
+    }
+
+    pub fn entries(&self) -> Vec<AlterationManifestEntry> {
+        self.alterations
+            .iter()
+            .zip(self.weights.iter())
+            .map(|(a, weight)| AlterationManifestEntry::new(&a.key, *weight))
+            .collect()
+    }
+
+    pub fn choose_random(&self) -> GenomeAlterationImplementation {
+        let total = self.weights.iter().sum::<usize>();
+        if total == 0 {
+            panic!("Alteration set has no alterations with a weight above 0");
+        }
+
+        let mut roll = rand::thread_rng().gen_range(0..total);
+        for (alteration, weight) in self.alterations.iter().zip(self.weights.iter()) {
+            if roll < *weight {
+                return alteration.clone();
+            }
+            roll -= weight;
+        }
+        unreachable!()
+    }
+
     pub fn from_keys(keys: &Vec<String>) -> Self {
         let all_alterations = default_alterations();
         let invalid = keys
@@ -99,12 +134,24 @@ pub fn get_random_genome_value() -> FramedGenomeValue {
     rng.gen_range(0..FramedGenomeValue::MAX)
 }
 
+/**
+ * Alteration sets are saved as their keys and weights, since the implementations are closures.
+ */
+impl Serialize for CompiledAlterationSet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.entries().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CompiledAlterationSet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let entries = Vec::<AlterationManifestEntry>::deserialize(deserializer)?;
+        Ok(Self::from_entries(&entries))
+    }
+}
+
 pub fn default_alteration_set() -> CompiledAlterationSet {
-    let mut set = CompiledAlterationSet {
-        alterations: default_alterations(),
-    };
-    set.normalize();
-    set
+    CompiledAlterationSet::new(default_alterations())
 }
 
 pub fn default_alterations() -> Vec<GenomeAlterationImplementation> {
@@ -4,11 +4,13 @@ use crate::simulation::{
     SimCell,
 };
 
+use serde::{Deserialize, Serialize};
+
 use super::types::{GenomeEntryId, TrialResultItem};
 
 pub type ExperimentFitnessRank = usize;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum FitnessRankAdjustmentMethod {
     Absolute,
 
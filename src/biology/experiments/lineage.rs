@@ -0,0 +1,413 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::alterations::GenomeAlterationTypeKey;
+use super::types::ExperimentGenomeUid;
+use super::variants::multi_pool::gene_pool::GenePoolId;
+
+/**
+ * Where a genome came from.  Seed genomes have no parents and no alteration, genomes that
+ * migrated from another gene pool point at their uid in that pool.
+ */
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GenomeLineage {
+    pub parents: Vec<ExperimentGenomeUid>,
+    pub alteration: Option<GenomeAlterationTypeKey>,
+    pub birth_tick: u64,
+    pub immigrated_from: Option<(GenePoolId, ExperimentGenomeUid)>,
+}
+
+impl GenomeLineage {
+    pub fn seed(birth_tick: u64) -> Self {
+        Self {
+            birth_tick,
+            ..Self::default()
+        }
+    }
+
+    pub fn altered(parents: Vec<ExperimentGenomeUid>, alteration: &str, birth_tick: u64) -> Self {
+        Self {
+            parents,
+            alteration: Some(alteration.to_string()),
+            birth_tick,
+            immigrated_from: None,
+        }
+    }
+
+    pub fn immigrant(gene_pool_id: GenePoolId, uid: ExperimentGenomeUid, birth_tick: u64) -> Self {
+        Self {
+            birth_tick,
+            immigrated_from: Some((gene_pool_id, uid)),
+            ..Self::default()
+        }
+    }
+}
+
+/**
+ * A genome's lineage, kept after the genome itself has been culled so that the ancestry of
+ * living genomes can be traced.
+ */
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LineageRecord {
+    pub uid: ExperimentGenomeUid,
+    pub lineage: GenomeLineage,
+}
+
+pub const LINEAGE_CSV_HEADER: &str = "uid,birth_tick,alteration,parents,immigrated_from\n";
+
+impl LineageRecord {
+    /**
+     * One row of a gene pool's lineage.csv.  Parents are separated by semicolons and
+     * immigrants are written as pool:uid.
+     */
+    pub fn to_csv_line(&self) -> String {
+        let parents = self
+            .lineage
+            .parents
+            .iter()
+            .map(|uid| uid.to_string())
+            .collect::<Vec<_>>()
+            .join(";");
+        let immigrated_from = self
+            .lineage
+            .immigrated_from
+            .map(|(gene_pool_id, uid)| format!("{}:{}", gene_pool_id, uid))
+            .unwrap_or_default();
+
+        format!(
+            "{},{},{},{},{}\n",
+            self.uid,
+            self.lineage.birth_tick,
+            self.lineage.alteration.clone().unwrap_or_default(),
+            parents,
+            immigrated_from
+        )
+    }
+
+    pub fn from_csv_line(line: &str) -> Self {
+        let cols = line.trim().split(",").collect::<Vec<_>>();
+        if cols.len() != 5 {
+            panic!("Invalid lineage row: {}", line);
+        }
+
+        let parse_uid = |s: &str| {
+            s.parse::<ExperimentGenomeUid>()
+                .expect(&format!("Invalid uid in lineage row: {}", line))
+        };
+
+        let parents = cols[3]
+            .split(";")
+            .filter(|s| !s.is_empty())
+            .map(parse_uid)
+            .collect::<Vec<_>>();
+        let immigrated_from = if cols[4].is_empty() {
+            None
+        } else {
+            let (gene_pool_id, uid) = cols[4]
+                .split_once(":")
+                .expect(&format!("Invalid immigrant in lineage row: {}", line));
+            Some((parse_uid(gene_pool_id), parse_uid(uid)))
+        };
+
+        LineageRecord {
+            uid: parse_uid(cols[0]),
+            lineage: GenomeLineage {
+                parents,
+                alteration: if cols[2].is_empty() {
+                    None
+                } else {
+                    Some(cols[2].to_string())
+                },
+                birth_tick: cols[1]
+                    .parse::<u64>()
+                    .expect(&format!("Invalid birth tick in lineage row: {}", line)),
+                immigrated_from,
+            },
+        }
+    }
+}
+
+pub fn parse_lineage_csv(contents: &str) -> Vec<LineageRecord> {
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty() && line.trim() != LINEAGE_CSV_HEADER.trim())
+        .map(LineageRecord::from_csv_line)
+        .collect()
+}
+
+fn node_label(uid: ExperimentGenomeUid) -> String {
+    format!("g{}", uid)
+}
+
+/**
+ * Newick can only express trees, so each genome hangs off its first parent only; the other
+ * parents of crossovers are dropped.  Branch lengths are measured in gene pool ticks.  Genomes
+ * without a known parent become children of an unnamed root.
+ */
+pub fn lineage_to_newick(records: &[LineageRecord]) -> String {
+    let by_uid = records
+        .iter()
+        .map(|record| (record.uid, record))
+        .collect::<HashMap<_, _>>();
+
+    let mut children: HashMap<ExperimentGenomeUid, Vec<ExperimentGenomeUid>> = HashMap::new();
+    let mut roots = vec![];
+    for record in records {
+        match record.lineage.parents.first() {
+            Some(parent) if by_uid.contains_key(parent) => {
+                children.entry(*parent).or_default().push(record.uid)
+            }
+            _ => roots.push(record.uid),
+        }
+    }
+
+    // the lineages can be thousands of generations deep, so this avoids recursion
+    let mut rendered: HashMap<ExperimentGenomeUid, String> = HashMap::new();
+    let mut stack = roots.iter().map(|uid| (*uid, false)).collect::<Vec<_>>();
+    while let Some((uid, children_done)) = stack.pop() {
+        let node_children = children.get(&uid).cloned().unwrap_or_default();
+        if !children_done && !node_children.is_empty() {
+            stack.push((uid, true));
+            stack.extend(node_children.iter().map(|child| (*child, false)));
+            continue;
+        }
+
+        let record = by_uid[&uid];
+        let mut s = String::new();
+        if !node_children.is_empty() {
+            let subtrees = node_children
+                .iter()
+                .map(|child| rendered.remove(child).unwrap())
+                .collect::<Vec<_>>();
+            s.push_str(&format!("({})", subtrees.join(",")));
+        }
+        s.push_str(&node_label(uid));
+
+        let parent_birth_tick = record
+            .lineage
+            .parents
+            .first()
+            .and_then(|parent| by_uid.get(parent))
+            .map(|parent| parent.lineage.birth_tick);
+        if let Some(parent_birth_tick) = parent_birth_tick {
+            s.push_str(&format!(
+                ":{}",
+                record.lineage.birth_tick.saturating_sub(parent_birth_tick)
+            ));
+        }
+
+        rendered.insert(uid, s);
+    }
+
+    let trees = roots
+        .iter()
+        .map(|uid| rendered.remove(uid).unwrap())
+        .collect::<Vec<_>>();
+
+    if trees.len() == 1 {
+        format!("{};", trees[0])
+    } else {
+        format!("({});", trees.join(","))
+    }
+}
+
+/**
+ * The full lineage graph, with an edge from every parent to its child, for tools like Gephi or
+ * networkx.
+ */
+pub fn lineage_to_graphml(records: &[LineageRecord]) -> String {
+    let uids = records
+        .iter()
+        .map(|record| record.uid)
+        .collect::<HashSet<_>>();
+
+    let mut s = String::new();
+    s.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    s.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    s.push_str(
+        "  <key id=\"birth_tick\" for=\"node\" attr.name=\"birth_tick\" attr.type=\"long\"/>\n",
+    );
+    s.push_str(
+        "  <key id=\"alteration\" for=\"node\" attr.name=\"alteration\" attr.type=\"string\"/>\n",
+    );
+    s.push_str("  <key id=\"immigrated_from\" for=\"node\" attr.name=\"immigrated_from\" attr.type=\"string\"/>\n");
+    s.push_str("  <graph id=\"lineage\" edgedefault=\"directed\">\n");
+
+    for record in records {
+        s.push_str(&format!("    <node id=\"{}\">\n", node_label(record.uid)));
+        s.push_str(&format!(
+            "      <data key=\"birth_tick\">{}</data>\n",
+            record.lineage.birth_tick
+        ));
+        if let Some(alteration) = &record.lineage.alteration {
+            s.push_str(&format!(
+                "      <data key=\"alteration\">{}</data>\n",
+                alteration
+            ));
+        }
+        if let Some((gene_pool_id, uid)) = record.lineage.immigrated_from {
+            s.push_str(&format!(
+                "      <data key=\"immigrated_from\">{}:{}</data>\n",
+                gene_pool_id, uid
+            ));
+        }
+        s.push_str("    </node>\n");
+    }
+
+    for record in records {
+        for parent in record
+            .lineage
+            .parents
+            .iter()
+            .filter(|uid| uids.contains(uid))
+        {
+            s.push_str(&format!(
+                "    <edge source=\"{}\" target=\"{}\"/>\n",
+                node_label(*parent),
+                node_label(record.uid)
+            ));
+        }
+    }
+
+    s.push_str("  </graph>\n");
+    s.push_str("</graphml>\n");
+    s
+}
+
+/**
+ * How many distinct founding genomes the given genomes descend from, following first parents.
+ * A pool where every living genome shares one founder has a diversity of 1.
+ */
+pub fn lineage_diversity(records: &[LineageRecord], uids: &[ExperimentGenomeUid]) -> usize {
+    let first_parents = first_parents(records);
+
+    uids.iter()
+        .map(|uid| founder_of(&first_parents, *uid))
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/**
+ * Keeps only the records of the given genomes, with each one's parents replaced by its
+ * founding genome.  The diversity of those genomes stays the same, so a gene pool can drop
+ * the rest of its lineage once it has been written to disk.
+ */
+pub fn collapse_to_founders(
+    records: &[LineageRecord],
+    uids: &[ExperimentGenomeUid],
+) -> Vec<LineageRecord> {
+    let first_parents = first_parents(records);
+    let uids = uids.iter().collect::<HashSet<_>>();
+
+    records
+        .iter()
+        .filter(|record| uids.contains(&record.uid))
+        .map(|record| {
+            let founder = founder_of(&first_parents, record.uid);
+            let mut record = record.clone();
+            if founder != record.uid {
+                record.lineage.parents = vec![founder];
+            }
+            record
+        })
+        .collect()
+}
+
+fn first_parents(
+    records: &[LineageRecord],
+) -> HashMap<ExperimentGenomeUid, Option<ExperimentGenomeUid>> {
+    records
+        .iter()
+        .map(|record| (record.uid, record.lineage.parents.first().cloned()))
+        .collect()
+}
+
+fn founder_of(
+    first_parents: &HashMap<ExperimentGenomeUid, Option<ExperimentGenomeUid>>,
+    uid: ExperimentGenomeUid,
+) -> ExperimentGenomeUid {
+    let mut uid = uid;
+    while let Some(Some(parent)) = first_parents.get(&uid) {
+        uid = *parent;
+    }
+    uid
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    fn records() -> Vec<LineageRecord> {
+        vec![
+            LineageRecord {
+                uid: 0,
+                lineage: GenomeLineage::seed(0),
+            },
+            LineageRecord {
+                uid: 1,
+                lineage: GenomeLineage::seed(0),
+            },
+            LineageRecord {
+                uid: 2,
+                lineage: GenomeLineage::altered(vec![0], "insertion", 3),
+            },
+            LineageRecord {
+                uid: 3,
+                lineage: GenomeLineage::altered(vec![2, 1], "crossover", 5),
+            },
+            LineageRecord {
+                uid: 4,
+                lineage: GenomeLineage::immigrant(1, 7, 6),
+            },
+        ]
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let records = records();
+        let mut csv = LINEAGE_CSV_HEADER.to_string();
+        for record in records.iter() {
+            csv.push_str(&record.to_csv_line());
+        }
+
+        assert_eq!(parse_lineage_csv(&csv), records);
+    }
+
+    #[test]
+    fn newick() {
+        assert_eq!(lineage_to_newick(&records()), "(((g3:2)g2:3)g0,g1,g4);");
+        assert_eq!(lineage_to_newick(&records()[0..1]), "g0;");
+    }
+
+    #[test]
+    fn graphml() {
+        let graphml = lineage_to_graphml(&records());
+        assert_eq!(graphml.matches("<node ").count(), 5);
+        assert_eq!(graphml.matches("<edge ").count(), 3);
+        assert!(graphml.contains("<edge source=\"g1\" target=\"g3\"/>"));
+        assert!(graphml.contains("<data key=\"immigrated_from\">1:7</data>"));
+    }
+
+    #[test]
+    fn diversity() {
+        let records = records();
+        assert_eq!(lineage_diversity(&records, &[2, 3]), 1);
+        assert_eq!(lineage_diversity(&records, &[1, 3, 4]), 3);
+    }
+
+    #[test]
+    fn collapsing_to_founders_keeps_diversity() {
+        let records = records();
+        let collapsed = collapse_to_founders(&records, &[1, 3, 4]);
+
+        assert_eq!(collapsed.len(), 3);
+        assert_eq!(collapsed[1].lineage.parents, vec![0]);
+        assert_eq!(
+            collapsed[1].lineage.alteration,
+            Some("crossover".to_string())
+        );
+        assert_eq!(lineage_diversity(&collapsed, &[1, 3, 4]), 3);
+        assert_eq!(lineage_diversity(&collapsed, &[3]), 1);
+    }
+}
@@ -1,8 +1,12 @@
-pub mod builders;
-pub mod util;
 pub mod alterations;
+pub mod builders;
 pub mod fitness;
+pub mod lineage;
 pub mod logging;
+pub mod seeding;
 pub mod sim_runner;
+pub mod sweep;
+pub mod tournament;
 pub mod types;
+pub mod util;
 pub mod variants;
@@ -0,0 +1,347 @@
+use std::path::Path;
+
+use rand::Rng;
+
+use crate::biology::genome::framed::common::{FramedGenomeWord, RawFramedGenome};
+use crate::biology::genome::framed::file::GenomeFile;
+use crate::biology::genome::framed::samples::sample_genome_by_name;
+use crate::simulation::common::GeneticManifest;
+
+use super::logging::get_exp_genomes_dir;
+use super::types::SeedGenomeSettings;
+use super::util::random_genome_of_length;
+
+/**
+ * Parses the genome csv files that experiments log, one genome per line.
+ */
+pub fn parse_genome_csv(contents: &str) -> Vec<RawFramedGenome> {
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            line.trim()
+                .split(",")
+                .map(|v| {
+                    v.trim()
+                        .parse::<FramedGenomeWord>()
+                        .expect(&format!("Invalid genome word: {}", v))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/**
+ * Loads either a genome csv or a .ron genome file, translated to gm if they were saved against a
+ * different manifest.  A csv carries no manifest of its own, so it is read against the .ron genome
+ * file logged next to it and refused when there is none.
+ */
+pub fn load_genomes(path: &Path, gm: &GeneticManifest) -> Vec<RawFramedGenome> {
+    let file = if path.extension().map_or(false, |ext| ext == "ron") {
+        GenomeFile::load(path)
+    } else {
+        let manifest_path = path.with_extension("ron");
+        if !manifest_path.exists() {
+            panic!(
+                "Genome csv {:?} has no genome file {:?} with the manifest it was saved against",
+                path, manifest_path
+            );
+        }
+
+        let contents = std::fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("Could not read genome file {:?}: {}", path, e));
+        GenomeFile {
+            genomes: parse_genome_csv(&contents),
+            ..GenomeFile::load(&manifest_path)
+        }
+    };
+
+    let (genomes, num_remapped) = file.remapped_genomes_for(gm);
+    if num_remapped > 0 {
+        println!(
+            "Translated {} genomes in {:?} to the current manifest",
+            num_remapped, path
+        );
+    }
+    genomes
+}
+
+/**
+ * Repeats the genomes in order until there are count of them.
+ */
+fn cycle_to(genomes: Vec<RawFramedGenome>, count: usize) -> Vec<RawFramedGenome> {
+    if genomes.is_empty() {
+        panic!("No seed genomes to choose from");
+    }
+    genomes.into_iter().cycle().take(count).collect()
+}
+
+/**
+ * Splits count between the parts of a mixed seed, proportional to their ratios.  Rounding is
+ * done on the running total so that the parts always add up to count.
+ */
+pub fn mixed_seed_counts(ratios: &[f32], count: usize) -> Vec<usize> {
+    let total = ratios.iter().sum::<f32>();
+    if total <= 0.0 {
+        panic!("Mixed seed ratios must add up to more than 0");
+    }
+
+    let mut cumulative = 0.0;
+    let mut assigned = 0;
+    ratios
+        .iter()
+        .map(|ratio| {
+            cumulative += ratio / total;
+            let until = (cumulative * count as f32).round() as usize;
+            let part = until.min(count) - assigned;
+            assigned += part;
+            part
+        })
+        .collect()
+}
+
+/**
+ * Creates count genomes to seed a gene pool with.
+ */
+pub fn seed_genomes(
+    settings: &SeedGenomeSettings,
+    count: usize,
+    gm: &GeneticManifest,
+) -> Vec<RawFramedGenome> {
+    match settings {
+        SeedGenomeSettings::Random { min_size, max_size } => {
+            if min_size > max_size {
+                panic!(
+                    "Random seed min_size ({}) is larger than max_size ({})",
+                    min_size, max_size
+                );
+            }
+
+            let mut rng = rand::thread_rng();
+            (0..count)
+                .map(|_| random_genome_of_length(rng.gen_range(*min_size..=*max_size)))
+                .collect()
+        }
+        SeedGenomeSettings::FromFiles { files } => {
+            let genomes = files
+                .iter()
+                .flat_map(|(exp_key, filename)| {
+                    let mut path = get_exp_genomes_dir(exp_key);
+                    path.push(filename);
+                    load_genomes(&path, gm)
+                })
+                .collect::<Vec<_>>();
+            cycle_to(genomes, count)
+        }
+        SeedGenomeSettings::FromSamples { samples } => {
+            let genomes = samples
+                .iter()
+                .map(|name| sample_genome_by_name(name, gm))
+                .collect::<Vec<_>>();
+            cycle_to(genomes, count)
+        }
+        SeedGenomeSettings::Mixed { parts } => {
+            let ratios = parts.iter().map(|(_, ratio)| *ratio).collect::<Vec<_>>();
+            parts
+                .iter()
+                .zip(mixed_seed_counts(&ratios, count))
+                .flat_map(|((settings, _), part_count)| seed_genomes(settings, part_count, gm))
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::chemistry::builder::ChemistryBuilder;
+
+    #[test]
+    fn mixed_counts() {
+        assert_eq!(mixed_seed_counts(&[1.0, 1.0], 5), vec![3, 2]);
+        assert_eq!(mixed_seed_counts(&[0.5, 0.25, 0.25], 20), vec![10, 5, 5]);
+        assert_eq!(
+            mixed_seed_counts(&[1.0, 1.0, 1.0], 10)
+                .iter()
+                .sum::<usize>(),
+            10
+        );
+        assert_eq!(mixed_seed_counts(&[0.0, 2.0], 4), vec![0, 4]);
+    }
+
+    #[test]
+    fn seeding() {
+        let gm = GeneticManifest::from_chemistry(&ChemistryBuilder::with_key("cheese").build());
+
+        let random = seed_genomes(
+            &SeedGenomeSettings::Random {
+                min_size: 5,
+                max_size: 8,
+            },
+            20,
+            &gm,
+        );
+        assert_eq!(random.len(), 20);
+        assert!(random.iter().all(|g| g.len() >= 5 && g.len() <= 8));
+
+        let samples = seed_genomes(
+            &SeedGenomeSettings::FromSamples {
+                samples: vec![
+                    "cheese::get_genome3".to_string(),
+                    "cheese::get_genome2".to_string(),
+                ],
+            },
+            3,
+            &gm,
+        );
+        assert_eq!(samples.len(), 3);
+        assert_eq!(samples[0], samples[2]);
+        assert_ne!(samples[0], samples[1]);
+
+        let mixed = seed_genomes(
+            &SeedGenomeSettings::Mixed {
+                parts: vec![
+                    (
+                        SeedGenomeSettings::FromSamples {
+                            samples: vec!["cheese::get_genome2".to_string()],
+                        },
+                        0.25,
+                    ),
+                    (
+                        SeedGenomeSettings::Random {
+                            min_size: 3,
+                            max_size: 3,
+                        },
+                        0.75,
+                    ),
+                ],
+            },
+            8,
+            &gm,
+        );
+        assert_eq!(mixed.len(), 8);
+        assert_eq!(mixed[0], samples[1]);
+        assert_eq!(mixed.iter().filter(|g| g.len() == 3).count(), 6);
+    }
+
+    #[test]
+    fn gene_pool_seeding() {
+        use crate::biology::experiments::variants::multi_pool::gene_pool::ExperimentGenePool;
+        use crate::scenarios::experiments::cheese::multi::{
+            base_gene_pool_settings, base_sim_settings,
+        };
+
+        let mut settings = base_gene_pool_settings(base_sim_settings().build());
+        settings
+            .num_genomes(6)
+            .seed_genome_settings(SeedGenomeSettings::Random {
+                min_size: 5,
+                max_size: 8,
+            });
+        let mut gene_pool = ExperimentGenePool::new(0, settings.build());
+
+        let sizes = |gene_pool: &ExperimentGenePool| {
+            gene_pool
+                .state
+                .genome_entries
+                .iter()
+                .map(|entry| entry.compiled_genome.raw_values.len())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(sizes(&gene_pool).len(), 6);
+        assert!(sizes(&gene_pool)
+            .iter()
+            .all(|size| *size >= 5 && *size <= 8));
+
+        let sample = sample_genome_by_name("cheese::get_genome2", &gene_pool.gm);
+        gene_pool.with_seed_genomes(vec![sample.clone()]);
+        assert_eq!(
+            gene_pool.state.genome_entries[0].compiled_genome.raw_values,
+            sample
+        );
+        assert_eq!(sizes(&gene_pool).len(), 6);
+        assert_eq!(gene_pool.state.lineage.len(), 6);
+    }
+
+    #[test]
+    fn load_genome_csv() {
+        let gm = GeneticManifest::from_chemistry(&ChemistryBuilder::with_key("cheese").build());
+        let genomes = vec![sample_genome_by_name("cheese::get_genome2", &gm)];
+
+        let dir = std::env::temp_dir().join(format!("genome_csv_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("0.csv");
+        GenomeFile::new(genomes.clone(), &gm).save(&path.with_extension("ron"));
+        std::fs::write(
+            &path,
+            genomes[0]
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+        .unwrap();
+        assert_eq!(load_genomes(&path, &gm), genomes);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn load_genome_csv_of_older_version() {
+        use crate::biology::genome::framed::common::*;
+
+        let gm = GeneticManifest::from_chemistry(&ChemistryBuilder::with_key("cheese").build());
+        let genome = frame_from_single_channel(vec![gene(
+            if_any(vec![if_all(vec![conditional!(gt, register(2), 5)])]),
+            then_do!(make_cheese, 1),
+        )])
+        .build(&gm);
+
+        let dir = std::env::temp_dir().join(format!("genome_csv_v2_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("0.csv");
+        let mut file = GenomeFile::new(vec![], &gm);
+        file.version = 2;
+        file.save(&path.with_extension("ron"));
+        std::fs::write(
+            &path,
+            genome
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+        .unwrap();
+
+        // registers read as 0 back then
+        let translated = frame_from_single_channel(vec![gene(
+            if_any(vec![if_all(vec![conditional!(gt, 0, 5)])]),
+            then_do!(make_cheese, 1),
+        )])
+        .build(&gm);
+        assert_eq!(load_genomes(&path, &gm), vec![translated]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "has no genome file")]
+    fn load_genome_csv_without_manifest() {
+        let gm = GeneticManifest::from_chemistry(&ChemistryBuilder::with_key("cheese").build());
+
+        let dir = std::env::temp_dir().join(format!("genome_csv_only_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("0.csv");
+        std::fs::write(&path, "1,2,3\n").unwrap();
+
+        load_genomes(&path, &gm);
+    }
+
+    #[test]
+    fn genome_csv() {
+        assert_eq!(
+            parse_genome_csv("1,2,3\n\n4, 5\n"),
+            vec![vec![1, 2, 3], vec![4, 5]]
+        );
+    }
+}
@@ -11,22 +11,43 @@ use crate::{
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
-use std::cell::RefCell;
 use std::fmt::{Debug, Formatter, Result};
 use std::path::PathBuf;
-use std::sync::mpsc;
+use std::sync::{mpsc, Arc};
 use std::time::Duration;
 use threadpool::ThreadPool;
 
 use super::types::{ExperimentGenomeUid, ExperimentSimSettings, GenomeEntryId, TrialResultItem};
 use super::variants::multi_pool::gene_pool::GenePoolId;
 
+/**
+ * How an entry differs from the rest of its simulation when genomes from opposing gene pools
+ * play together, eg. predators and prey.
+ */
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SimRunnerEntrySide {
+    pub fitness_calculation_key: String,
+    pub default_unit_attr: Vec<(String, UnitAttributeValue)>,
+}
+
+#[derive(Clone)]
 pub struct SimRunnerGenomeEntry {
     pub gene_pool_id: GenePoolId,
     pub genome_idx: GenomeEntryId,
     pub genome_uid: ExperimentGenomeUid,
     pub genome: CompiledFramedGenome,
     pub execution_stats: FramedGenomeExecutionStats,
+
+    // when set, replaces the runner's fitness calculation and default unit attributes
+    pub side: Option<SimRunnerEntrySide>,
+}
+
+impl SimRunnerGenomeEntry {
+    fn fitness_calculation_key<'a>(&'a self, default: &'a String) -> &'a String {
+        self.side
+            .as_ref()
+            .map_or(default, |side| &side.fitness_calculation_key)
+    }
 }
 
 impl Debug for SimRunnerGenomeEntry {
@@ -40,7 +61,7 @@ impl Debug for SimRunnerGenomeEntry {
 }
 
 pub struct ExperimentSimRunner {
-    gm: Rc<GeneticManifest>,
+    gm: Arc<GeneticManifest>,
     genomes: Vec<SimRunnerGenomeEntry>,
     sim_settings: ExperimentSimSettings,
     chemistry_builder: ChemistryBuilder,
@@ -55,7 +76,7 @@ impl ExperimentSimRunner {
         fitness_calculation_key: String,
     ) -> Self {
         let chemistry = chemistry_builder.clone().build();
-        let gm = GeneticManifest::from_chemistry(&chemistry).wrap_rc();
+        let gm = GeneticManifest::from_chemistry(&chemistry).wrap_arc();
 
         Self {
             genomes: genomes,
@@ -104,11 +125,9 @@ impl ExperimentSimRunner {
             let stats = stat_entries[i].clone();
             let sim_unit_entry_id = entry.info.unit_entry_id;
 
-            let genome_entry = self
-                .genomes
-                .iter()
-                .find(|genome_entry| genome_entry.genome_uid == entry.info.external_id)
-                .unwrap();
+            // uids are only unique within a gene pool, so entries are matched by position
+            let genome_entry = &self.genomes[i];
+            assert_eq!(genome_entry.genome_uid, entry.info.external_id);
             let genome_idx = genome_entry.genome_idx;
             let genome_uid = genome_entry.genome_uid;
             let genome = &genome_entry.genome;
@@ -117,7 +136,7 @@ impl ExperimentSimRunner {
             // println!("{:?}", executor.simulation.unit_entry_attributes);
 
             let mut fitness_score = calculate_fitness(
-                &self.fitness_calculation_key,
+                genome_entry.fitness_calculation_key(&self.fitness_calculation_key),
                 entry.info.unit_entry_id,
                 &mut executor.simulation.editable(),
             );
@@ -138,36 +157,44 @@ impl ExperimentSimRunner {
                 fitness_score,
                 genome_idx,
                 gene_pool_id: genome_entry.gene_pool_id,
-                stats: (*stats).clone().into_inner(), // inefficient
+                stats: (*stats).clone(), // inefficient
             };
             fitness_scores.push(resultItem);
         }
         fitness_scores
     }
 
-    pub fn get_unit_entries(
-        &mut self,
-    ) -> (Vec<UnitEntry>, Vec<Rc<RefCell<FramedGenomeExecutionStats>>>) {
+    pub fn get_unit_entries(&mut self) -> (Vec<UnitEntry>, Vec<Arc<FramedGenomeExecutionStats>>) {
         let mut unit_entries = vec![];
         let mut stat_entries = vec![];
         let mut count = 0;
         let cm = &self.gm.chemistry_manifest;
         for genome_entry in self.genomes.iter() {
-            let stats = Rc::new(RefCell::new(genome_entry.execution_stats.clone()));
+            let mut stats = genome_entry.execution_stats.clone();
+            stats.initialize(&genome_entry.genome.frames);
+            let stats = Arc::new(stats);
             stat_entries.push(stats.clone());
 
             let unit_entry = UnitEntryBuilder::default()
                 .species_name(format!("species: {}", count))
                 .behavior(
                     FramedGenomeUnitBehavior::new_with_stats(
-                        Rc::new(genome_entry.genome.clone()),
-                        Rc::new(self.gm.as_ref().clone()),
+                        Arc::new(genome_entry.genome.clone()),
+                        Arc::new(self.gm.as_ref().clone()),
                         stats,
                     )
                     .construct(),
                 )
                 .default_resources(self.sim_settings.default_unit_resources.clone())
-                .default_attributes(self.sim_settings.default_unit_attr.clone())
+                .default_attributes(
+                    genome_entry
+                        .side
+                        .as_ref()
+                        .map_or(&self.sim_settings.default_unit_attr, |side| {
+                            &side.default_unit_attr
+                        })
+                        .clone(),
+                )
                 .external_id(genome_entry.genome_uid)
                 .build(&self.gm.chemistry_manifest);
 
@@ -184,14 +211,32 @@ pub fn execute_sim_runners(
     use_threads: bool,
     sim_settings: &ExperimentSimSettings,
     fitness_calculation_key: &String,
+) -> Vec<Vec<TrialResultItem>> {
+    execute_sim_runners_with_settings(
+        groups
+            .into_iter()
+            .map(|entries| (entries, sim_settings.clone()))
+            .collect(),
+        use_threads,
+        fitness_calculation_key,
+    )
+}
+
+/**
+ * Like `execute_sim_runners`, but every group is simulated with its own settings, eg. with
+ * its own seed.
+ */
+pub fn execute_sim_runners_with_settings(
+    groups: Vec<(Vec<SimRunnerGenomeEntry>, ExperimentSimSettings)>,
+    use_threads: bool,
+    fitness_calculation_key: &String,
 ) -> Vec<Vec<TrialResultItem>> {
     if use_threads {
         let (tx, rx) = mpsc::channel();
         let pool = ThreadPool::new(5);
 
         let group_count = groups.len();
-        for entries in groups {
-            let sim_settings = sim_settings.clone();
+        for (entries, sim_settings) in groups {
             let fitness_key = fitness_calculation_key.clone();
             let chemistry_builder = sim_settings.chemistry_options.clone();
 
@@ -211,8 +256,7 @@ pub fn execute_sim_runners(
     } else {
         let result = groups
             .into_iter()
-            .map(|entries| {
-                let sim_settings = sim_settings.clone();
+            .map(|(entries, sim_settings)| {
                 let fitness_key = fitness_calculation_key.clone();
                 let chemistry_builder = sim_settings.chemistry_options.clone();
 
@@ -0,0 +1,614 @@
+use std::path::Path;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use threadpool::ThreadPool;
+
+use crate::chemistry::config::ChemistryConfigValue;
+use crate::chemistry::properties::ResourceAmount;
+use crate::simulation::fitness::FitnessScore;
+
+use super::types::{CullStrategy, ExperimentSimSettings};
+use super::variants::multi_pool::config::MultiPoolExperimentConfig;
+use super::variants::multi_pool::logger::{
+    MultiPoolExperimentLogger, MultiPoolExperimentLoggingSettings,
+};
+
+/**
+ * What a sweep parameter changes in the base config.  Gene pool targets are set on every pool.
+ */
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SweepTarget {
+    // a ChemistryConfiguration key, set for every gene pool and the reference simulations
+    Chemistry(String),
+
+    NumGenomes,
+    NumGenomesPerSim,
+    NumSimulationTicks,
+
+    // only applies to the WorstFirst cull strategy
+    CullPercent,
+
+    MaxIterations,
+    EvaluationPointsPerTick,
+}
+
+impl SweepTarget {
+    pub fn name(&self) -> String {
+        match self {
+            SweepTarget::Chemistry(key) => key.clone(),
+            SweepTarget::NumGenomes => "num_genomes".to_string(),
+            SweepTarget::NumGenomesPerSim => "num_genomes_per_sim".to_string(),
+            SweepTarget::NumSimulationTicks => "num_simulation_ticks".to_string(),
+            SweepTarget::CullPercent => "cull_percent".to_string(),
+            SweepTarget::MaxIterations => "max_iterations".to_string(),
+            SweepTarget::EvaluationPointsPerTick => "evaluation_points_per_tick".to_string(),
+        }
+    }
+
+    /**
+     * Sets the value in the config and returns it the way it was applied, eg. rounded for
+     * targets that only take whole numbers.
+     */
+    pub fn apply(
+        &self,
+        config: &mut MultiPoolExperimentConfig,
+        value: &ChemistryConfigValue,
+    ) -> ChemistryConfigValue {
+        let whole_number = || sweep_number(value).round() as u64;
+
+        match self {
+            SweepTarget::Chemistry(key) => {
+                let applied =
+                    set_chemistry_value(&mut config.settings.reference_sim_settings, key, value);
+                for gene_pool in config.gene_pools.iter_mut() {
+                    set_chemistry_value(&mut gene_pool.sim_settings, key, value);
+                }
+                applied
+            }
+            SweepTarget::NumGenomes => {
+                for gene_pool in config.gene_pools.iter_mut() {
+                    gene_pool.num_genomes = whole_number() as usize;
+                }
+                ChemistryConfigValue::Integer(whole_number())
+            }
+            SweepTarget::NumGenomesPerSim => {
+                for gene_pool in config.gene_pools.iter_mut() {
+                    gene_pool.sim_settings.num_genomes_per_sim = whole_number() as usize;
+                }
+                ChemistryConfigValue::Integer(whole_number())
+            }
+            SweepTarget::NumSimulationTicks => {
+                for gene_pool in config.gene_pools.iter_mut() {
+                    gene_pool.sim_settings.num_simulation_ticks = whole_number();
+                }
+                ChemistryConfigValue::Integer(whole_number())
+            }
+            SweepTarget::CullPercent => {
+                let applied = sweep_number(value) as f32;
+                for gene_pool in config.gene_pools.iter_mut() {
+                    match &mut gene_pool.cull_strategy {
+                        CullStrategy::WorstFirst { percent } => *percent = applied,
+                        _ => panic!(
+                            "cull_percent can only be swept for the WorstFirst cull strategy"
+                        ),
+                    }
+                }
+                ChemistryConfigValue::Float64(applied as f64)
+            }
+            SweepTarget::MaxIterations => {
+                config.settings.max_iterations = whole_number();
+                ChemistryConfigValue::Integer(whole_number())
+            }
+            SweepTarget::EvaluationPointsPerTick => {
+                config.settings.evaluation_points_per_tick = whole_number();
+                ChemistryConfigValue::Integer(whole_number())
+            }
+        }
+    }
+}
+
+/**
+ * Sets a chemistry config value, converting sampled numbers to the type the chemistry already
+ * uses for that key.  Returns the converted value.
+ */
+fn set_chemistry_value(
+    sim_settings: &mut ExperimentSimSettings,
+    key: &str,
+    value: &ChemistryConfigValue,
+) -> ChemistryConfigValue {
+    let chemistry_options = &mut sim_settings.chemistry_options;
+    let existing = chemistry_options
+        .build()
+        .get_configuration()
+        .get(key)
+        .cloned();
+
+    let value = match (existing, value) {
+        (Some(ChemistryConfigValue::ResourceAmount(_)), value) => {
+            ChemistryConfigValue::ResourceAmount(sweep_number(value).round() as ResourceAmount)
+        }
+        (Some(ChemistryConfigValue::Integer(_)), value) => {
+            ChemistryConfigValue::Integer(sweep_number(value).round() as u64)
+        }
+        (Some(ChemistryConfigValue::Float64(_)), value) => {
+            ChemistryConfigValue::Float64(sweep_number(value))
+        }
+        (_, value) => value.clone(),
+    };
+
+    let mut configuration = chemistry_options
+        .chemistry_configuration
+        .clone()
+        .unwrap_or_default();
+    configuration.insert(key.to_string(), value.clone());
+    chemistry_options.chemistry_configuration = Some(configuration);
+    value
+}
+
+fn sweep_number(value: &ChemistryConfigValue) -> f64 {
+    match value {
+        ChemistryConfigValue::ResourceAmount(x) => *x as f64,
+        ChemistryConfigValue::Integer(x) => *x as f64,
+        ChemistryConfigValue::Float64(x) => *x,
+        _ => panic!("Expected a number in the sweep but found a {:?}", value),
+    }
+}
+
+fn display_value(value: &ChemistryConfigValue) -> String {
+    match value {
+        ChemistryConfigValue::ResourceAmount(x) => x.to_string(),
+        ChemistryConfigValue::Integer(x) => x.to_string(),
+        ChemistryConfigValue::Float64(x) => format!("{:.4}", x),
+        ChemistryConfigValue::Boolean(x) => x.to_string(),
+        _ => format!("{:?}", value),
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum SweepValues {
+    List(Vec<ChemistryConfigValue>),
+
+    // a uniformly sampled number, so only usable with random sampling
+    Range { min: f64, max: f64 },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SweepParameter {
+    pub target: SweepTarget,
+    pub values: SweepValues,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum SweepSampling {
+    // every combination of the listed values
+    Cartesian,
+
+    // each run draws every parameter independently
+    Random { num_runs: usize },
+}
+
+pub type SweepAssignment = Vec<ChemistryConfigValue>;
+
+/**
+ * Expands a base experiment config into one experiment per combination of parameter values.
+ * Runs are named after the sweep, ie. name_000, name_001, etc.
+ */
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SweepSpec {
+    pub name_key: String,
+
+    // path of the MultiPoolExperimentConfig that every run starts from
+    pub base_config: String,
+    pub parameters: Vec<SweepParameter>,
+    pub sampling: SweepSampling,
+    pub max_parallel_runs: usize,
+}
+
+impl SweepSpec {
+    pub fn from_ron(s: &str) -> Self {
+        ron::from_str(s).unwrap_or_else(|e| panic!("Invalid sweep spec: {}", e))
+    }
+
+    pub fn load(path: &Path) -> Self {
+        let s = std::fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("Could not read sweep spec {:?}: {}", path, e));
+        Self::from_ron(&s)
+    }
+
+    /**
+     * One assignment per run, with values in the same order as the parameters.
+     */
+    pub fn expand(&self) -> Vec<SweepAssignment> {
+        match &self.sampling {
+            SweepSampling::Cartesian => {
+                let mut assignments: Vec<SweepAssignment> = vec![vec![]];
+                for parameter in self.parameters.iter() {
+                    let values = match &parameter.values {
+                        SweepValues::List(values) => values,
+                        SweepValues::Range { .. } => panic!(
+                            "{} is a range, which can only be swept with random sampling",
+                            parameter.target.name()
+                        ),
+                    };
+
+                    assignments = assignments
+                        .iter()
+                        .flat_map(|assignment| {
+                            values.iter().map(move |value| {
+                                let mut assignment = assignment.clone();
+                                assignment.push(value.clone());
+                                assignment
+                            })
+                        })
+                        .collect();
+                }
+                assignments
+            }
+            SweepSampling::Random { num_runs } => {
+                let mut rng = rand::thread_rng();
+                (0..*num_runs)
+                    .map(|_| {
+                        self.parameters
+                            .iter()
+                            .map(|parameter| match &parameter.values {
+                                SweepValues::List(values) => {
+                                    values[rng.gen_range(0..values.len())].clone()
+                                }
+                                SweepValues::Range { min, max } => {
+                                    ChemistryConfigValue::Float64(rng.gen_range(*min..=*max))
+                                }
+                            })
+                            .collect()
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    pub fn run_key(&self, run_idx: usize) -> String {
+        format!("{}_{:03}", self.name_key, run_idx)
+    }
+
+    /**
+     * The base config with the assignment applied, logging under the run's own key.  Also
+     * returns the assignment the way it was applied.
+     */
+    pub fn configure_run(
+        &self,
+        base: &MultiPoolExperimentConfig,
+        run_idx: usize,
+        assignment: &SweepAssignment,
+    ) -> (MultiPoolExperimentConfig, SweepAssignment) {
+        let mut config = base.clone();
+        let applied = self
+            .parameters
+            .iter()
+            .zip(assignment.iter())
+            .map(|(parameter, value)| parameter.target.apply(&mut config, value))
+            .collect::<Vec<_>>();
+
+        // the summary is read from the logs, so every run has to log
+        config
+            .settings
+            .logging_settings
+            .get_or_insert_with(MultiPoolExperimentLoggingSettings::default);
+        (config.with_experiment_key(&self.run_key(run_idx)), applied)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunFitness {
+    // the highest max fitness of any gene pool on the last logged tick
+    pub final_fitness: Option<FitnessScore>,
+
+    // the highest max fitness of any gene pool on any logged tick
+    pub best_fitness: Option<FitnessScore>,
+}
+
+/**
+ * Reads the rows of a gene pool's fitness.csv, which are the tick followed by the 0th, 25th,
+ * 75th and 100th percentile of max fitness.
+ */
+pub fn parse_fitness_csv(contents: &str) -> Vec<(u64, Vec<FitnessScore>)> {
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let mut cols = line.trim().split(",").filter(|col| !col.is_empty());
+            let tick = cols
+                .next()
+                .unwrap()
+                .parse::<u64>()
+                .expect(&format!("Invalid tick in fitness row: {}", line));
+            let percentiles = cols
+                .map(|col| {
+                    col.parse::<FitnessScore>()
+                        .expect(&format!("Invalid fitness in fitness row: {}", line))
+                })
+                .collect();
+            (tick, percentiles)
+        })
+        .collect()
+}
+
+pub fn run_fitness_from_logs(pool_logs: &[Vec<(u64, Vec<FitnessScore>)>]) -> RunFitness {
+    let max_of = |row: &(u64, Vec<FitnessScore>)| row.1.last().cloned();
+
+    RunFitness {
+        final_fitness: pool_logs
+            .iter()
+            .filter_map(|rows| rows.last().and_then(max_of))
+            .max(),
+        best_fitness: pool_logs
+            .iter()
+            .flat_map(|rows| rows.iter().filter_map(max_of))
+            .max(),
+    }
+}
+
+pub fn read_run_fitness(experiment_key: &str, num_gene_pools: usize) -> RunFitness {
+    let logger = MultiPoolExperimentLogger {
+        settings: MultiPoolExperimentLoggingSettings {
+            experiment_key: experiment_key.to_string(),
+            ..MultiPoolExperimentLoggingSettings::default()
+        },
+    };
+
+    let pool_logs = (0..num_gene_pools)
+        .map(|gene_pool_id| {
+            let mut path = logger.get_gene_pool_log_dir(gene_pool_id);
+            path.push("fitness.csv");
+            std::fs::read_to_string(path)
+                .map(|contents| parse_fitness_csv(&contents))
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    run_fitness_from_logs(&pool_logs)
+}
+
+#[derive(Clone, Debug)]
+pub struct SweepRunResult {
+    pub experiment_key: String,
+
+    // the values as they were applied to the run's config
+    pub assignment: SweepAssignment,
+    pub fitness: RunFitness,
+}
+
+/**
+ * Runs every experiment of the sweep, at most max_parallel_runs at a time, and then reads each
+ * run's fitness back from its logs.  A run that panics shows up without fitness.
+ */
+pub fn run_sweep(spec: &SweepSpec) -> Vec<SweepRunResult> {
+    let base = MultiPoolExperimentConfig::load(Path::new(&spec.base_config));
+    let num_gene_pools = base.gene_pools.len();
+    let assignments = spec.expand();
+
+    println!(
+        "Sweep {}: {} runs, {} at a time",
+        &spec.name_key,
+        assignments.len(),
+        spec.max_parallel_runs
+    );
+
+    // experiments hold Rc's, so each one is built from its config on the thread that runs it
+    let pool = ThreadPool::new(spec.max_parallel_runs.max(1));
+    let mut applied_assignments = vec![];
+    for (run_idx, assignment) in assignments.iter().enumerate() {
+        let (config, applied) = spec.configure_run(&base, run_idx, assignment);
+        applied_assignments.push(applied);
+
+        let config_ron = config.to_ron();
+        pool.execute(move || {
+            MultiPoolExperimentConfig::from_ron(&config_ron)
+                .to_experiment()
+                .start();
+        });
+    }
+    pool.join();
+
+    if pool.panic_count() > 0 {
+        println!("{} sweep runs panicked", pool.panic_count());
+    }
+
+    applied_assignments
+        .into_iter()
+        .enumerate()
+        .map(|(run_idx, assignment)| {
+            let experiment_key = spec.run_key(run_idx);
+            SweepRunResult {
+                fitness: read_run_fitness(&experiment_key, num_gene_pools),
+                experiment_key,
+                assignment,
+            }
+        })
+        .collect()
+}
+
+fn summary_rows(spec: &SweepSpec, results: &[SweepRunResult]) -> Vec<Vec<String>> {
+    let mut header = vec!["experiment".to_string()];
+    header.extend(spec.parameters.iter().map(|p| p.target.name()));
+    header.push("final_fitness".to_string());
+    header.push("best_fitness".to_string());
+
+    let mut rows = vec![header];
+    for result in results {
+        let mut row = vec![result.experiment_key.clone()];
+        row.extend(result.assignment.iter().map(display_value));
+        for fitness in [result.fitness.final_fitness, result.fitness.best_fitness] {
+            row.push(fitness.map(|f| f.to_string()).unwrap_or_default());
+        }
+        rows.push(row);
+    }
+
+    rows
+}
+
+pub fn sweep_summary_csv(spec: &SweepSpec, results: &[SweepRunResult]) -> String {
+    summary_rows(spec, results)
+        .iter()
+        .map(|row| format!("{}\n", row.join(",")))
+        .collect()
+}
+
+/**
+ * The summary as an aligned table, with the best runs first.
+ */
+pub fn sweep_summary_table(spec: &SweepSpec, results: &[SweepRunResult]) -> String {
+    let mut results = results.to_vec();
+    results.sort_by_key(|result| std::cmp::Reverse(result.fitness.best_fitness));
+
+    let rows = summary_rows(spec, &results);
+    let widths = (0..rows[0].len())
+        .map(|col| rows.iter().map(|row| row[col].len()).max().unwrap())
+        .collect::<Vec<_>>();
+
+    rows.iter()
+        .map(|row| {
+            let cols = row
+                .iter()
+                .zip(widths.iter())
+                .map(|(col, width)| format!("{:<w$}", col, w = width))
+                .collect::<Vec<_>>();
+            format!("{}\n", cols.join("  ").trim_end())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::scenarios::experiments::cheese::multi::vary_chemistry_config;
+
+    fn spec(sampling: SweepSampling) -> SweepSpec {
+        SweepSpec {
+            name_key: "sweep".to_string(),
+            base_config: "configs/experiments/cheese_multi_pool.ron".to_string(),
+            parameters: vec![
+                SweepParameter {
+                    target: SweepTarget::Chemistry("move_cost".to_string()),
+                    values: SweepValues::List(vec![
+                        ChemistryConfigValue::ResourceAmount(-1),
+                        ChemistryConfigValue::ResourceAmount(-5),
+                    ]),
+                },
+                SweepParameter {
+                    target: SweepTarget::NumGenomes,
+                    values: SweepValues::List(vec![
+                        ChemistryConfigValue::Integer(10),
+                        ChemistryConfigValue::Integer(20),
+                        ChemistryConfigValue::Integer(30),
+                    ]),
+                },
+            ],
+            sampling,
+            max_parallel_runs: 2,
+        }
+    }
+
+    #[test]
+    fn expand() {
+        let assignments = spec(SweepSampling::Cartesian).expand();
+        assert_eq!(assignments.len(), 6);
+        assert_eq!(
+            display_value(&assignments[5][0]) + " " + &display_value(&assignments[5][1]),
+            "-5 30"
+        );
+
+        let mut spec = spec(SweepSampling::Random { num_runs: 4 });
+        spec.parameters[1].values = SweepValues::Range {
+            min: 0.25,
+            max: 0.5,
+        };
+        let assignments = spec.expand();
+        assert_eq!(assignments.len(), 4);
+        for assignment in assignments {
+            let x = sweep_number(&assignment[1]);
+            assert!(x >= 0.25 && x <= 0.5);
+        }
+    }
+
+    #[test]
+    fn example_spec() {
+        let spec = SweepSpec::load(Path::new("configs/sweeps/cheese_costs.ron"));
+        assert_eq!(spec.expand().len(), 6);
+    }
+
+    #[test]
+    fn configure_run() {
+        let spec = spec(SweepSampling::Cartesian);
+        let base = vary_chemistry_config("base");
+        let (config, applied) = spec.configure_run(
+            &base,
+            7,
+            &vec![
+                ChemistryConfigValue::Float64(-4.6),
+                ChemistryConfigValue::Float64(12.3),
+            ],
+        );
+
+        // the summary shows the values the run actually used
+        assert_eq!(
+            applied.iter().map(display_value).collect::<Vec<_>>(),
+            vec!["-5", "12"]
+        );
+        assert_eq!(applied[0].unwrap_resource_amount(), -5);
+
+        assert_eq!(config.settings.experiment_key, "sweep_007");
+        for gene_pool in config.gene_pools.iter() {
+            assert_eq!(gene_pool.num_genomes, 12);
+
+            let chemistry_config = gene_pool
+                .sim_settings
+                .chemistry_options
+                .chemistry_configuration
+                .clone()
+                .unwrap();
+            assert_eq!(chemistry_config["move_cost"].unwrap_resource_amount(), -5);
+        }
+    }
+
+    #[test]
+    fn summary() {
+        let pool_logs = vec![
+            parse_fitness_csv("0,1,2,3,40\n10,1,2,3,20\n"),
+            parse_fitness_csv("0,1,2,3,4\n10,1,2,3,30\n"),
+            vec![],
+        ];
+        let fitness = run_fitness_from_logs(&pool_logs);
+        assert_eq!(fitness.final_fitness, Some(30));
+        assert_eq!(fitness.best_fitness, Some(40));
+
+        let spec = spec(SweepSampling::Cartesian);
+        let results = vec![
+            SweepRunResult {
+                experiment_key: spec.run_key(0),
+                assignment: vec![
+                    ChemistryConfigValue::ResourceAmount(-1),
+                    ChemistryConfigValue::Integer(10),
+                ],
+                fitness: RunFitness::default(),
+            },
+            SweepRunResult {
+                experiment_key: spec.run_key(1),
+                assignment: vec![
+                    ChemistryConfigValue::ResourceAmount(-5),
+                    ChemistryConfigValue::Integer(10),
+                ],
+                fitness,
+            },
+        ];
+
+        assert_eq!(
+            sweep_summary_csv(&spec, &results),
+            "experiment,move_cost,num_genomes,final_fitness,best_fitness\n\
+             sweep_000,-1,10,,\n\
+             sweep_001,-5,10,30,40\n"
+        );
+
+        let table = sweep_summary_table(&spec, &results);
+        assert!(table.lines().nth(1).unwrap().starts_with("sweep_001"));
+    }
+}
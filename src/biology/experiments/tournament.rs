@@ -0,0 +1,463 @@
+use crate::biology::genome::framed::{
+    annotated::FramedGenomeExecutionStats, common::CompiledFramedGenome,
+};
+use crate::simulation::fitness::FitnessScore;
+
+use super::fitness::{
+    calculate_new_fitness_ranks, normalize_ranks, ExperimentFitnessRank,
+    FitnessRankAdjustmentMethod,
+};
+use super::sim_runner::{execute_sim_runners_with_settings, SimRunnerGenomeEntry};
+use super::types::{ExperimentSimSettings, TrialResultItem};
+use super::variants::multi_pool::reference::FitnessSummary;
+
+pub const INITIAL_ELO: f64 = 1500.0;
+pub const ELO_K_FACTOR: f64 = 32.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TournamentFormat {
+    // every contestant plays every other contestant
+    RoundRobin,
+
+    // contestants with similar records are paired each round
+    Swiss { num_rounds: usize },
+}
+
+#[derive(Clone)]
+pub struct TournamentSettings {
+    pub format: TournamentFormat,
+
+    // how many simulations each pairing plays.  every match is seeded from the tournament's
+    // seed, so trials are different but reproducible worlds
+    pub num_trials: usize,
+    pub seed: u64,
+    pub sim_settings: ExperimentSimSettings,
+    pub fitness_calculation_key: String,
+    pub use_threads: bool,
+}
+
+#[derive(Clone)]
+pub struct TournamentContestant {
+    pub name: String,
+    pub genome: CompiledFramedGenome,
+}
+
+#[derive(Clone, Debug)]
+pub struct ContestantStanding {
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    pub fitness_scores: Vec<FitnessScore>,
+    pub elo: f64,
+
+    // the same rank experiments keep for their genomes, see calculate_new_fitness_ranks
+    pub rank: ExperimentFitnessRank,
+    pub opponents: Vec<usize>,
+
+    // rounds of a swiss tournament sat out
+    pub byes: usize,
+}
+
+impl Default for ContestantStanding {
+    fn default() -> Self {
+        Self {
+            wins: 0,
+            draws: 0,
+            losses: 0,
+            fitness_scores: vec![],
+            elo: INITIAL_ELO,
+            rank: 0,
+            opponents: vec![],
+            byes: 0,
+        }
+    }
+}
+
+impl ContestantStanding {
+    pub fn games(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+
+    pub fn points(&self) -> f64 {
+        self.wins as f64 + self.draws as f64 * 0.5
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        if self.games() == 0 {
+            0.0
+        } else {
+            self.points() / self.games() as f64
+        }
+    }
+
+    pub fn fitness(&self) -> FitnessSummary {
+        FitnessSummary::from_scores(&self.fitness_scores)
+    }
+}
+
+/**
+ * The score a player rated a is expected to get against one rated b, between 0 and 1.
+ */
+pub fn elo_expected_score(a: f64, b: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((b - a) / 400.0))
+}
+
+/**
+ * Returns the new ratings of a and b, given a's actual score of 1 for a win, 0.5 for a draw
+ * and 0 for a loss.
+ */
+pub fn update_elo(a: f64, b: f64, a_score: f64) -> (f64, f64) {
+    let a_expected = elo_expected_score(a, b);
+    let delta = ELO_K_FACTOR * (a_score - a_expected);
+    (a + delta, b - delta)
+}
+
+/**
+ * Plays genomes against each other, two per simulation, with the higher fitness winning.
+ */
+pub struct Tournament {
+    pub contestants: Vec<TournamentContestant>,
+    pub settings: TournamentSettings,
+    pub standings: Vec<ContestantStanding>,
+
+    // the number of matches played so far, each of which gets its own seed
+    pub num_matches: u64,
+}
+
+impl Tournament {
+    pub fn new(contestants: Vec<TournamentContestant>, settings: TournamentSettings) -> Self {
+        if contestants.len() < 2 {
+            panic!("A tournament needs at least two contestants");
+        }
+
+        Self {
+            standings: vec![ContestantStanding::default(); contestants.len()],
+            contestants,
+            settings,
+            num_matches: 0,
+        }
+    }
+
+    pub fn run(&mut self) {
+        match self.settings.format.clone() {
+            TournamentFormat::RoundRobin => {
+                let mut pairs = vec![];
+                for a in 0..self.contestants.len() {
+                    for b in (a + 1)..self.contestants.len() {
+                        pairs.push((a, b));
+                    }
+                }
+                self.play_round(&pairs);
+            }
+            TournamentFormat::Swiss { num_rounds } => {
+                for _ in 0..num_rounds {
+                    let pairs = self.swiss_pairings();
+                    self.play_round(&pairs);
+                }
+            }
+        }
+    }
+
+    pub fn play_round(&mut self, pairs: &[(usize, usize)]) {
+        let mut groups = vec![];
+        for (a, b) in pairs.iter() {
+            for _ in 0..self.settings.num_trials {
+                let seed = self.settings.seed.wrapping_add(self.num_matches);
+                self.num_matches += 1;
+
+                groups.push((
+                    vec![self.runner_entry(*a), self.runner_entry(*b)],
+                    self.settings.sim_settings.seeded(seed),
+                ));
+            }
+        }
+
+        let results = execute_sim_runners_with_settings(
+            groups,
+            self.settings.use_threads,
+            &self.settings.fitness_calculation_key,
+        );
+
+        for result in results.iter() {
+            self.record_match(result);
+        }
+    }
+
+    fn runner_entry(&self, contestant_idx: usize) -> SimRunnerGenomeEntry {
+        let genome = &self.contestants[contestant_idx].genome;
+        SimRunnerGenomeEntry {
+            gene_pool_id: 0,
+            genome_idx: contestant_idx,
+            genome_uid: contestant_idx,
+            genome: genome.clone(),
+            execution_stats: FramedGenomeExecutionStats::new(&genome.frames),
+            side: None,
+        }
+    }
+
+    pub fn record_match(&mut self, results: &Vec<TrialResultItem>) {
+        assert_eq!(results.len(), 2);
+        let (a, b) = (&results[0], &results[1]);
+        let (a_idx, b_idx) = (a.experiment_genome_uid, b.experiment_genome_uid);
+
+        let a_score = if a.fitness_score > b.fitness_score {
+            self.standings[a_idx].wins += 1;
+            self.standings[b_idx].losses += 1;
+            1.0
+        } else if a.fitness_score < b.fitness_score {
+            self.standings[a_idx].losses += 1;
+            self.standings[b_idx].wins += 1;
+            0.0
+        } else {
+            self.standings[a_idx].draws += 1;
+            self.standings[b_idx].draws += 1;
+            0.5
+        };
+
+        let (a_elo, b_elo) = update_elo(
+            self.standings[a_idx].elo,
+            self.standings[b_idx].elo,
+            a_score,
+        );
+        self.standings[a_idx].elo = a_elo;
+        self.standings[b_idx].elo = b_elo;
+
+        for (idx, opponent, result) in [(a_idx, b_idx, a), (b_idx, a_idx, b)] {
+            self.standings[idx]
+                .fitness_scores
+                .push(result.fitness_score);
+            self.standings[idx].opponents.push(opponent);
+        }
+
+        let new_ranks = calculate_new_fitness_ranks(
+            &results
+                .iter()
+                .map(|r| (r.clone(), self.standings[r.experiment_genome_uid].rank))
+                .collect::<Vec<_>>(),
+            &FitnessRankAdjustmentMethod::Absolute,
+        );
+        for (result, rank) in new_ranks {
+            self.standings[result.experiment_genome_uid].rank = rank;
+        }
+
+        let mut ranks = self
+            .standings
+            .iter()
+            .enumerate()
+            .map(|(i, standing)| (i, standing.rank))
+            .collect::<Vec<_>>();
+        normalize_ranks(&mut ranks);
+        for (i, rank) in ranks {
+            self.standings[i].rank = rank;
+        }
+    }
+
+    /**
+     * Pairs contestants with their closest neighbor by points, then rating, that they haven't
+     * played yet.  Rematches only happen when nobody else is left.  With an odd number of
+     * contestants the lowest placed of those with the fewest byes sits the round out.
+     */
+    pub fn swiss_pairings(&mut self) -> Vec<(usize, usize)> {
+        let mut order = (0..self.contestants.len()).collect::<Vec<_>>();
+        order.sort_by(|a, b| {
+            let (a, b) = (&self.standings[*a], &self.standings[*b]);
+            b.points()
+                .partial_cmp(&a.points())
+                .unwrap()
+                .then(b.elo.partial_cmp(&a.elo).unwrap())
+        });
+
+        if order.len() % 2 == 1 {
+            let fewest_byes = order.iter().map(|i| self.standings[*i].byes).min().unwrap();
+            let bye_pos = order
+                .iter()
+                .rposition(|i| self.standings[*i].byes == fewest_byes)
+                .unwrap();
+            let bye = order.remove(bye_pos);
+            self.standings[bye].byes += 1;
+        }
+
+        let mut pairs = vec![];
+        while order.len() > 1 {
+            let a = order.remove(0);
+            let opponent_pos = order
+                .iter()
+                .position(|b| !self.standings[a].opponents.contains(b))
+                .unwrap_or(0);
+            let b = order.remove(opponent_pos);
+            pairs.push((a, b));
+        }
+
+        pairs
+    }
+
+    /**
+     * A table of the contestants ordered by rating.  Fitness is shown as the mean with its 95%
+     * confidence interval.
+     */
+    pub fn report(&self) -> String {
+        let mut order = (0..self.contestants.len()).collect::<Vec<_>>();
+        order.sort_by(|a, b| {
+            self.standings[*b]
+                .elo
+                .partial_cmp(&self.standings[*a].elo)
+                .unwrap()
+        });
+
+        let name_width = self
+            .contestants
+            .iter()
+            .map(|c| c.name.len())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+
+        let mut s = format!(
+            "{:<w$}  {:>6}  {:>5}  {:>5}  {:>6}  {:>8}  {:>20}  {:>7}  {:>4}\n",
+            "name",
+            "games",
+            "wins",
+            "draws",
+            "losses",
+            "win rate",
+            "fitness",
+            "elo",
+            "rank",
+            w = name_width
+        );
+
+        for idx in order {
+            let standing = &self.standings[idx];
+            let fitness = standing.fitness();
+            s.push_str(&format!(
+                "{:<w$}  {:>6}  {:>5}  {:>5}  {:>6}  {:>7.1}%  {:>20}  {:>7.1}  {:>4}\n",
+                self.contestants[idx].name,
+                standing.games(),
+                standing.wins,
+                standing.draws,
+                standing.losses,
+                standing.win_rate() * 100.0,
+                format!(
+                    "{:.1} ± {:.1}",
+                    fitness.mean,
+                    fitness.confidence_interval_95()
+                ),
+                standing.elo,
+                standing.rank,
+                w = name_width
+            ));
+        }
+
+        s
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::biology::experiments::util::random_genome_of_length;
+    use crate::biology::genome::framed::builders::FramedGenomeCompiler;
+    use crate::scenarios::experiments::cheese::multi::base_sim_settings;
+    use crate::simulation::common::GeneticManifest;
+
+    #[test]
+    fn elo() {
+        assert_eq!(elo_expected_score(1500.0, 1500.0), 0.5);
+        assert_eq!(update_elo(1500.0, 1500.0, 1.0), (1516.0, 1484.0));
+        assert_eq!(update_elo(1500.0, 1500.0, 0.5), (1500.0, 1500.0));
+
+        let (a, b) = update_elo(1700.0, 1300.0, 1.0);
+        assert!(a > 1700.0 && a < 1705.0);
+        assert!((a + b - 3000.0).abs() < 1e-9);
+    }
+
+    fn tournament(format: TournamentFormat, num_contestants: usize) -> Tournament {
+        let sim_settings = base_sim_settings().num_simulation_ticks(3).build();
+        let gm = GeneticManifest::from_chemistry(&sim_settings.chemistry_options.build());
+
+        let contestants = (0..num_contestants)
+            .map(|i| TournamentContestant {
+                name: format!("genome {}", i),
+                genome: FramedGenomeCompiler::compile(random_genome_of_length(30), &gm),
+            })
+            .collect::<Vec<_>>();
+
+        Tournament::new(
+            contestants,
+            TournamentSettings {
+                format,
+                num_trials: 2,
+                seed: 0,
+                sim_settings,
+                fitness_calculation_key: "total_cheese_acquired".to_string(),
+                use_threads: false,
+            },
+        )
+    }
+
+    #[test]
+    fn round_robin() {
+        let mut tournament = tournament(TournamentFormat::RoundRobin, 3);
+        tournament.run();
+
+        // each contestant plays 2 opponents, 2 trials each
+        for standing in tournament.standings.iter() {
+            assert_eq!(standing.games(), 4);
+            assert_eq!(standing.fitness_scores.len(), 4);
+        }
+
+        let total_elo = tournament.standings.iter().map(|s| s.elo).sum::<f64>();
+        assert!((total_elo - 3.0 * INITIAL_ELO).abs() < 1e-6);
+        assert_eq!(tournament.report().lines().count(), 4);
+
+        // the header and the rows line up
+        let report = tournament.report();
+        let header_width = report.lines().next().unwrap().chars().count();
+        assert!(report
+            .lines()
+            .all(|line| line.chars().count() == header_width));
+    }
+
+    #[test]
+    fn swiss_pairings_avoid_rematches() {
+        let mut tournament = tournament(TournamentFormat::Swiss { num_rounds: 2 }, 5);
+        let pairs = tournament.swiss_pairings();
+        assert_eq!(pairs.len(), 2);
+
+        tournament.play_round(&pairs);
+        for (a, b) in tournament.swiss_pairings() {
+            assert!(!pairs.contains(&(a, b)) && !pairs.contains(&(b, a)));
+        }
+    }
+
+    #[test]
+    fn swiss_byes_rotate() {
+        let mut tournament = tournament(TournamentFormat::Swiss { num_rounds: 3 }, 3);
+        tournament.run();
+
+        for standing in tournament.standings.iter() {
+            assert_eq!(standing.byes, 1);
+            assert_eq!(standing.games(), 4);
+        }
+    }
+
+    #[test]
+    fn seeded_matches() {
+        let mut seeded = tournament(TournamentFormat::RoundRobin, 3);
+        seeded.settings.seed = 7;
+
+        // the same contestants each time, the helper draws new random genomes
+        let play = || {
+            let mut tournament =
+                Tournament::new(seeded.contestants.clone(), seeded.settings.clone());
+            tournament.run();
+            tournament
+                .standings
+                .iter()
+                .map(|standing| standing.fitness_scores.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(play(), play());
+    }
+}
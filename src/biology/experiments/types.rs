@@ -1,6 +1,7 @@
 use std::{
     fmt::{Debug, Formatter, Result},
     rc::Rc,
+    sync::Arc,
 };
 
 use serde::{Deserialize, Serialize};
@@ -9,6 +10,7 @@ use crate::{
     biology::genome::framed::{
         annotated::FramedGenomeExecutionStats, common::CompiledFramedGenome,
     },
+    chemistry::ChemistryConfigBuilder,
     simulation::{
         common::{builder::ChemistryBuilder, helpers::place_units::PlaceUnitsMethod, UnitEntryId},
         fitness::FitnessScore,
@@ -16,6 +18,7 @@ use crate::{
     },
 };
 
+use super::lineage::GenomeLineage;
 use super::variants::multi_pool::gene_pool::GenePoolId;
 
 /**
@@ -35,9 +38,29 @@ pub enum CullStrategy {
     RandomTiers { percent_per_tercile: [f32; 3] },
 }
 
-#[derive(Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub enum SeedGenomeSettings {
-    Random { min_size: usize, max_size: usize },
+    Random {
+        min_size: usize,
+        max_size: usize,
+    },
+
+    // genome files logged by previous experiments, as (experiment, file name) pairs.  csv files
+    // need the .ron genome file logged next to them.  when the pool needs more genomes than the
+    // files have, they are repeated
+    FromFiles {
+        files: Vec<(String, String)>,
+    },
+
+    // functions in genome::framed::samples by name, eg. "cheese::get_genome2"
+    FromSamples {
+        samples: Vec<String>,
+    },
+
+    // each part seeds a share of the pool in proportion to its ratio
+    Mixed {
+        parts: Vec<(SeedGenomeSettings, f32)>,
+    },
 }
 
 #[derive(Clone)]
@@ -47,8 +70,9 @@ pub struct GenomeExperimentEntry {
     pub num_evaluations: usize,
     pub uid: ExperimentGenomeUid,
     pub current_rank_score: usize,
-    pub compiled_genome: Rc<CompiledFramedGenome>,
+    pub compiled_genome: Arc<CompiledFramedGenome>,
     pub previous_execution_stats: FramedGenomeExecutionStats,
+    pub lineage: GenomeLineage,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -62,6 +86,28 @@ pub struct ExperimentSimSettings {
     pub chemistry_options: ChemistryBuilder,
 }
 
+impl ExperimentSimSettings {
+    /**
+     * The same settings with the chemistry's update and world seeds set, so that the
+     * simulation plays out the same way every time.
+     */
+    pub fn seeded(&self, seed: u64) -> Self {
+        let config = ChemistryConfigBuilder::from_config(
+            self.chemistry_options
+                .chemistry_configuration
+                .clone()
+                .unwrap_or_default(),
+        )
+        .set_update_seed(seed)
+        .set_world_seed(seed)
+        .build();
+
+        let mut settings = self.clone();
+        settings.chemistry_options = settings.chemistry_options.config(config);
+        settings
+    }
+}
+
 #[derive(Clone)]
 pub struct TrialResultItem {
     pub sim_unit_entry_id: UnitEntryId,
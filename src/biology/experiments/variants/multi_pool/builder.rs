@@ -1,6 +1,9 @@
 use crate::biology::experiments::types::ExperimentSimSettings;
 
-use super::{logger::MultiPoolExperimentLoggingSettings, types::MultiPoolExperimentSettings};
+use super::{
+    coevolution::CoevolutionSettings, logger::MultiPoolExperimentLoggingSettings,
+    reference::ReferenceEvaluationSettings, types::MultiPoolExperimentSettings,
+};
 
 // #[derive(Builder)]
 // #[builder(pattern = "owned", setter(strip_option))]
@@ -24,6 +27,8 @@ pub struct MultiPoolExperimentSettingsBuilderTemplate {
 
     pub reference_sim_settings: ExperimentSimSettings,
     pub reference_fitness_calculation_key: String,
+    pub reference_evaluation_settings: ReferenceEvaluationSettings,
+    pub coevolution: CoevolutionSettings,
 }
 
 impl MultiPoolExperimentSettingsBuilder {
@@ -39,6 +44,8 @@ impl MultiPoolExperimentSettingsBuilder {
                 .reference_fitness_calculation_key
                 .unwrap()
                 .clone(),
+            reference_evaluation_settings: self.reference_evaluation_settings.unwrap_or_default(),
+            coevolution: self.coevolution,
         }
     }
 }
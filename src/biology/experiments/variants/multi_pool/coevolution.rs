@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::biology::experiments::{
+    fitness::{adjust_winners_rank, ExperimentFitnessRank, FitnessRankAdjustmentMethod},
+    sim_runner::SimRunnerGenomeEntry,
+    types::{ExperimentGenomeUid, TrialResultItem},
+};
+
+use super::gene_pool::GenePoolId;
+
+/**
+ * Pools that are evaluated against each other instead of on their own, eg. a pool of predators
+ * and a pool of prey.  Every simulation of a pair holds one group of genomes from each side.
+ * Each side keeps its own fitness calculation and default unit attributes, and a genome's rank
+ * moves against the opposing genomes it shared a simulation with (see rank_against_opponents).
+ *
+ * The world of a pair's simulations comes from the sim settings of the first pool.
+ */
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CoevolutionSettings {
+    pub opposing_pools: Vec<(GenePoolId, GenePoolId)>,
+}
+
+impl CoevolutionSettings {
+    pub fn is_coevolving(&self, gene_pool_id: GenePoolId) -> bool {
+        self.opposing_pools
+            .iter()
+            .any(|(a, b)| *a == gene_pool_id || *b == gene_pool_id)
+    }
+
+    pub fn are_opponents(&self, a: GenePoolId, b: GenePoolId) -> bool {
+        self.opposing_pools
+            .iter()
+            .any(|pair| *pair == (a, b) || *pair == (b, a))
+    }
+}
+
+/**
+ * One simulation per group of the side with more groups.  The other side's groups are reused in
+ * order, so every genome plays at least once.
+ */
+pub fn pair_opposing_groups(
+    a: &[Vec<SimRunnerGenomeEntry>],
+    b: &[Vec<SimRunnerGenomeEntry>],
+) -> Vec<Vec<SimRunnerGenomeEntry>> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+
+    (0..a.len().max(b.len()))
+        .map(|i| {
+            a[i % a.len()]
+                .iter()
+                .chain(b[i % b.len()].iter())
+                .cloned()
+                .collect()
+        })
+        .collect()
+}
+
+/**
+ * The results of one simulation that belong to the given pool.
+ */
+pub fn results_for_pool(
+    results: &[TrialResultItem],
+    gene_pool_id: GenePoolId,
+) -> Vec<TrialResultItem> {
+    results
+        .iter()
+        .filter(|result| result.gene_pool_id == gene_pool_id)
+        .cloned()
+        .collect()
+}
+
+pub type CoevolutionRanks = HashMap<(GenePoolId, ExperimentGenomeUid), ExperimentFitnessRank>;
+
+/**
+ * Plays every genome against each opposing genome it shared a simulation with.  The two sides'
+ * fitness calculations measure different things, so a match is won by the result that placed
+ * higher among its own pool's results of the round.  The winner's rank then moves against the
+ * loser's rank with the winner pool's adjustment method, and ties change nothing.
+ */
+pub fn rank_against_opponents(
+    results: &[Vec<TrialResultItem>],
+    ranks: &mut CoevolutionRanks,
+    methods: &HashMap<GenePoolId, FitnessRankAdjustmentMethod>,
+) {
+    let percentiles = fitness_percentiles(results);
+
+    for (sim_results, sim_percentiles) in results.iter().zip(percentiles.iter()) {
+        for i in 0..sim_results.len() {
+            for j in (i + 1)..sim_results.len() {
+                if sim_results[i].gene_pool_id == sim_results[j].gene_pool_id
+                    || sim_percentiles[i] == sim_percentiles[j]
+                {
+                    continue;
+                }
+
+                let (winner, loser) = if sim_percentiles[i] > sim_percentiles[j] {
+                    (&sim_results[i], &sim_results[j])
+                } else {
+                    (&sim_results[j], &sim_results[i])
+                };
+                let winner_key = (winner.gene_pool_id, winner.experiment_genome_uid);
+                let loser_key = (loser.gene_pool_id, loser.experiment_genome_uid);
+
+                let new_rank = adjust_winners_rank(
+                    ranks[&winner_key],
+                    ranks[&loser_key],
+                    &methods[&winner.gene_pool_id],
+                );
+                ranks.insert(winner_key, new_rank);
+            }
+        }
+    }
+}
+
+/**
+ * Where each result falls among the results of its own pool across all the simulations of a
+ * round, from 0 for the lowest fitness to 1 for the highest.
+ */
+fn fitness_percentiles(results: &[Vec<TrialResultItem>]) -> Vec<Vec<f32>> {
+    let mut scores_by_pool = HashMap::new();
+    for result in results.iter().flatten() {
+        scores_by_pool
+            .entry(result.gene_pool_id)
+            .or_insert_with(Vec::new)
+            .push(result.fitness_score);
+    }
+
+    results
+        .iter()
+        .map(|sim_results| {
+            sim_results
+                .iter()
+                .map(|result| {
+                    let scores = &scores_by_pool[&result.gene_pool_id];
+                    if scores.len() == 1 {
+                        return 0.5;
+                    }
+
+                    let lower = scores
+                        .iter()
+                        .filter(|score| **score < result.fitness_score)
+                        .count();
+                    let equal = scores
+                        .iter()
+                        .filter(|score| **score == result.fitness_score)
+                        .count();
+                    (lower as f32 + (equal - 1) as f32 / 2.0) / (scores.len() - 1) as f32
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::biology::genome::framed::annotated::FramedGenomeExecutionStats;
+    use crate::scenarios::experiments::predator_prey::coevolution_config;
+
+    #[test]
+    fn opposing_pools() {
+        let settings = CoevolutionSettings {
+            opposing_pools: vec![(0, 1)],
+        };
+        assert!(settings.is_coevolving(1));
+        assert!(!settings.is_coevolving(2));
+        assert!(settings.are_opponents(1, 0));
+        assert!(!settings.are_opponents(0, 2));
+    }
+
+    fn result(gene_pool_id: GenePoolId, uid: ExperimentGenomeUid, fitness: u64) -> TrialResultItem {
+        TrialResultItem {
+            gene_pool_id,
+            genome_idx: uid,
+            sim_unit_entry_id: 0,
+            experiment_genome_uid: uid,
+            fitness_score: fitness,
+            stats: FramedGenomeExecutionStats::empty(),
+        }
+    }
+
+    #[test]
+    fn ranking_against_opponents() {
+        // pool 0 genome 1 beats the strong pool 1 genome 0 despite a lower fitness score,
+        // because it placed higher within its own pool than genome 0 did within pool 1
+        let results = vec![
+            vec![result(0, 0, 5), result(1, 0, 900)],
+            vec![result(0, 1, 10), result(1, 0, 100)],
+            vec![result(0, 2, 1), result(1, 1, 500)],
+        ];
+        let mut ranks = CoevolutionRanks::new();
+        ranks.insert((0, 0), 0);
+        ranks.insert((0, 1), 0);
+        ranks.insert((0, 2), 0);
+        ranks.insert((1, 0), 3);
+        ranks.insert((1, 1), 1);
+
+        let methods = HashMap::from([
+            (0, FitnessRankAdjustmentMethod::Absolute),
+            (1, FitnessRankAdjustmentMethod::Absolute),
+        ]);
+        rank_against_opponents(&results, &mut ranks, &methods);
+
+        // 0.5 against 1.0: genome 0 of pool 1 wins, but it was already ranked higher
+        assert_eq!(ranks[&(1, 0)], 3);
+        assert_eq!(ranks[&(0, 0)], 0);
+        // 1.0 against 0.0: an upset over the rank 3 genome
+        assert_eq!(ranks[&(0, 1)], 4);
+        // 0.0 against 0.5: genome 1 of pool 1 wins, already ranked higher
+        assert_eq!(ranks[&(1, 1)], 1);
+        assert_eq!(ranks[&(0, 2)], 0);
+    }
+
+    #[test]
+    fn coevolution_tick() {
+        let mut config = coevolution_config("coevolution_test", 4);
+        config.settings.logging_settings = None;
+        config
+            .settings
+            .reference_evaluation_settings
+            .evaluate_in_isolation = false;
+        for gene_pool in config.gene_pools.iter_mut() {
+            gene_pool.sim_settings.num_simulation_ticks = 10;
+            gene_pool.sim_settings.num_genomes_per_sim = 2;
+        }
+        config.settings.reference_sim_settings.num_simulation_ticks = 10;
+
+        let mut exp = config.to_experiment();
+        exp.tick();
+        exp.tick();
+
+        for gene_pool in exp.state.gene_pools.iter() {
+            assert_eq!(gene_pool.state.current_tick, 2);
+            assert!(gene_pool
+                .state
+                .genome_entries
+                .iter()
+                .all(|entry| entry.num_evaluations > 0));
+        }
+    }
+}
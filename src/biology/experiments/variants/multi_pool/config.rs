@@ -0,0 +1,109 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::types::{GenePoolSettings, MultiPoolExperimentSettings};
+use super::MultiPoolExperiment;
+
+/**
+ * Everything needed to construct a MultiPoolExperiment, so that experiments can be described in
+ * a ron file instead of a scenario function.  Alteration sets are written as a list of keys and
+ * weights.
+ */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MultiPoolExperimentConfig {
+    pub settings: MultiPoolExperimentSettings,
+    pub gene_pools: Vec<GenePoolSettings>,
+}
+
+impl MultiPoolExperimentConfig {
+    pub fn from_ron(s: &str) -> Self {
+        ron::from_str(s).unwrap_or_else(|e| panic!("Invalid experiment config: {}", e))
+    }
+
+    pub fn load(path: &Path) -> Self {
+        let s = std::fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("Could not read experiment config {:?}: {}", path, e));
+        Self::from_ron(&s)
+    }
+
+    pub fn to_ron(&self) -> String {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).unwrap()
+    }
+
+    /**
+     * Renames the experiment, which also moves where it is logged.
+     */
+    pub fn with_experiment_key(mut self, experiment_key: &str) -> Self {
+        self.settings.experiment_key = experiment_key.to_string();
+        if let Some(logging_settings) = self.settings.logging_settings.as_mut() {
+            logging_settings.experiment_key = experiment_key.to_string();
+        }
+        self
+    }
+
+    pub fn to_experiment(self) -> MultiPoolExperiment {
+        if self.gene_pools.is_empty() {
+            panic!("Experiment config has no gene pools");
+        }
+
+        let mut exp = MultiPoolExperiment::new(self.settings, self.gene_pools);
+        exp.initialize();
+        exp
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::biology::experiments::alterations::{
+        AlterationManifestEntry, CompiledAlterationSet,
+    };
+    use crate::scenarios::experiments::cheese::multi::vary_chemistry_config;
+
+    #[test]
+    fn ron_round_trip() {
+        let config = vary_chemistry_config("config_round_trip");
+
+        // chemistry configurations are hash maps, so compare without regard to key order
+        let as_value = |config: &MultiPoolExperimentConfig| {
+            ron::from_str::<ron::Value>(&config.to_ron()).unwrap()
+        };
+        let parsed = MultiPoolExperimentConfig::from_ron(&config.to_ron());
+        assert_eq!(as_value(&parsed), as_value(&config));
+        assert_eq!(parsed.gene_pools.len(), 3);
+        assert_eq!(parsed.gene_pools[1].name_key, "sparse_resources");
+
+        let renamed = parsed.with_experiment_key("renamed");
+        assert_eq!(renamed.settings.experiment_key, "renamed");
+        assert_eq!(
+            renamed.settings.logging_settings.unwrap().experiment_key,
+            "renamed"
+        );
+    }
+
+    #[test]
+    fn example_config() {
+        let config =
+            MultiPoolExperimentConfig::load(Path::new("configs/experiments/cheese_multi_pool.ron"));
+        assert_eq!(config.gene_pools.len(), 3);
+    }
+
+    #[test]
+    fn alteration_weights() {
+        let set: CompiledAlterationSet =
+            ron::from_str(r#"[(key: "insertion", weight: 0), (key: "deletion", weight: 3)]"#)
+                .unwrap();
+        assert_eq!(
+            set.entries(),
+            vec![
+                AlterationManifestEntry::new("insertion", 0),
+                AlterationManifestEntry::new("deletion", 3)
+            ]
+        );
+
+        for _ in 0..20 {
+            assert_eq!(set.choose_random().key, "deletion");
+        }
+    }
+}
@@ -1,4 +1,10 @@
-use std::{cell::Cell, rc::Rc, sync::mpsc, thread, time::Duration};
+use std::{
+    cell::Cell,
+    rc::Rc,
+    sync::{mpsc, Arc},
+    thread,
+    time::Duration,
+};
 
 use rand::Rng;
 use serde::Serialize;
@@ -9,7 +15,11 @@ use crate::{
         experiments::{
             alterations::{CompiledAlterationSet, GenomeAlterationImplementation},
             fitness::{calculate_new_fitness_ranks, normalize_ranks, ExperimentFitnessRank},
-            sim_runner::{execute_sim_runners, ExperimentSimRunner, SimRunnerGenomeEntry},
+            lineage::{collapse_to_founders, lineage_diversity, GenomeLineage, LineageRecord},
+            seeding::seed_genomes,
+            sim_runner::{
+                execute_sim_runners, ExperimentSimRunner, SimRunnerEntrySide, SimRunnerGenomeEntry,
+            },
             types::{
                 CullStrategy, ExperimentGenomeUid, GenomeEntryId, GenomeExperimentEntry,
                 TrialResultItem,
@@ -44,7 +54,7 @@ pub type GenePoolId = usize;
 pub struct ExperimentGenePool {
     pub id: GenePoolId,
     pub settings: GenePoolSettings,
-    pub gm: Rc<GeneticManifest>,
+    pub gm: Arc<GeneticManifest>,
     pub state: ExperimentGenePoolState,
 }
 
@@ -54,7 +64,11 @@ pub struct ExperimentGenePoolState {
     pub eval_points: u64,
     _last_entry_id: usize,
     pub current_tick: u64,
-    pub external_genomes_queue: Vec<CompiledFramedGenome>,
+    pub external_genomes_queue: Vec<(GenePoolId, ExperimentGenomeUid, CompiledFramedGenome)>,
+
+    // the lineage of the living genomes, plus any records not yet written to lineage.csv
+    pub lineage: Vec<LineageRecord>,
+    _logged_lineage_count: usize,
 }
 
 impl ExperimentGenePool {
@@ -70,8 +84,10 @@ impl ExperimentGenePool {
                 eval_points: 0,
                 current_tick: 0,
                 external_genomes_queue: vec![],
+                lineage: vec![],
+                _logged_lineage_count: 0,
             },
-            gm: Rc::new(gm),
+            gm: Arc::new(gm),
         };
 
         s.initialize();
@@ -84,18 +100,30 @@ impl ExperimentGenePool {
     }
 
     pub fn populate_initial_genomes(&mut self) {
-        let mut rng = rand::thread_rng();
-
-        match self.settings.seed_genome_settings {
-            crate::biology::experiments::types::SeedGenomeSettings::Random {
-                min_size,
-                max_size,
-            } => {
-                while self.state.genome_entries.len() < self.settings.num_genomes {
-                    self.register_new_genome(&random_genome_of_length(rng.gen_range((30..50))));
-                }
-            }
-        };
+        let still_need = self.settings.num_genomes - self.state.genome_entries.len();
+        for genome in seed_genomes(&self.settings.seed_genome_settings, still_need, &self.gm) {
+            self.register_new_genome(&genome, GenomeLineage::seed(self.state.current_tick));
+        }
+    }
+
+    /**
+     * Replaces the pool's seed genomes with the given ones, then tops the pool back up from
+     * the seed settings.  Only possible before the pool's first tick.
+     */
+    pub fn with_seed_genomes(&mut self, genomes: Vec<RawFramedGenome>) {
+        if self.state.current_tick != 0 {
+            panic!("Seed genomes can only be given before the gene pool has started");
+        }
+        if genomes.len() > self.settings.num_genomes {
+            panic!("Too many genomes given");
+        }
+
+        self.state.genome_entries.clear();
+        self.state.lineage.clear();
+        for genome in genomes.iter() {
+            self.register_new_genome(genome, GenomeLineage::seed(self.state.current_tick));
+        }
+        self.populate_initial_genomes();
     }
 
     pub fn add_eval_points(&mut self, eval_points: u64) {
@@ -110,9 +138,7 @@ impl ExperimentGenePool {
     }
 
     pub fn tick(&mut self) {
-        if self.state.current_tick != 0 {
-            self.cull_and_replace();
-        }
+        self.begin_tick();
 
         perf_timer_start!("gp_groups");
         let groups = self.partition_into_groups();
@@ -127,13 +153,49 @@ impl ExperimentGenePool {
             self.update_genomes_with_fitness_result(&fitness_result);
         }
 
+        self.finish_tick();
+
+        // thread::sleep(Duration::from_millis(2000));
+    }
+
+    pub fn begin_tick(&mut self) {
+        if self.state.current_tick != 0 {
+            self.cull_and_replace();
+        }
+    }
+
+    pub fn finish_tick(&mut self) {
         if self.state.current_tick % 100 == 0 {
             println!("gene pool {} tick: {}", self.id, self.state.current_tick);
         }
 
         self.state.current_tick += 1;
+    }
 
-        // thread::sleep(Duration::from_millis(2000));
+    /**
+     * How this pool's genomes are set apart when they share simulations with an opposing pool.
+     */
+    pub fn side(&self) -> SimRunnerEntrySide {
+        SimRunnerEntrySide {
+            fitness_calculation_key: self.settings.fitness_calculation_key.clone(),
+            default_unit_attr: self.settings.sim_settings.default_unit_attr.clone(),
+        }
+    }
+
+    /**
+     * This tick's groups, ready to be mixed into simulations with an opposing pool.  Spends
+     * eval points the same way run_eval_for_groups does.
+     */
+    pub fn runnable_side_groups(&mut self) -> Vec<Vec<SimRunnerGenomeEntry>> {
+        let groups = self.partition_into_groups();
+        let groups = scramble_groups(groups, &self.settings.fitness_cycle_strategy);
+        let side = self.side();
+
+        let mut groups = self._make_runnable_genome_groups(groups);
+        for entry in groups.iter_mut().flatten() {
+            entry.side = Some(side.clone());
+        }
+        groups
     }
 
     pub fn run_eval_for_groups(
@@ -177,6 +239,7 @@ impl ExperimentGenePool {
                                     genome_uid: entry.uid,
                                     genome: entry.compiled_genome.as_ref().clone(),
                                     execution_stats: entry.previous_execution_stats.clone(),
+                                    side: None,
                                 }
                             })
                             .collect::<Vec<_>>(),
@@ -216,6 +279,43 @@ impl ExperimentGenePool {
     }
 
     pub fn update_genomes_with_fitness_result(&mut self, fitness_results: &Vec<TrialResultItem>) {
+        self.record_fitness_results(fitness_results);
+
+        let new_ranks = calculate_new_fitness_ranks(
+            &fitness_results
+                .iter()
+                .map(|res| {
+                    (
+                        res.clone(),
+                        self.state.genome_entries[res.genome_idx].current_rank_score,
+                    )
+                })
+                .collect::<Vec<_>>(),
+            &self.settings.fitness_rank_adjustment_method,
+        );
+
+        for new_rank in new_ranks {
+            // println!(
+            //     "fitness: {}, rank: {}, id: {}",
+            //     new_rank.0.fitness_score, new_rank.1, new_rank.0.genome_idx
+            // );
+
+            assert_eq!(
+                self._find_by_uid(new_rank.0.experiment_genome_uid).unwrap(),
+                new_rank.0.genome_idx
+            );
+
+            self.state.genome_entries[new_rank.0.genome_idx].current_rank_score = new_rank.1;
+        }
+
+        self.normalize_ranks();
+    }
+
+    /**
+     * Updates the fitness metrics and evaluation counts of the genomes in the results, leaving
+     * their ranks alone.
+     */
+    pub fn record_fitness_results(&mut self, fitness_results: &Vec<TrialResultItem>) {
         for trial_result in fitness_results.iter() {
             let genome_idx = self
                 ._find_by_uid(trial_result.experiment_genome_uid)
@@ -248,35 +348,6 @@ impl ExperimentGenePool {
             //     trial_result.genome_idx, trial_result.fitness_score
             // );
         }
-
-        let new_ranks = calculate_new_fitness_ranks(
-            &fitness_results
-                .iter()
-                .map(|res| {
-                    (
-                        res.clone(),
-                        self.state.genome_entries[res.genome_idx].current_rank_score,
-                    )
-                })
-                .collect::<Vec<_>>(),
-            &self.settings.fitness_rank_adjustment_method,
-        );
-
-        for new_rank in new_ranks {
-            // println!(
-            //     "fitness: {}, rank: {}, id: {}",
-            //     new_rank.0.fitness_score, new_rank.1, new_rank.0.genome_idx
-            // );
-
-            assert_eq!(
-                self._find_by_uid(new_rank.0.experiment_genome_uid).unwrap(),
-                new_rank.0.genome_idx
-            );
-
-            self.state.genome_entries[new_rank.0.genome_idx].current_rank_score = new_rank.1;
-        }
-
-        self.normalize_ranks();
     }
 
     pub fn normalize_ranks(&mut self) {
@@ -305,29 +376,60 @@ impl ExperimentGenePool {
         while self.state.external_genomes_queue.len() > 0
             && self.state.genome_entries.len() < self.settings.num_genomes
         {
-            let genome = self.state.external_genomes_queue.remove(0);
-            self.register_new_genome(&genome.raw_values);
+            let (gene_pool_id, uid, genome) = self.state.external_genomes_queue.remove(0);
+            self.register_new_genome(
+                &genome.raw_values,
+                GenomeLineage::immigrant(gene_pool_id, uid, self.state.current_tick),
+            );
         }
 
         let raw_genomes = pull_fresh_genomes(
             &mut self.state.genome_entries,
             self.settings.num_genomes,
             &self.settings.alteration_specs,
+            self.state.current_tick,
         );
 
-        for raw_genome in &raw_genomes {
-            self.register_new_genome(raw_genome);
+        for (raw_genome, lineage) in raw_genomes {
+            self.register_new_genome(&raw_genome, lineage);
         }
     }
 
-    pub fn register_new_genome(&mut self, genome: &RawFramedGenome) {
+    /**
+     * The lineage records registered since the last call, for the logger to append.  Once
+     * taken, the records of culled genomes are dropped so the lineage kept in memory stays
+     * the size of the pool.
+     */
+    pub fn take_unlogged_lineage(&mut self) -> Vec<LineageRecord> {
+        let records = self.state.lineage[self.state._logged_lineage_count..].to_vec();
+        self.state.lineage = collapse_to_founders(&self.state.lineage, &self.living_uids());
+        self.state._logged_lineage_count = self.state.lineage.len();
+        records
+    }
+
+    /**
+     * How many distinct seed or immigrant genomes the living genomes descend from.
+     */
+    pub fn lineage_diversity(&self) -> usize {
+        lineage_diversity(&self.state.lineage, &self.living_uids())
+    }
+
+    fn living_uids(&self) -> Vec<ExperimentGenomeUid> {
+        self.state
+            .genome_entries
+            .iter()
+            .map(|entry| entry.uid)
+            .collect()
+    }
+
+    pub fn register_new_genome(&mut self, genome: &RawFramedGenome, lineage: GenomeLineage) {
         let next_genome_id = if self.state.genome_entries.len() > 0 {
             self.state._last_entry_id + 1
         } else {
             0
         };
 
-        let compiled_genome = FramedGenomeCompiler::compile(genome.clone(), &self.gm).wrap_rc();
+        let compiled_genome = FramedGenomeCompiler::compile(genome.clone(), &self.gm).wrap_arc();
         let stats = FramedGenomeExecutionStats::new(&compiled_genome.frames);
 
         let genome_entry = GenomeExperimentEntry {
@@ -338,6 +440,7 @@ impl ExperimentGenePool {
             uid: next_genome_id as ExperimentGenomeUid,
             current_rank_score: 0,
             previous_execution_stats: stats,
+            lineage,
         };
 
         self.state._last_entry_id = genome_entry.uid;
@@ -350,6 +453,10 @@ impl ExperimentGenePool {
         {
             panic!("uid is duplicated");
         }
+        self.state.lineage.push(LineageRecord {
+            uid: genome_entry.uid,
+            lineage: genome_entry.lineage.clone(),
+        });
         self.state.genome_entries.push(genome_entry);
     }
 
@@ -366,7 +473,8 @@ pub fn pull_fresh_genomes(
     genomes: &mut Vec<GenomeExperimentEntry>,
     target_count: usize,
     alteration_set: &CompiledAlterationSet,
-) -> Vec<Vec<FramedGenomeWord>> {
+    birth_tick: u64,
+) -> Vec<(Vec<FramedGenomeWord>, GenomeLineage)> {
     let mut sorted_by_rank = genomes
         .iter()
         .map(|genome| (genome.uid, genome.current_rank_score))
@@ -376,9 +484,12 @@ pub fn pull_fresh_genomes(
     let mut raw_genomes = vec![];
     while genomes.len() + raw_genomes.len() < target_count {
         let alteration = choose_random_alteration(alteration_set);
-        let genome = pull_fresh_genome(genomes, &alteration, &sorted_by_rank);
+        let (genome, parents) = pull_fresh_genome(genomes, &alteration, &sorted_by_rank);
         if genome.len() > 0 {
-            raw_genomes.push(genome);
+            raw_genomes.push((
+                genome,
+                GenomeLineage::altered(parents, &alteration.key, birth_tick),
+            ));
         }
     }
 
@@ -388,17 +499,16 @@ pub fn pull_fresh_genomes(
 pub fn choose_random_alteration(
     alterations_set: &CompiledAlterationSet,
 ) -> GenomeAlterationImplementation {
-    let mut rng = rand::thread_rng();
-    let alt_i = rng.gen_range((0..alterations_set.alterations.len()));
-    alterations_set.alterations[alt_i].clone()
+    alterations_set.choose_random()
 }
 
 fn pull_fresh_genome(
     genomes: &Vec<GenomeExperimentEntry>,
     alteration: &GenomeAlterationImplementation,
     sorted_by_fitness: &Vec<(ExperimentGenomeUid, ExperimentFitnessRank)>,
-) -> Vec<FramedGenomeWord> {
+) -> (Vec<FramedGenomeWord>, Vec<ExperimentGenomeUid>) {
     let mut input_genomes = vec![];
+    let mut parents = vec![];
     for i in (0..alteration.genomes_required) {
         let uid = select_random_top_genome(sorted_by_fitness);
 
@@ -414,12 +524,13 @@ fn pull_fresh_genome(
         // );
 
         input_genomes.push(genomes[idx].compiled_genome.as_ref());
+        parents.push(uid);
     }
 
     let params = (alteration.prepare)(&input_genomes.as_slice());
     let new_genome = (alteration.execute)(&input_genomes.as_slice(), &params.as_slice());
 
-    new_genome
+    (new_genome, parents)
 }
 
 pub fn select_random_top_genome(
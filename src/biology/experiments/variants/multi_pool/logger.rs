@@ -1,10 +1,12 @@
 use crate::biology::experiments::alterations;
+use crate::biology::experiments::lineage::{LineageRecord, LINEAGE_CSV_HEADER};
 use crate::biology::experiments::logging::{
     ensure_dir_exists, ensure_experiment_data_dir_exists, ensure_experiment_dir_exists,
     get_data_dir, get_experiment_log_dir, log_fitness_percentiles, log_status,
     logarithmic_tick_test, write_to_file,
 };
 use crate::biology::experiments::types::{GenomeExperimentEntry, TrialResultItem};
+use crate::biology::genome::framed::file::GenomeFile;
 use crate::biology::genome::framed::render::with_stats::render_frames_with_stats;
 use crate::biology::unit_behavior::framed::common::*;
 use crate::simulation::common::*;
@@ -20,7 +22,9 @@ use std::fs::OpenOptions;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
+use super::config::MultiPoolExperimentConfig;
 use super::gene_pool::{ExperimentGenePool, GenePoolId};
+use super::reference::{FitnessSummary, HallOfFame, PoolReferenceResult};
 use super::types::MultiPoolExperimentSettings;
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -60,11 +64,31 @@ impl MultiPoolExperimentLogger {
             if !gene_pool_dir_path.as_path().exists() {
                 fs::create_dir(gene_pool_dir_path.as_path()).expect("failed to create path");
             }
+
+            write_to_file(
+                self.get_gene_pool_lineage_path(gene_pool.id),
+                LINEAGE_CSV_HEADER.as_bytes(),
+                false,
+            );
+            write_to_file(
+                self.get_gene_pool_diversity_path(gene_pool.id),
+                "tick,diversity\n".as_bytes(),
+                false,
+            );
         }
 
         self.init_reference_eval_results(gene_pools);
     }
 
+    /**
+     * Saves the experiment as a config that run_experiment can start again.
+     */
+    pub fn log_config(&self, config: &MultiPoolExperimentConfig) {
+        let mut path = get_experiment_log_dir(&self.settings.experiment_key);
+        path.push("config.ron");
+        write_to_file(path, config.to_ron().as_bytes(), false);
+    }
+
     pub fn log_gene_pool_fitness_percentiles(&self, gene_pool: &ExperimentGenePool, tick: u64) {
         let mut path = self.get_gene_pool_log_dir(gene_pool.id);
         path.push("fitness.csv");
@@ -72,6 +96,46 @@ impl MultiPoolExperimentLogger {
         log_fitness_percentiles(&path, tick, &gene_pool.state.genome_entries);
     }
 
+    pub fn get_gene_pool_lineage_path(&self, gene_pool_id: GenePoolId) -> PathBuf {
+        let mut path = self.get_gene_pool_log_dir(gene_pool_id);
+        path.push("lineage.csv");
+        path
+    }
+
+    pub fn log_gene_pool_lineage(&self, gene_pool_id: GenePoolId, records: &[LineageRecord]) {
+        if records.is_empty() {
+            return;
+        }
+
+        let s = records
+            .iter()
+            .map(|record| record.to_csv_line())
+            .collect::<String>();
+        write_to_file(
+            self.get_gene_pool_lineage_path(gene_pool_id),
+            s.as_bytes(),
+            true,
+        );
+    }
+
+    pub fn get_gene_pool_diversity_path(&self, gene_pool_id: GenePoolId) -> PathBuf {
+        let mut path = self.get_gene_pool_log_dir(gene_pool_id);
+        path.push("diversity.csv");
+        path
+    }
+
+    /**
+     * Appends how many founding genomes the pool's living genomes descend from.
+     */
+    pub fn log_gene_pool_diversity(&self, gene_pool: &ExperimentGenePool, tick: u64) {
+        let s = format!("{},{}\n", tick, gene_pool.lineage_diversity());
+        write_to_file(
+            self.get_gene_pool_diversity_path(gene_pool.id),
+            s.as_bytes(),
+            true,
+        );
+    }
+
     pub fn get_gene_pool_log_dir(&self, gene_pool_id: GenePoolId) -> PathBuf {
         let mut path = get_experiment_log_dir(&self.settings.experiment_key);
 
@@ -106,23 +170,46 @@ impl MultiPoolExperimentLogger {
         let mut s = format!("tick,");
 
         for gene_pool in gene_pools.iter() {
-            s.push_str(&format!("{},", &gene_pool.settings.name_key));
+            let name = &gene_pool.settings.name_key;
+            s.push_str(&format!(
+                "{}_mean,{}_variance,{}_isolated_mean,{}_isolated_variance,",
+                name, name, name, name
+            ));
         }
 
         s.push_str("\n");
 
         let path = self._get_reference_fitness_path();
         write_to_file(path, s.as_bytes(), true);
+
+        write_to_file(
+            self._get_hall_of_fame_fitness_path(),
+            "tick,size,best_mean,best_variance,mean\n".as_bytes(),
+            false,
+        );
     }
 
-    pub fn log_reference_eval_results(&self, results: &Vec<TrialResultItem>, tick: u64) {
+    /**
+     * One row per tick, with the mean and variance of each pool's reference scores.  The
+     * isolated columns are left empty when isolated evaluation is turned off.
+     */
+    pub fn log_reference_eval_results(&self, results: &Vec<PoolReferenceResult>, tick: u64) {
         let mut results = results.clone();
         results.sort_by_cached_key(|r| r.gene_pool_id);
 
         let mut s = format!("{},", tick);
 
-        for (i, result) in results.iter().enumerate() {
-            s.push_str(&format!("{},", result.fitness_score))
+        for result in results.iter() {
+            s.push_str(&format!(
+                "{:.2},{:.2},",
+                result.together.mean, result.together.variance
+            ));
+            match &result.isolated {
+                Some(isolated) => {
+                    s.push_str(&format!("{:.2},{:.2},", isolated.mean, isolated.variance))
+                }
+                None => s.push_str(",,"),
+            }
         }
 
         s.push_str("\n");
@@ -130,6 +217,80 @@ impl MultiPoolExperimentLogger {
         let path = self._get_reference_fitness_path();
         write_to_file(path, s.as_bytes(), true);
     }
+
+    pub fn _get_hall_of_fame_fitness_path(&self) -> PathBuf {
+        let mut path = get_experiment_log_dir(&self.settings.experiment_key);
+        path.push("hall_of_fame.csv");
+        path
+    }
+
+    /**
+     * Appends the hall of fame's progress to hall_of_fame.csv and rewrites hall_of_fame.txt
+     * with its current members.  Each gene pool's members are also saved as a genome file in
+     * the gene pool's directory, since pools can have different manifests.
+     */
+    pub fn log_hall_of_fame(
+        &self,
+        hall_of_fame: &HallOfFame,
+        tick: u64,
+        gene_pools: &Vec<ExperimentGenePool>,
+    ) {
+        let summaries = hall_of_fame
+            .entries
+            .iter()
+            .map(|entry| hall_of_fame.fitness(entry))
+            .collect::<Vec<_>>();
+        let best = summaries.first().cloned().unwrap_or_default();
+        let mean = if summaries.is_empty() {
+            0.0
+        } else {
+            summaries.iter().map(|s| s.mean).sum::<f64>() / summaries.len() as f64
+        };
+
+        let s = format!(
+            "{},{},{:.2},{:.2},{:.2}\n",
+            tick,
+            summaries.len(),
+            best.mean,
+            best.variance,
+            mean
+        );
+        write_to_file(self._get_hall_of_fame_fitness_path(), s.as_bytes(), true);
+
+        let mut s = String::new();
+        for (entry, summary) in hall_of_fame.entries.iter().zip(summaries.iter()) {
+            s.push_str(&format!(
+                "------------------\n(gene pool: {}, uid: {}, inducted: {}, mean: {:.2}, variance: {:.2}, samples: {})\n",
+                gene_pools[entry.genome.gene_pool_id].settings.name_key,
+                entry.genome.uid,
+                entry.inducted_tick,
+                summary.mean,
+                summary.variance,
+                summary.num_samples
+            ));
+            s.push_str(&format!(
+                "raw_genome: {:?}\n\n",
+                &entry.genome.genome.raw_values
+            ));
+        }
+
+        let mut path = get_experiment_log_dir(&self.settings.experiment_key);
+        path.push("hall_of_fame.txt");
+        write_to_file(path, s.as_bytes(), false);
+
+        for gene_pool in gene_pools.iter() {
+            let genomes = hall_of_fame
+                .entries
+                .iter()
+                .filter(|entry| entry.genome.gene_pool_id == gene_pool.id)
+                .map(|entry| entry.genome.genome.raw_values.clone())
+                .collect::<Vec<_>>();
+
+            let mut path = self.get_gene_pool_log_dir(gene_pool.id);
+            path.push("hall_of_fame.ron");
+            GenomeFile::new(genomes, &gene_pool.gm).save(&path);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,16 +1,19 @@
 pub mod builder;
+pub mod coevolution;
+pub mod config;
 pub mod data_store;
 pub mod gene_pool;
 pub mod logger;
+pub mod reference;
 pub mod types;
 pub mod utils;
-use std::{cell::Cell, rc::Rc};
+use std::{cell::Cell, collections::HashMap, rc::Rc};
 
 use serde::Serialize;
 
 use crate::{
     biology::{
-        experiments::sim_runner::{ExperimentSimRunner, SimRunnerGenomeEntry},
+        experiments::sim_runner::{execute_sim_runners, ExperimentSimRunner, SimRunnerGenomeEntry},
         genome::framed::common::{CompiledFramedGenome, RawFramedGenome},
     },
     simulation::{
@@ -21,9 +24,15 @@ use crate::{
 };
 
 use self::{
+    coevolution::{pair_opposing_groups, rank_against_opponents, results_for_pool},
+    config::MultiPoolExperimentConfig,
     data_store::MultiPoolExperimentDataStore,
-    gene_pool::ExperimentGenePool,
+    gene_pool::{ExperimentGenePool, GenePoolId},
     logger::MultiPoolExperimentLogger,
+    reference::{
+        evaluate_in_isolation, evaluate_together, summarize_pool, HallOfFame, ReferenceGenome,
+        ReferenceScoreKind,
+    },
     types::{GenePoolSettings, MultiPoolExperimentSettings, MultiPoolExperimentState},
 };
 
@@ -42,6 +51,13 @@ impl MultiPoolExperiment {
             state: MultiPoolExperimentState {
                 current_tick: 0,
                 gene_pools: vec![],
+                hall_of_fame: HallOfFame::new(
+                    if settings.reference_evaluation_settings.evaluate_in_isolation {
+                        ReferenceScoreKind::Isolated
+                    } else {
+                        ReferenceScoreKind::Together
+                    },
+                ),
             },
             _logger: settings
                 .logging_settings
@@ -69,11 +85,28 @@ impl MultiPoolExperiment {
     pub fn initialize(&mut self) {
         if let Some(logger) = &self._logger {
             logger.init(&self.state.gene_pools);
+            logger.log_config(&self.config());
         }
 
         // self.populate_initial_genomes();
     }
 
+    pub fn with_seed_genomes(&mut self, gene_pool_id: GenePoolId, genomes: Vec<RawFramedGenome>) {
+        self.state.gene_pools[gene_pool_id].with_seed_genomes(genomes);
+    }
+
+    pub fn config(&self) -> MultiPoolExperimentConfig {
+        MultiPoolExperimentConfig {
+            settings: self.settings.clone(),
+            gene_pools: self
+                .state
+                .gene_pools
+                .iter()
+                .map(|gene_pool| gene_pool.settings.clone())
+                .collect(),
+        }
+    }
+
     pub fn start(&mut self) {
         self.resume();
     }
@@ -89,8 +122,22 @@ impl MultiPoolExperiment {
     }
 
     pub fn tick(&mut self) {
+        let coevolution = self.settings.coevolution.clone().unwrap_or_default();
+        for gene_pool in self.state.gene_pools.iter_mut() {
+            if !coevolution.is_coevolving(gene_pool.id) {
+                gene_pool.execute_with_points(self.settings.evaluation_points_per_tick);
+            }
+        }
+
+        for (a, b) in coevolution.opposing_pools {
+            self.execute_coevolution(a, b);
+        }
+
         for gene_pool in self.state.gene_pools.iter_mut() {
-            gene_pool.execute_with_points(self.settings.evaluation_points_per_tick);
+            let lineage = gene_pool.take_unlogged_lineage();
+            if let Some(logger) = &self._logger {
+                logger.log_gene_pool_lineage(gene_pool.id, &lineage);
+            }
         }
 
         self.execute_reference_evaluation();
@@ -106,10 +153,9 @@ impl MultiPoolExperiment {
 
             if let Some(logger) = &self._logger {
                 for gene_pool in &self.state.gene_pools {
-
-                    
                     logger.log_gene_pool_summary(gene_pool);
                     logger.log_gene_pool_fitness_percentiles(gene_pool, self.state.current_tick);
+                    logger.log_gene_pool_diversity(gene_pool, self.state.current_tick);
                 }
             }
         }
@@ -117,6 +163,84 @@ impl MultiPoolExperiment {
         self.state.current_tick += 1;
     }
 
+    fn is_coevolving(&self, gene_pool_id: GenePoolId) -> bool {
+        self.settings
+            .coevolution
+            .as_ref()
+            .map_or(false, |coevolution| coevolution.is_coevolving(gene_pool_id))
+    }
+
+    /**
+     * Spends a tick's eval points of two opposing pools on simulations that mix their genomes.
+     * Each pool is culled and refilled once per round, like a gene pool tick.
+     */
+    pub fn execute_coevolution(&mut self, a: GenePoolId, b: GenePoolId) {
+        if a == b {
+            panic!("Gene pool {} can't oppose itself", a);
+        }
+
+        for id in [a, b] {
+            self.state.gene_pools[id].add_eval_points(self.settings.evaluation_points_per_tick);
+        }
+
+        while self.state.gene_pools[a].state.eval_points > 0
+            && self.state.gene_pools[b].state.eval_points > 0
+        {
+            for id in [a, b] {
+                self.state.gene_pools[id].begin_tick();
+            }
+
+            let groups = pair_opposing_groups(
+                &self.state.gene_pools[a].runnable_side_groups(),
+                &self.state.gene_pools[b].runnable_side_groups(),
+            );
+            let settings = &self.state.gene_pools[a].settings;
+            let results = execute_sim_runners(
+                groups,
+                false,
+                &settings.sim_settings,
+                &settings.fitness_calculation_key,
+            );
+
+            for sim_results in results.iter() {
+                for id in [a, b] {
+                    let pool_results = results_for_pool(sim_results, id);
+                    if !pool_results.is_empty() {
+                        self.state.gene_pools[id].record_fitness_results(&pool_results);
+                    }
+                }
+            }
+
+            let mut ranks = HashMap::new();
+            let mut methods = HashMap::new();
+            for id in [a, b] {
+                let gene_pool = &self.state.gene_pools[id];
+                for entry in gene_pool.state.genome_entries.iter() {
+                    ranks.insert((id, entry.uid), entry.current_rank_score);
+                }
+                methods.insert(
+                    id,
+                    gene_pool.settings.fitness_rank_adjustment_method.clone(),
+                );
+            }
+            rank_against_opponents(&results, &mut ranks, &methods);
+
+            for id in [a, b] {
+                let gene_pool = &mut self.state.gene_pools[id];
+                for entry in gene_pool.state.genome_entries.iter_mut() {
+                    entry.current_rank_score = ranks[&(id, entry.uid)];
+                }
+                gene_pool.normalize_ranks();
+                gene_pool.finish_tick();
+            }
+        }
+
+        // whichever pool has points left couldn't play without its opponent
+        for id in [a, b] {
+            self.state.gene_pools[id].state.eval_points = 0;
+        }
+    }
+
     pub fn print_fitness_summary(&self) {
         let best_scores = self
             .state
@@ -147,60 +271,118 @@ impl MultiPoolExperiment {
             .iter()
             .map(|gene_pool| {
                 let i = gene_pool._highest_fitness_idx();
-                gene_pool.state.genome_entries[i]
-                    .compiled_genome
-                    .as_ref()
-                    .clone()
+                let entry = &gene_pool.state.genome_entries[i];
+                (
+                    gene_pool.id,
+                    entry.uid,
+                    entry.compiled_genome.as_ref().clone(),
+                )
             })
             .collect::<Vec<_>>();
 
+        let coevolution = self.settings.coevolution.clone().unwrap_or_default();
         for gene_pool in self.state.gene_pools.iter_mut() {
             if gene_pool.settings.receive_external_genomes {
-                gene_pool
-                    .state
-                    .external_genomes_queue
-                    .append(&mut best_genomes.clone());
+                // genomes evolved for the other side of a co-evolution would play the wrong role
+                let mut genomes = best_genomes
+                    .iter()
+                    .filter(|(id, _, _)| !coevolution.are_opponents(*id, gene_pool.id))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                gene_pool.state.external_genomes_queue.append(&mut genomes);
             }
         }
     }
 
+    /**
+     * Evaluates the top genomes of every pool under the same reference settings, both together
+     * and alone, and feeds the results into the hall of fame.
+     */
     pub fn execute_reference_evaluation(&mut self) {
-        let sim_settings = self.settings.reference_sim_settings.clone();
-        let fitness_key = self.settings.reference_fitness_calculation_key.clone();
-        let chemistry_builder = sim_settings.chemistry_options.clone();
+        let sim_settings = &self.settings.reference_sim_settings;
+        let fitness_key = &self.settings.reference_fitness_calculation_key;
+        let eval_settings = &self.settings.reference_evaluation_settings;
+        let tick = self.state.current_tick;
+        let trial_seeds = eval_settings.trial_seeds(tick);
 
-        let entries = self
+        let genomes = self
             .state
             .gene_pools
             .iter()
-            .map(|gene_pool| {
-                let (id, genome) = gene_pool
-                    .state
-                    .genome_entries
-                    .iter()
-                    .enumerate()
-                    .max_by(|(i, genome1), (j, genome2)| {
-                        genome1.current_rank_score.cmp(&genome2.current_rank_score)
+            .flat_map(|gene_pool| {
+                let mut entries = gene_pool.state.genome_entries.iter().collect::<Vec<_>>();
+                entries.sort_by_key(|entry| std::cmp::Reverse(entry.current_rank_score));
+
+                entries
+                    .into_iter()
+                    .take(eval_settings.genomes_per_pool)
+                    .map(|entry| ReferenceGenome {
+                        gene_pool_id: gene_pool.id,
+                        uid: entry.uid,
+                        genome: entry.compiled_genome.as_ref().clone(),
+                        side: if self.is_coevolving(gene_pool.id) {
+                            Some(gene_pool.side())
+                        } else {
+                            None
+                        },
                     })
-                    .unwrap();
-
-                SimRunnerGenomeEntry {
-                    gene_pool_id: gene_pool.id,
-                    genome_idx: id,
-                    genome_uid: genome.uid,
-                    genome: genome.compiled_genome.as_ref().clone(),
-                    execution_stats: genome.previous_execution_stats.clone(),
-                }
+                    .collect::<Vec<_>>()
             })
             .collect::<Vec<_>>();
 
-        let mut runner =
-            ExperimentSimRunner::new(chemistry_builder, entries, sim_settings, fitness_key);
+        let together = evaluate_together(&genomes, sim_settings, fitness_key, &trial_seeds);
+        let isolated = if eval_settings.evaluate_in_isolation {
+            Some(evaluate_in_isolation(
+                &genomes,
+                sim_settings,
+                fitness_key,
+                &trial_seeds,
+            ))
+        } else {
+            None
+        };
+
+        let pool_results = self
+            .state
+            .gene_pools
+            .iter()
+            .map(|gene_pool| summarize_pool(gene_pool.id, &together, isolated.as_ref()))
+            .collect::<Vec<_>>();
 
-        let results = runner.run_evaluation_for_uids();
+        self.state.hall_of_fame.consider(
+            &genomes,
+            &together,
+            isolated.as_ref(),
+            tick,
+            eval_settings.hall_of_fame_size,
+        );
+
+        // re-evaluated the way the hall is ranked, with seeds the pools weren't scored on
+        if eval_settings.is_hall_of_fame_tick(tick) {
+            let hall_of_fame = &mut self.state.hall_of_fame;
+            let hall_seeds = eval_settings.hall_of_fame_seeds(tick);
+            let genomes = hall_of_fame.genomes();
+            let scores = match hall_of_fame.ranked_by {
+                ReferenceScoreKind::Together => {
+                    evaluate_together(&genomes, sim_settings, fitness_key, &hall_seeds)
+                }
+                ReferenceScoreKind::Isolated => {
+                    evaluate_in_isolation(&genomes, sim_settings, fitness_key, &hall_seeds)
+                }
+            };
+            hall_of_fame.add_scores(
+                hall_of_fame.ranked_by,
+                &scores,
+                eval_settings.hall_of_fame_size,
+            );
+
+            if let Some(logger) = &self._logger {
+                logger.log_hall_of_fame(&self.state.hall_of_fame, tick, &self.state.gene_pools);
+            }
+        }
 
         if let Some(logger) = &self._logger {
-            logger.log_reference_eval_results(&results, self.state.current_tick);
+            logger.log_reference_eval_results(&pool_results, tick);
         }
     }
 }
@@ -0,0 +1,560 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    biology::{
+        experiments::{
+            sim_runner::{execute_sim_runners, SimRunnerEntrySide, SimRunnerGenomeEntry},
+            types::{ExperimentGenomeUid, ExperimentSimSettings},
+        },
+        genome::framed::{annotated::FramedGenomeExecutionStats, common::CompiledFramedGenome},
+    },
+    simulation::fitness::FitnessScore,
+};
+
+use super::gene_pool::GenePoolId;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ReferenceEvaluationSettings {
+    // how many of each pool's top ranked genomes are evaluated every tick
+    pub genomes_per_pool: usize,
+
+    // each trial's chemistry gets its own update and world seed, so that every trial is a
+    // different but reproducible draw of the world
+    pub num_trials: usize,
+
+    #[serde(default)]
+    pub seed: u64,
+
+    // also run every reference genome alone, which makes scores comparable across runs
+    pub evaluate_in_isolation: bool,
+
+    pub hall_of_fame_size: usize,
+
+    // how many ticks between re-evaluations of the hall of fame, 0 turns them off
+    pub hall_of_fame_interval: u64,
+}
+
+/**
+ * Evaluates one genome per pool in a single simulation, the same cost as before reference
+ * evaluation had settings.
+ */
+impl Default for ReferenceEvaluationSettings {
+    fn default() -> Self {
+        Self {
+            genomes_per_pool: 1,
+            num_trials: 1,
+            seed: 0,
+            evaluate_in_isolation: false,
+            hall_of_fame_size: 10,
+            hall_of_fame_interval: 0,
+        }
+    }
+}
+
+impl ReferenceEvaluationSettings {
+    /**
+     * The update seeds of the given tick's trials, distinct across trials and ticks.
+     */
+    pub fn trial_seeds(&self, tick: u64) -> Vec<u64> {
+        let num_trials = self.num_trials as u64;
+        (0..num_trials)
+            .map(|trial| self.seed.wrapping_add(tick * num_trials + trial))
+            .collect()
+    }
+
+    /**
+     * The seeds of the given tick's hall of fame re-evaluation, from the upper half of the
+     * seed space so that they never repeat a tick's trial seeds.
+     */
+    pub fn hall_of_fame_seeds(&self, tick: u64) -> Vec<u64> {
+        self.trial_seeds(tick)
+            .into_iter()
+            .map(|seed| seed ^ (1 << 63))
+            .collect()
+    }
+
+    pub fn is_hall_of_fame_tick(&self, tick: u64) -> bool {
+        self.hall_of_fame_interval > 0 && tick % self.hall_of_fame_interval == 0
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct FitnessSummary {
+    pub mean: f64,
+    pub variance: f64,
+    pub num_samples: usize,
+}
+
+impl FitnessSummary {
+    /**
+     * Uses the sample variance, which is 0 until there are at least two scores.
+     */
+    pub fn from_scores(scores: &[FitnessScore]) -> Self {
+        let n = scores.len();
+        if n == 0 {
+            return Self::default();
+        }
+
+        let mean = scores.iter().map(|s| *s as f64).sum::<f64>() / n as f64;
+        let variance = if n > 1 {
+            scores
+                .iter()
+                .map(|s| (*s as f64 - mean).powi(2))
+                .sum::<f64>()
+                / (n - 1) as f64
+        } else {
+            0.0
+        };
+
+        Self {
+            mean,
+            variance,
+            num_samples: n,
+        }
+    }
+
+    /**
+     * Half the width of the normal approximation 95% confidence interval of the mean.
+     */
+    pub fn confidence_interval_95(&self) -> f64 {
+        if self.num_samples == 0 {
+            return 0.0;
+        }
+        1.96 * (self.variance / self.num_samples as f64).sqrt()
+    }
+}
+
+/**
+ * A genome taken out of its gene pool for reference evaluation.  Uids are only unique within a
+ * pool, so genomes are identified by both.
+ */
+#[derive(Clone)]
+pub struct ReferenceGenome {
+    pub gene_pool_id: GenePoolId,
+    pub uid: ExperimentGenomeUid,
+    pub genome: CompiledFramedGenome,
+
+    // set for genomes of co-evolving pools, so they're scored the way their own pool scores them
+    pub side: Option<SimRunnerEntrySide>,
+}
+
+pub type ReferenceGenomeKey = (GenePoolId, ExperimentGenomeUid);
+
+impl ReferenceGenome {
+    pub fn key(&self) -> ReferenceGenomeKey {
+        (self.gene_pool_id, self.uid)
+    }
+
+    fn to_runner_entry(&self) -> SimRunnerGenomeEntry {
+        SimRunnerGenomeEntry {
+            gene_pool_id: self.gene_pool_id,
+            genome_idx: 0,
+            genome_uid: self.uid,
+            execution_stats: FramedGenomeExecutionStats::new(&self.genome.frames),
+            genome: self.genome.clone(),
+            side: self.side.clone(),
+        }
+    }
+}
+
+pub type ReferenceScores = HashMap<ReferenceGenomeKey, Vec<FitnessScore>>;
+
+fn run_trials(
+    groups: Vec<Vec<&ReferenceGenome>>,
+    sim_settings: &ExperimentSimSettings,
+    fitness_calculation_key: &String,
+    trial_seeds: &[u64],
+) -> ReferenceScores {
+    let mut scores = ReferenceScores::new();
+
+    for seed in trial_seeds.iter() {
+        let trial_settings = sim_settings.seeded(*seed);
+
+        let runner_groups = groups
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .map(|g| g.to_runner_entry())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        for result in execute_sim_runners(
+            runner_groups,
+            false,
+            &trial_settings,
+            fitness_calculation_key,
+        )
+        .into_iter()
+        .flatten()
+        {
+            scores
+                .entry((result.gene_pool_id, result.experiment_genome_uid))
+                .or_default()
+                .push(result.fitness_score);
+        }
+    }
+
+    scores
+}
+
+/**
+ * Runs all of the genomes in one simulation, once per trial seed.
+ */
+pub fn evaluate_together(
+    genomes: &[ReferenceGenome],
+    sim_settings: &ExperimentSimSettings,
+    fitness_calculation_key: &String,
+    trial_seeds: &[u64],
+) -> ReferenceScores {
+    run_trials(
+        vec![genomes.iter().collect()],
+        sim_settings,
+        fitness_calculation_key,
+        trial_seeds,
+    )
+}
+
+/**
+ * Runs each genome alone in its own simulation, once per trial seed.
+ */
+pub fn evaluate_in_isolation(
+    genomes: &[ReferenceGenome],
+    sim_settings: &ExperimentSimSettings,
+    fitness_calculation_key: &String,
+    trial_seeds: &[u64],
+) -> ReferenceScores {
+    run_trials(
+        genomes.iter().map(|genome| vec![genome]).collect(),
+        sim_settings,
+        fitness_calculation_key,
+        trial_seeds,
+    )
+}
+
+#[derive(Clone, Debug)]
+pub struct PoolReferenceResult {
+    pub gene_pool_id: GenePoolId,
+    pub together: FitnessSummary,
+    pub isolated: Option<FitnessSummary>,
+}
+
+/**
+ * Pools the scores of all of a gene pool's reference genomes over all trials.
+ */
+pub fn summarize_pool(
+    gene_pool_id: GenePoolId,
+    together: &ReferenceScores,
+    isolated: Option<&ReferenceScores>,
+) -> PoolReferenceResult {
+    let pool_scores = |scores: &ReferenceScores| {
+        scores
+            .iter()
+            .filter(|((id, _), _)| *id == gene_pool_id)
+            .flat_map(|(_, scores)| scores.iter().cloned())
+            .collect::<Vec<_>>()
+    };
+
+    PoolReferenceResult {
+        gene_pool_id,
+        together: FitnessSummary::from_scores(&pool_scores(together)),
+        isolated: isolated.map(|scores| FitnessSummary::from_scores(&pool_scores(scores))),
+    }
+}
+
+/**
+ * Scores of genomes evaluated together and alone come from different simulations, so they're
+ * never averaged with each other.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReferenceScoreKind {
+    Together,
+    Isolated,
+}
+
+#[derive(Clone)]
+pub struct HallOfFameEntry {
+    pub genome: ReferenceGenome,
+    pub inducted_tick: u64,
+
+    // every reference score of each kind the genome has received, so the estimates sharpen
+    // over time
+    pub together_scores: Vec<FitnessScore>,
+    pub isolated_scores: Vec<FitnessScore>,
+}
+
+impl HallOfFameEntry {
+    pub fn scores(&self, kind: ReferenceScoreKind) -> &Vec<FitnessScore> {
+        match kind {
+            ReferenceScoreKind::Together => &self.together_scores,
+            ReferenceScoreKind::Isolated => &self.isolated_scores,
+        }
+    }
+
+    pub fn fitness(&self, kind: ReferenceScoreKind) -> FitnessSummary {
+        FitnessSummary::from_scores(self.scores(kind))
+    }
+}
+
+/**
+ * The best genomes seen over the whole experiment, across all gene pools, ordered by their
+ * mean reference fitness of the `ranked_by` kind.
+ */
+#[derive(Clone)]
+pub struct HallOfFame {
+    pub entries: Vec<HallOfFameEntry>,
+    pub ranked_by: ReferenceScoreKind,
+}
+
+impl Default for HallOfFame {
+    fn default() -> Self {
+        Self::new(ReferenceScoreKind::Together)
+    }
+}
+
+impl HallOfFame {
+    pub fn new(ranked_by: ReferenceScoreKind) -> Self {
+        Self {
+            entries: vec![],
+            ranked_by,
+        }
+    }
+
+    /**
+     * Adds the genomes that aren't in the hall yet, then records the scores of every kind.
+     */
+    pub fn consider(
+        &mut self,
+        genomes: &[ReferenceGenome],
+        together: &ReferenceScores,
+        isolated: Option<&ReferenceScores>,
+        tick: u64,
+        max_size: usize,
+    ) {
+        for genome in genomes.iter() {
+            if !self
+                .entries
+                .iter()
+                .any(|entry| entry.genome.key() == genome.key())
+            {
+                self.entries.push(HallOfFameEntry {
+                    genome: genome.clone(),
+                    inducted_tick: tick,
+                    together_scores: vec![],
+                    isolated_scores: vec![],
+                });
+            }
+        }
+
+        self.extend_scores(ReferenceScoreKind::Together, together);
+        if let Some(isolated) = isolated {
+            self.extend_scores(ReferenceScoreKind::Isolated, isolated);
+        }
+
+        self.sort_and_truncate(max_size);
+    }
+
+    pub fn add_scores(
+        &mut self,
+        kind: ReferenceScoreKind,
+        scores: &ReferenceScores,
+        max_size: usize,
+    ) {
+        self.extend_scores(kind, scores);
+        self.sort_and_truncate(max_size);
+    }
+
+    pub fn fitness(&self, entry: &HallOfFameEntry) -> FitnessSummary {
+        entry.fitness(self.ranked_by)
+    }
+
+    fn extend_scores(&mut self, kind: ReferenceScoreKind, scores: &ReferenceScores) {
+        for entry in self.entries.iter_mut() {
+            if let Some(new_scores) = scores.get(&entry.genome.key()) {
+                match kind {
+                    ReferenceScoreKind::Together => &mut entry.together_scores,
+                    ReferenceScoreKind::Isolated => &mut entry.isolated_scores,
+                }
+                .extend_from_slice(new_scores);
+            }
+        }
+    }
+
+    pub fn genomes(&self) -> Vec<ReferenceGenome> {
+        self.entries
+            .iter()
+            .map(|entry| entry.genome.clone())
+            .collect()
+    }
+
+    fn sort_and_truncate(&mut self, max_size: usize) {
+        let kind = self.ranked_by;
+        self.entries.sort_by(|a, b| {
+            b.fitness(kind)
+                .mean
+                .partial_cmp(&a.fitness(kind).mean)
+                .unwrap()
+        });
+        self.entries.truncate(max_size);
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn fitness_summary() {
+        assert_eq!(FitnessSummary::from_scores(&[]), FitnessSummary::default());
+
+        let summary = FitnessSummary::from_scores(&[2, 4, 6]);
+        assert_eq!(summary.mean, 4.0);
+        assert_eq!(summary.variance, 4.0);
+        assert_eq!(summary.num_samples, 3);
+
+        assert_eq!(FitnessSummary::from_scores(&[5]).variance, 0.0);
+    }
+
+    fn genome(gene_pool_id: GenePoolId, uid: ExperimentGenomeUid) -> ReferenceGenome {
+        ReferenceGenome {
+            gene_pool_id,
+            uid,
+            genome: CompiledFramedGenome {
+                frames: vec![],
+                raw_size: 0,
+                raw_values: vec![],
+            },
+            side: None,
+        }
+    }
+
+    #[test]
+    fn evaluation_trials() {
+        use crate::biology::experiments::util::random_genome_of_length;
+        use crate::biology::genome::framed::builders::FramedGenomeCompiler;
+        use crate::scenarios::experiments::cheese::multi::base_sim_settings;
+        use crate::simulation::common::GeneticManifest;
+
+        let sim_settings = base_sim_settings().num_simulation_ticks(3).build();
+        let gm = GeneticManifest::from_chemistry(&sim_settings.chemistry_options.build());
+
+        // the same uid in two pools must not be confused
+        let genomes = vec![0, 1]
+            .into_iter()
+            .map(|gene_pool_id| ReferenceGenome {
+                gene_pool_id,
+                uid: 5,
+                genome: FramedGenomeCompiler::compile(random_genome_of_length(30), &gm),
+                side: None,
+            })
+            .collect::<Vec<_>>();
+        let fitness_key = "total_cheese_acquired".to_string();
+
+        let together = evaluate_together(&genomes, &sim_settings, &fitness_key, &[0, 1]);
+        let isolated = evaluate_in_isolation(&genomes, &sim_settings, &fitness_key, &[0, 1, 2]);
+
+        assert_eq!(together.len(), 2);
+        assert_eq!(together[&(0, 5)].len(), 2);
+        assert_eq!(isolated[&(1, 5)].len(), 3);
+
+        let result = summarize_pool(1, &together, Some(&isolated));
+        assert_eq!(result.together.num_samples, 2);
+        assert_eq!(result.isolated.unwrap().num_samples, 3);
+    }
+
+    #[test]
+    fn trial_seeds() {
+        let settings = ReferenceEvaluationSettings {
+            num_trials: 3,
+            seed: 100,
+            ..ReferenceEvaluationSettings::default()
+        };
+        assert_eq!(settings.trial_seeds(0), vec![100, 101, 102]);
+        assert_eq!(settings.trial_seeds(2), vec![106, 107, 108]);
+
+        // re-evaluations never reuse the seeds of any tick's trials
+        let hall_seeds = settings.hall_of_fame_seeds(2);
+        assert_eq!(hall_seeds.len(), 3);
+        for tick in 0..100 {
+            assert!(settings
+                .trial_seeds(tick)
+                .iter()
+                .all(|seed| !hall_seeds.contains(seed)));
+        }
+
+        assert!(!settings.is_hall_of_fame_tick(0));
+        let settings = ReferenceEvaluationSettings {
+            hall_of_fame_interval: 10,
+            ..settings
+        };
+        assert!(settings.is_hall_of_fame_tick(20));
+        assert!(!settings.is_hall_of_fame_tick(21));
+    }
+
+    fn consider(
+        hall: &mut HallOfFame,
+        key: ReferenceGenomeKey,
+        scores: Vec<FitnessScore>,
+        tick: u64,
+    ) {
+        let mut together = ReferenceScores::new();
+        together.insert(key, scores);
+        hall.consider(&[genome(key.0, key.1)], &together, None, tick, 2);
+    }
+
+    #[test]
+    fn hall_of_fame() {
+        let mut hall = HallOfFame::default();
+        consider(&mut hall, (0, 1), vec![10], 0);
+        consider(&mut hall, (1, 1), vec![30], 0);
+        consider(&mut hall, (0, 2), vec![20], 1);
+
+        let keys = hall
+            .entries
+            .iter()
+            .map(|entry| entry.genome.key())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec![(1, 1), (0, 2)]);
+
+        // re-evaluation can reorder the hall
+        let mut scores = ReferenceScores::new();
+        scores.insert((1, 1), vec![0, 0]);
+        hall.add_scores(ReferenceScoreKind::Together, &scores, 2);
+
+        assert_eq!(hall.entries[0].genome.key(), (0, 2));
+        assert_eq!(hall.fitness(&hall.entries[1]).mean, 10.0);
+        assert_eq!(hall.entries[1].inducted_tick, 0);
+    }
+
+    #[test]
+    fn hall_of_fame_keeps_score_kinds_apart() {
+        let mut hall = HallOfFame::new(ReferenceScoreKind::Isolated);
+        let genomes = vec![genome(0, 1), genome(0, 2)];
+
+        let mut together = ReferenceScores::new();
+        together.insert((0, 1), vec![100]);
+        together.insert((0, 2), vec![0]);
+        let mut isolated = ReferenceScores::new();
+        isolated.insert((0, 1), vec![10]);
+        isolated.insert((0, 2), vec![20]);
+        hall.consider(&genomes, &together, Some(&isolated), 0, 2);
+
+        // ranked by the isolated scores alone
+        assert_eq!(hall.entries[0].genome.key(), (0, 2));
+        assert_eq!(hall.entries[0].together_scores, vec![0]);
+        assert_eq!(hall.entries[0].isolated_scores, vec![20]);
+
+        let mut scores = ReferenceScores::new();
+        scores.insert((0, 1), vec![50]);
+        hall.add_scores(ReferenceScoreKind::Isolated, &scores, 2);
+        assert_eq!(hall.entries[0].genome.key(), (0, 1));
+        assert_eq!(hall.fitness(&hall.entries[0]).mean, 30.0);
+        assert_eq!(
+            hall.entries[0].fitness(ReferenceScoreKind::Together).mean,
+            100.0
+        );
+    }
+}
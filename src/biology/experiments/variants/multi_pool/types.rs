@@ -1,6 +1,6 @@
 use std::{cell::Cell, rc::Rc};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     biology::{
@@ -21,16 +21,22 @@ use crate::{
     },
 };
 
-use super::{gene_pool::ExperimentGenePool, logger::MultiPoolExperimentLoggingSettings};
+use super::{
+    coevolution::CoevolutionSettings,
+    gene_pool::ExperimentGenePool,
+    logger::MultiPoolExperimentLoggingSettings,
+    reference::{HallOfFame, ReferenceEvaluationSettings},
+};
 
 // use super::FitnessCycleStrategy;
 #[derive(Clone)]
 pub struct MultiPoolExperimentState {
     pub current_tick: u64,
     pub gene_pools: Vec<ExperimentGenePool>,
+    pub hall_of_fame: HallOfFame,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct MultiPoolExperimentSettings {
     pub max_iterations: u64,
     pub chemistry_key: String,
@@ -40,12 +46,18 @@ pub struct MultiPoolExperimentSettings {
 
     pub reference_sim_settings: ExperimentSimSettings,
     pub reference_fitness_calculation_key: String,
+
+    #[serde(default)]
+    pub reference_evaluation_settings: ReferenceEvaluationSettings,
+
+    #[serde(default)]
+    pub coevolution: Option<CoevolutionSettings>,
 }
 
 // #[derive(Serialize, Clone)]
 // pub struct MultiPoolLoggingSettings {}
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct GenePoolSettings {
     pub sim_settings: ExperimentSimSettings,
     pub num_genomes: usize,
@@ -60,7 +72,7 @@ pub struct GenePoolSettings {
     pub receive_external_genomes: bool,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum FitnessCycleStrategy {
     // every genome is tested every cycle
     Exaustive {
@@ -82,5 +94,5 @@ pub enum FitnessCycleStrategy {
 //     pub raw_genome: RawFramedGenome,
 //     pub uid: ExperimentGenomeUid,
 //     pub current_rank_score: usize,
-//     pub compiled_genome: Rc<CompiledFramedGenome>,
+//     pub compiled_genome: Arc<CompiledFramedGenome>,
 // }
@@ -5,6 +5,7 @@ use crate::biology::experiments::logging::{
     write_to_file,
 };
 use crate::biology::experiments::types::GenomeExperimentEntry;
+use crate::biology::genome::framed::file::GenomeFile;
 use crate::biology::genome::framed::render::with_stats::render_frames_with_stats;
 use crate::biology::unit_behavior::framed::common::*;
 use crate::simulation::common::*;
@@ -69,6 +70,7 @@ impl SimpleExperimentLogger {
         tick: u64,
         genome_entries: &Vec<GenomeExperimentEntry>,
         num_genomes: usize,
+        gm: &GeneticManifest,
     ) {
         if !logarithmic_tick_test(tick) {
             return;
@@ -83,11 +85,20 @@ impl SimpleExperimentLogger {
             return;
         }
 
-        let entries = entries.iter().take(num_genomes);
+        let entries = entries.iter().take(num_genomes).collect::<Vec<_>>();
 
         let mut path = get_experiment_log_dir(&self.settings.experiment_key);
         path.push("genomes");
-        path.push(format!("{}.csv", tick));
+        path.push(format!("{}.ron", tick));
+        GenomeFile::new(
+            entries
+                .iter()
+                .map(|entry| entry.compiled_genome.raw_values.clone())
+                .collect(),
+            gm,
+        )
+        .save(&path);
+        path.set_extension("csv");
 
         let mut s = String::new();
 
@@ -3,6 +3,8 @@ pub mod utils;
 
 use crate::biology::experiments::alterations;
 use crate::biology::experiments::fitness::normalize_ranks;
+use crate::biology::experiments::lineage::GenomeLineage;
+use crate::biology::experiments::seeding::seed_genomes;
 use crate::biology::experiments::sim_runner::{execute_sim_runners, SimRunnerGenomeEntry};
 use crate::biology::experiments::types::{
     CullStrategy, ExperimentGenomeUid, GenomeExperimentEntry, TrialResultItem,
@@ -16,6 +18,7 @@ use crate::biology::genome::framed::annotated::FramedGenomeExecutionStats;
 use crate::biology::genome::framed::common::*;
 use crate::simulation::fitness::FitnessScore;
 use crate::util::RateCounter;
+use std::sync::Arc;
 use rand::Rng;
 
 use self::logger::SimpleExperimentLogger;
@@ -46,7 +49,7 @@ pub struct SimpleExperiment {
     pub settings: Rc<SimpleExperimentSettings>,
     pub _last_entry_id: usize,
 
-    _gm: Rc<GeneticManifest>, // a cached copy.  note that this might eventually change depending on the genome.
+    _gm: Arc<GeneticManifest>, // a cached copy.  note that this might eventually change depending on the genome.
     _logger: Option<SimpleExperimentLogger>,
     _seed_genomes: Option<Vec<RawFramedGenome>>,
 
@@ -72,7 +75,7 @@ impl SimpleExperiment {
             settings: Rc::new(settings),
             _last_entry_id: 0,
 
-            _gm: Rc::new(gm),
+            _gm: Arc::new(gm),
             _logger: logger,
             _seed_genomes: None,
             _rate_counter: RateCounter::new(),
@@ -108,16 +111,13 @@ impl SimpleExperiment {
     pub fn populate_initial_genomes(&mut self) {
         if let Some(seed_genomes) = self._seed_genomes.clone() {
             for genome in seed_genomes.into_iter() {
-                self.register_new_genome(genome);
+                self.register_new_genome(genome, GenomeLineage::seed(self.current_tick));
             }
         }
 
         let still_need = self.settings.num_genomes - self.genome_entries.len();
-
-        let mut rng = rand::thread_rng();
-
-        for i in (0..still_need) {
-            self.register_new_genome(random_genome_of_length(rng.gen_range((30..50))));
+        for genome in seed_genomes(&self.settings.seed_genome_settings, still_need, &self._gm) {
+            self.register_new_genome(genome, GenomeLineage::seed(self.current_tick));
         }
     }
 
@@ -203,6 +203,7 @@ impl SimpleExperiment {
                     _tick as u64,
                     &self.genome_entries,
                     self.settings.sim_settings.num_genomes_per_sim,
+                    &self._gm,
                 );
             }
             perf_timer_stop!("experiment_logging");
@@ -381,10 +382,11 @@ impl SimpleExperiment {
             &mut self.genome_entries,
             self.settings.num_genomes,
             &self.settings.alteration_set,
+            self.current_tick,
         );
 
-        for raw_genome in raw_genomes {
-            self.register_new_genome(raw_genome);
+        for (raw_genome, lineage) in raw_genomes {
+            self.register_new_genome(raw_genome, lineage);
         }
     }
 
@@ -415,14 +417,14 @@ impl SimpleExperiment {
     //     }
     // }
 
-    pub fn register_new_genome(&mut self, genome: RawFramedGenome) {
+    pub fn register_new_genome(&mut self, genome: RawFramedGenome, lineage: GenomeLineage) {
         let next_genome_id = if self.genome_entries.len() > 0 {
             self._last_entry_id + 1
         } else {
             0
         };
 
-        let compiled_genome = FramedGenomeCompiler::compile(genome.clone(), &self._gm).wrap_rc();
+        let compiled_genome = FramedGenomeCompiler::compile(genome.clone(), &self._gm).wrap_arc();
         let stats = FramedGenomeExecutionStats::new(&compiled_genome.frames);
 
         let genome_entry = GenomeExperimentEntry {
@@ -433,6 +435,7 @@ impl SimpleExperiment {
             uid: next_genome_id as ExperimentGenomeUid,
             current_rank_score: 0,
             previous_execution_stats: stats,
+            lineage,
         };
 
         self._last_entry_id = genome_entry.uid;
@@ -568,6 +571,7 @@ impl SimpleExperiment {
                             execution_stats: self.genome_entries[entry_info.id]
                                 .previous_execution_stats
                                 .clone(),
+                            side: None,
                         }
                     })
                     .collect::<Vec<_>>()
@@ -624,7 +628,7 @@ impl SimpleExperiment {
     //         last_fitness_metrics: vec![],
     //         max_fitness_metric: None,
     //         num_evaluations: 0,
-    //         compiled_genome: FramedGenomeCompiler::compile(genome.clone(), &self._gm).wrap_rc(),
+    //         compiled_genome: FramedGenomeCompiler::compile(genome.clone(), &self._gm).wrap_arc(),
     //         raw_genome: genome,
     //         uid: usize::MAX,
     //         current_rank_score: 10,
@@ -641,7 +645,9 @@ pub mod tests {
 
     use self::utils::SimpleExperimentSettingsBuilder;
     use super::*;
-    use crate::biology::experiments::types::{CullStrategy, ExperimentSimSettings};
+    use crate::biology::experiments::types::{
+        CullStrategy, ExperimentSimSettings, SeedGenomeSettings,
+    };
     use crate::biology::experiments::util::random_genome_of_length;
     use crate::biology::genetic_manifest::GeneticManifest;
     use crate::simulation::common::builder::ChemistryBuilder;
@@ -653,12 +659,16 @@ pub mod tests {
         let chemistry = chemistry_builder.build();
 
         let gm =
-            GeneticManifest::construct::<CheeseChemistry>(&chemistry.get_configuration()).wrap_rc();
+            GeneticManifest::construct::<CheeseChemistry>(&chemistry.get_configuration()).wrap_arc();
 
         SimpleExperimentSettingsBuilder::default()
             .alteration_set(alterations::default_alteration_set())
             .experiment_key("my_experiment".to_string())
             .cull_strategy(CullStrategy::WorstFirst { percent: 0.30 })
+            .seed_genome_settings(SeedGenomeSettings::Random {
+                min_size: 30,
+                max_size: 50,
+            })
             .fitness_calculation_key("total_cheese_acquired".to_string())
             .num_genomes(11)
             .sim_settings(ExperimentSimSettings {
@@ -735,7 +745,7 @@ pub mod tests {
     #[test]
     fn test_random_genome_generation() {
         let chemistry = ChemistryBuilder::with_key("cheese").build();
-        let gm = GeneticManifest::from_default_chemistry_config::<CheeseChemistry>().wrap_rc();
+        let gm = GeneticManifest::from_default_chemistry_config::<CheeseChemistry>().wrap_arc();
 
         let vals1 = random_genome_of_length(100);
 
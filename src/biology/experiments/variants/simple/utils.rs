@@ -1,6 +1,6 @@
 use crate::biology::experiments::alterations;
 use crate::biology::experiments::types::{
-    CullStrategy, ExperimentGenomeUid, ExperimentSimSettings,
+    CullStrategy, ExperimentGenomeUid, ExperimentSimSettings, SeedGenomeSettings,
 };
 use crate::biology::experiments::variants::multi_pool::types::FitnessCycleStrategy;
 use crate::biology::genetic_manifest::GeneticManifest;
@@ -32,5 +32,8 @@ pub struct SimpleExperimentSettings {
     pub alteration_set: alterations::CompiledAlterationSet,
     pub fitness_calculation_key: String, // needed?  should this be a trait object?  how will fitness calculation change?
     pub cull_strategy: CullStrategy,
-    pub fitness_cycle_strategy: FitnessCycleStrategy, // pub gm: Rc<GeneticManifest>, // note: eventually this might be defined on a per-genome basis
+    pub fitness_cycle_strategy: FitnessCycleStrategy, // pub gm: Arc<GeneticManifest>, // note: eventually this might be defined on a per-genome basis
+
+    // fills whatever part of the pool with_seed_genomes doesn't
+    pub seed_genome_settings: SeedGenomeSettings,
 }
@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::biology::genome::framed::types::FramedGenomeValue;
 use crate::chemistry::actions::ActionManifest;
+use crate::chemistry::construct_chemistry_libraries;
 use crate::simulation::common::serialize::ChemistryManifestData;
 use crate::simulation::common::{
     ActionDefinition, ActionLibrary, Chemistry, ChemistryConfiguration, ChemistryInstance,
@@ -73,14 +74,16 @@ impl GeneticManifest {
                 &chemistry_manifest,
                 &local_property_sensors,
             )),
+            operator_manifest: Arc::new(OperatorManifest::new(
+                construct_chemistry_libraries(&chemistry_manifest.chemistry_key).operator_library,
+            )),
             chemistry_manifest: Arc::new(chemistry_manifest),
-            operator_manifest: Arc::new(OperatorManifest::default_operators()),
             number_of_registers: 5,
         }
     }
 
-    pub fn wrap_rc(self) -> Rc<Self> {
-        Rc::new(self)
+    pub fn wrap_arc(self) -> Arc<Self> {
+        Arc::new(self)
     }
 
     pub fn operator_id_for_key(&self, s: &str) -> OperatorId {
@@ -101,8 +104,7 @@ impl GeneticManifest {
  *
  *
  */
-// #[derive(Clone, Serialize, Deserialize)]
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct GeneticManifestData {
     pub chemistry_manifest: ChemistryManifestData,
 
@@ -117,6 +119,43 @@ pub struct GeneticManifestData {
 }
 
 impl GeneticManifestData {
+    pub fn from_manifest(gm: &GeneticManifest) -> Self {
+        Self {
+            chemistry_manifest: ChemistryManifestData::from_manifest(&gm.chemistry_manifest),
+            sensor_manifest: gm.sensor_manifest.as_ref().clone(),
+            operator_manifest: OperatorManifestData::from_manifest(&gm.operator_manifest),
+            number_of_registers: gm.number_of_registers,
+        }
+    }
+
+    /**
+     * Genomes refer to sensors, operators and reactions by their position in these lists, so
+     * two manifests with the same keys in the same order read a genome the same way.
+     */
+    pub fn sensor_keys(&self) -> Vec<String> {
+        self.sensor_manifest
+            .sensors
+            .iter()
+            .map(|sensor| sensor.key.clone())
+            .collect()
+    }
+
+    pub fn operator_keys(&self) -> Vec<String> {
+        self.operator_manifest
+            .operators
+            .iter()
+            .map(|op| op.name.clone())
+            .collect()
+    }
+
+    pub fn reaction_keys(&self) -> Vec<String> {
+        self.chemistry_manifest
+            .reactions
+            .iter()
+            .map(|reaction| reaction.key.clone())
+            .collect()
+    }
+
     // pub fn to_compiled(
     //     &self,
     //     action_library: ActionLibrary,
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::HashMap;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub type OperatorId = u8;
 
@@ -35,11 +35,11 @@ pub struct OperatorImplementation {
     pub is_constant: bool,
 }
 
-pub type OperatorFunction = dyn Fn(&[OperatorParam]) -> bool;
-pub type OperatorFunctionInstance = Rc<OperatorFunction>;
+pub type OperatorFunction = dyn Fn(&[OperatorParam]) -> bool + Send + Sync;
+pub type OperatorFunctionInstance = Arc<OperatorFunction>;
 
-pub type OperatorRenderFunction = Rc<dyn Fn(&[String]) -> String>;
-pub type OperatorRenderFunctionInstance = Rc<OperatorRenderFunction>;
+pub type OperatorRenderFunction = Arc<dyn Fn(&[String]) -> String + Send + Sync>;
+pub type OperatorRenderFunctionInstance = Arc<OperatorRenderFunction>;
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct OperatorManifestData {
@@ -49,8 +49,12 @@ pub struct OperatorManifestData {
 
 impl OperatorManifestData {
     pub fn default() -> OperatorManifestData {
+        Self::from_manifest(&default_operators())
+    }
+
+    pub fn from_manifest(operator_manifest: &OperatorManifest) -> OperatorManifestData {
         let mut manifest = Self {
-            operators: default_operators()
+            operators: operator_manifest
                 .operators
                 .iter()
                 .map(|op| OperatorEntry {
@@ -141,7 +145,12 @@ fn to_operator_set<'a>(mut operators: Vec<OperatorImplementation>) -> OperatorMa
     }
 }
 
-pub fn default_operators() -> OperatorManifest {
+/**
+ * The default operators as they were before the range, modulo, bitwise and scaled operators were
+ * appended and is_even was given its parameter.  Raw genomes evolved against it need to be
+ * translated, see GenomeTranslator, so don't change it along with default_operators.
+ */
+pub fn version_1_default_operators() -> OperatorManifest {
     return to_operator_set(vec![
         OperatorImplementation {
             index: 0,
@@ -149,9 +158,11 @@ pub fn default_operators() -> OperatorManifest {
             num_params: 2,
             is_constant: false,
 
-            evaluate: Rc::new(|params: &[OperatorParam]| -> bool { return params[0] == params[1] }),
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool {
+                return params[0] == params[1];
+            }),
 
-            render: Rc::new(|param_strs: &[String]| -> String {
+            render: Arc::new(|param_strs: &[String]| -> String {
                 format!("{} == {}", &param_strs[0], &param_strs[1])
             }),
         },
@@ -160,11 +171,11 @@ pub fn default_operators() -> OperatorManifest {
             name: "is_truthy",
             num_params: 1,
             is_constant: false,
-            evaluate: Rc::new(|params: &[OperatorParam]| -> bool {
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool {
                 return params[0] > 0;
             }),
 
-            render: Rc::new(|param_strs: &[String]| -> String {
+            render: Arc::new(|param_strs: &[String]| -> String {
                 format!("is_truthy({})", param_strs[0])
             }),
         },
@@ -173,11 +184,11 @@ pub fn default_operators() -> OperatorManifest {
             name: "is_falsy",
             num_params: 1,
             is_constant: false,
-            evaluate: Rc::new(|params: &[OperatorParam]| -> bool {
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool {
                 return params[0] == 0;
             }),
 
-            render: Rc::new(|param_strs: &[String]| -> String {
+            render: Arc::new(|param_strs: &[String]| -> String {
                 format!("is_truthy({})", param_strs[0])
             }),
         },
@@ -186,8 +197,8 @@ pub fn default_operators() -> OperatorManifest {
             name: "gt",
             num_params: 2,
             is_constant: false,
-            evaluate: Rc::new(|params: &[OperatorParam]| -> bool { return params[0] > params[1] }),
-            render: Rc::new(|param_strs: &[String]| -> String {
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool { return params[0] > params[1] }),
+            render: Arc::new(|param_strs: &[String]| -> String {
                 format!("{} > {}", param_strs[0], param_strs[1])
             }),
         },
@@ -196,8 +207,10 @@ pub fn default_operators() -> OperatorManifest {
             name: "gte",
             num_params: 2,
             is_constant: false,
-            evaluate: Rc::new(|params: &[OperatorParam]| -> bool { return params[0] >= params[1] }),
-            render: Rc::new(|param_strs: &[String]| -> String {
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool {
+                return params[0] >= params[1];
+            }),
+            render: Arc::new(|param_strs: &[String]| -> String {
                 format!("{} >= {}", param_strs[0], param_strs[1])
             }),
         },
@@ -206,8 +219,8 @@ pub fn default_operators() -> OperatorManifest {
             name: "lt",
             num_params: 2,
             is_constant: false,
-            evaluate: Rc::new(|params: &[OperatorParam]| -> bool { return params[0] < params[1] }),
-            render: Rc::new(|param_strs: &[String]| -> String {
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool { return params[0] < params[1] }),
+            render: Arc::new(|param_strs: &[String]| -> String {
                 format!("{} < {}", param_strs[0], param_strs[1])
             }),
         },
@@ -216,8 +229,10 @@ pub fn default_operators() -> OperatorManifest {
             name: "lte",
             num_params: 2,
             is_constant: false,
-            evaluate: Rc::new(|params: &[OperatorParam]| -> bool { return params[0] <= params[1] }),
-            render: Rc::new(|param_strs: &[String]| -> String {
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool {
+                return params[0] <= params[1];
+            }),
+            render: Arc::new(|param_strs: &[String]| -> String {
                 format!("{} <= {}", param_strs[0], param_strs[1])
             }),
         },
@@ -226,27 +241,257 @@ pub fn default_operators() -> OperatorManifest {
             name: "true",
             num_params: 0,
             is_constant: true,
-            evaluate: Rc::new(|params: &[OperatorParam]| -> bool { true }),
-            render: Rc::new(|param_strs: &[String]| -> String { format!("TRUE") }),
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool { true }),
+            render: Arc::new(|param_strs: &[String]| -> String { format!("TRUE") }),
         },
         OperatorImplementation {
             index: 0,
             name: "false",
             num_params: 0,
             is_constant: true,
-            evaluate: Rc::new(|params: &[OperatorParam]| -> bool { false }),
-            render: Rc::new(|param_strs: &[String]| -> String { format!("FALSE") }),
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool { false }),
+            render: Arc::new(|param_strs: &[String]| -> String { format!("FALSE") }),
         },
         OperatorImplementation {
             index: 0,
             name: "is_even",
             num_params: 0,
             is_constant: false,
-            evaluate: Rc::new(|params: &[OperatorParam]| -> bool { params[0] % 2 == 0 }),
-            render: Rc::new(|param_strs: &[String]| -> String {
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool { params[0] % 2 == 0 }),
+            render: Arc::new(|param_strs: &[String]| -> String {
+                format!("is_even({})", param_strs[0])
+            }),
+        },
+    ]);
+}
+
+pub fn default_operators() -> OperatorManifest {
+    return to_operator_set(vec![
+        OperatorImplementation {
+            index: 0,
+            name: "eq",
+            num_params: 2,
+            is_constant: false,
+
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool {
+                return params[0] == params[1];
+            }),
+
+            render: Arc::new(|param_strs: &[String]| -> String {
+                format!("{} == {}", &param_strs[0], &param_strs[1])
+            }),
+        },
+        OperatorImplementation {
+            index: 0,
+            name: "is_truthy",
+            num_params: 1,
+            is_constant: false,
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool {
+                return params[0] > 0;
+            }),
+
+            render: Arc::new(|param_strs: &[String]| -> String {
+                format!("is_truthy({})", param_strs[0])
+            }),
+        },
+        OperatorImplementation {
+            index: 0,
+            name: "is_falsy",
+            num_params: 1,
+            is_constant: false,
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool {
+                return params[0] == 0;
+            }),
+
+            render: Arc::new(|param_strs: &[String]| -> String {
+                format!("is_falsy({})", param_strs[0])
+            }),
+        },
+        OperatorImplementation {
+            index: 0,
+            name: "gt",
+            num_params: 2,
+            is_constant: false,
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool { return params[0] > params[1] }),
+            render: Arc::new(|param_strs: &[String]| -> String {
+                format!("{} > {}", param_strs[0], param_strs[1])
+            }),
+        },
+        OperatorImplementation {
+            index: 0,
+            name: "gte",
+            num_params: 2,
+            is_constant: false,
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool {
+                return params[0] >= params[1];
+            }),
+            render: Arc::new(|param_strs: &[String]| -> String {
+                format!("{} >= {}", param_strs[0], param_strs[1])
+            }),
+        },
+        OperatorImplementation {
+            index: 0,
+            name: "lt",
+            num_params: 2,
+            is_constant: false,
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool { return params[0] < params[1] }),
+            render: Arc::new(|param_strs: &[String]| -> String {
+                format!("{} < {}", param_strs[0], param_strs[1])
+            }),
+        },
+        OperatorImplementation {
+            index: 0,
+            name: "lte",
+            num_params: 2,
+            is_constant: false,
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool {
+                return params[0] <= params[1];
+            }),
+            render: Arc::new(|param_strs: &[String]| -> String {
+                format!("{} <= {}", param_strs[0], param_strs[1])
+            }),
+        },
+        OperatorImplementation {
+            index: 0,
+            name: "true",
+            num_params: 0,
+            is_constant: true,
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool { true }),
+            render: Arc::new(|param_strs: &[String]| -> String { format!("TRUE") }),
+        },
+        OperatorImplementation {
+            index: 0,
+            name: "false",
+            num_params: 0,
+            is_constant: true,
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool { false }),
+            render: Arc::new(|param_strs: &[String]| -> String { format!("FALSE") }),
+        },
+        OperatorImplementation {
+            index: 0,
+            name: "is_even",
+            num_params: 1,
+            is_constant: false,
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool { params[0] % 2 == 0 }),
+            render: Arc::new(|param_strs: &[String]| -> String {
                 format!("is_even({})", param_strs[0])
             }),
         },
+        // added after version_1_default_operators
+        OperatorImplementation {
+            index: 0,
+            name: "between",
+            num_params: 3,
+            is_constant: false,
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool {
+                let (low, high) = (params[1].min(params[2]), params[1].max(params[2]));
+                params[0] >= low && params[0] <= high
+            }),
+            render: Arc::new(|param_strs: &[String]| -> String {
+                format!(
+                    "between({}, {}, {})",
+                    param_strs[0], param_strs[1], param_strs[2]
+                )
+            }),
+        },
+        OperatorImplementation {
+            index: 0,
+            name: "abs_diff_lt",
+            num_params: 3,
+            is_constant: false,
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool {
+                (params[0] as i64 - params[1] as i64).abs() < params[2] as i64
+            }),
+            render: Arc::new(|param_strs: &[String]| -> String {
+                format!(
+                    "|{} - {}| < {}",
+                    param_strs[0], param_strs[1], param_strs[2]
+                )
+            }),
+        },
+        OperatorImplementation {
+            index: 0,
+            name: "is_multiple_of",
+            num_params: 2,
+            is_constant: false,
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool {
+                params[1] != 0 && params[0].wrapping_rem(params[1]) == 0
+            }),
+            render: Arc::new(|param_strs: &[String]| -> String {
+                format!("{} % {} == 0", param_strs[0], param_strs[1])
+            }),
+        },
+        OperatorImplementation {
+            index: 0,
+            name: "mod_eq",
+            num_params: 3,
+            is_constant: false,
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool {
+                params[1] != 0 && params[0].wrapping_rem_euclid(params[1]) == params[2]
+            }),
+            render: Arc::new(|param_strs: &[String]| -> String {
+                format!("{} % {} == {}", param_strs[0], param_strs[1], param_strs[2])
+            }),
+        },
+        OperatorImplementation {
+            index: 0,
+            name: "has_bit",
+            num_params: 2,
+            is_constant: false,
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool {
+                (params[0] >> (params[1] & 31)) & 1 == 1
+            }),
+            render: Arc::new(|param_strs: &[String]| -> String {
+                format!("{} & (1 << {}) != 0", param_strs[0], param_strs[1])
+            }),
+        },
+        OperatorImplementation {
+            index: 0,
+            name: "has_all_bits",
+            num_params: 2,
+            is_constant: false,
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool {
+                params[0] & params[1] == params[1]
+            }),
+            render: Arc::new(|param_strs: &[String]| -> String {
+                format!("{} & {} == {}", param_strs[0], param_strs[1], param_strs[1])
+            }),
+        },
+        OperatorImplementation {
+            index: 0,
+            name: "has_any_bits",
+            num_params: 2,
+            is_constant: false,
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool { params[0] & params[1] != 0 }),
+            render: Arc::new(|param_strs: &[String]| -> String {
+                format!("{} & {} != 0", param_strs[0], param_strs[1])
+            }),
+        },
+        // compare one value to a percentage of another, eg. gt_scaled(a, b, 150) is a > 1.5 * b
+        OperatorImplementation {
+            index: 0,
+            name: "gt_scaled",
+            num_params: 3,
+            is_constant: false,
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool {
+                params[0] as i64 * 100 > params[1] as i64 * params[2] as i64
+            }),
+            render: Arc::new(|param_strs: &[String]| -> String {
+                format!("{} > {} * {}%", param_strs[0], param_strs[1], param_strs[2])
+            }),
+        },
+        OperatorImplementation {
+            index: 0,
+            name: "lt_scaled",
+            num_params: 3,
+            is_constant: false,
+            evaluate: Arc::new(|params: &[OperatorParam]| -> bool {
+                (params[0] as i64) * 100 < params[1] as i64 * params[2] as i64
+            }),
+            render: Arc::new(|param_strs: &[String]| -> String {
+                format!("{} < {} * {}%", param_strs[0], param_strs[1], param_strs[2])
+            }),
+        },
     ]);
 }
 
@@ -275,4 +520,116 @@ mod tests {
         assert_eq!((op.evaluate)(&vec![2, 1]), true);
         assert_eq!((op.evaluate)(&vec![1, 1]), false);
     }
+
+    #[test]
+    fn test_arithmetic_operators() {
+        let operators = default_operators();
+        let eval =
+            |key: &str, params: [OperatorParam; 3]| (operators.by_key(key).evaluate)(&params);
+
+        assert!(eval("between", [5, 10, 1]));
+        assert!(!eval("between", [11, 1, 10]));
+        assert!(eval("abs_diff_lt", [3, 7, 5]));
+        assert!(!eval("abs_diff_lt", [7, 3, 4]));
+        assert!(eval("is_multiple_of", [12, 4, 0]));
+        assert!(!eval("is_multiple_of", [12, 0, 0]));
+        assert!(eval("mod_eq", [-1, 4, 3]));
+        assert!(eval("has_bit", [0b100, 2, 0]));
+        assert!(!eval("has_bit", [0b100, 1, 0]));
+        assert!(eval("has_all_bits", [0b111, 0b101, 0]));
+        assert!(!eval("has_all_bits", [0b110, 0b101, 0]));
+        assert!(eval("has_any_bits", [0b110, 0b101, 0]));
+        assert!(eval("gt_scaled", [16, 10, 150]));
+        assert!(!eval("gt_scaled", [15, 10, 150]));
+        assert!(eval("lt_scaled", [4, 10, 50]));
+        assert!(!eval("lt_scaled", [i32::MAX, i32::MAX, 100]));
+
+        let render = |key: &str| {
+            (operators.by_key(key).render)(&["a".to_string(), "b".to_string(), "c".to_string()])
+        };
+        assert_eq!(render("abs_diff_lt"), "|a - b| < c");
+        assert_eq!(render("gt_scaled"), "a > b * c%");
+    }
+
+    #[test]
+    fn test_single_param_operators() {
+        let operators = default_operators();
+        let render = |key: &str| (operators.by_key(key).render)(&["a".to_string()]);
+        assert_eq!(render("is_truthy"), "is_truthy(a)");
+        assert_eq!(render("is_falsy"), "is_falsy(a)");
+        assert_eq!(render("is_even"), "is_even(a)");
+
+        // is_even reads its param, so it has to be given one
+        assert_eq!(operators.by_key("is_even").num_params, 1);
+        assert!((operators.by_key("is_even").evaluate)(&[4]));
+    }
+
+    #[test]
+    fn test_version_1_operators() {
+        use crate::biology::experiments::util::random_genome_of_length;
+        use crate::biology::genetic_manifest::GeneticManifest;
+        use crate::biology::genome::framed::common::FramedGenomeCompiler;
+        use crate::biology::genome::framed::translate::GenomeTranslator;
+        use crate::chemistry::variants::CheeseChemistry;
+
+        use crate::biology::genome::framed::translate::Untranslatable;
+
+        let gm = GeneticManifest::from_default_chemistry_config::<CheeseChemistry>();
+        let mut version_1_gm = gm.clone();
+        version_1_gm.operator_manifest = Arc::new(version_1_default_operators());
+
+        let operators = &version_1_gm.operator_manifest;
+        assert_eq!(operators.operators.len(), 10);
+        assert_eq!(operators.operators.last().unwrap().name, "is_even");
+        assert_eq!(operators.by_key("is_even").num_params, 0);
+        assert_eq!(
+            (operators.by_key("is_falsy").render)(&["a".to_string()]),
+            "is_truthy(a)"
+        );
+
+        // is_even reads a param now, so genomes that use it can't be translated
+        let translator = GenomeTranslator::between(&version_1_gm, &gm);
+        let mut num_translated = 0;
+        for _ in 0..50 {
+            let genome = random_genome_of_length(60);
+            let translation = translator.translate(&genome);
+            if !translation.untranslatable.is_empty() {
+                assert!(translation.untranslatable.iter().all(|u| match u {
+                    Untranslatable::Operator { key, .. } => key == "is_even",
+                    _ => false,
+                }));
+                continue;
+            }
+
+            // version 1 rendered is_falsy as is_truthy
+            num_translated += 1;
+            assert_eq!(
+                FramedGenomeCompiler::compile(translation.genome, &gm)
+                    .display(&gm)
+                    .replace("is_falsy(", "is_truthy("),
+                FramedGenomeCompiler::compile(genome, &version_1_gm).display(&version_1_gm)
+            );
+        }
+        assert!(num_translated > 0);
+    }
+
+    #[test]
+    fn test_chemistry_operators() {
+        use crate::biology::genetic_manifest::GeneticManifest;
+        use crate::chemistry::variants::{CheeseChemistry, FooChemistry};
+
+        let num_default_operators = default_operators().operators.len();
+
+        let gm = GeneticManifest::from_default_chemistry_config::<FooChemistry>();
+        let op = gm.operator_manifest.by_key("is_magic_foo_amount");
+        assert_eq!(op.index as usize, num_default_operators);
+        assert_eq!((op.evaluate)(&[10, 0, 0]), true);
+        assert_eq!(
+            (op.render)(&["x".to_string(), "".to_string(), "".to_string()]),
+            "is_magic_foo_amount(x)"
+        );
+
+        let gm = GeneticManifest::from_default_chemistry_config::<CheeseChemistry>();
+        assert_eq!(gm.operator_manifest.operators.len(), num_default_operators);
+    }
 }
@@ -1,24 +1,54 @@
-use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use super::common::{Frame, Gene, NUM_CHANNELS};
 
+/**
+ * A usize counter that units evaluated on different threads can bump at the same time.
+ */
+#[derive(Debug, Default)]
+pub struct StatCounter(AtomicUsize);
+
+impl StatCounter {
+    pub fn new(value: usize) -> Self {
+        Self(AtomicUsize::new(value))
+    }
+
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub fn set(&self, value: usize) {
+        self.0.store(value, Ordering::Relaxed)
+    }
+
+    pub fn increment(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Clone for StatCounter {
+    fn clone(&self) -> Self {
+        Self::new(self.get())
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct FramedGenomeExecutionStats {
     pub frames: Vec<FrameExecutionStats>,
-    pub eval_count: Cell<usize>,
+    pub eval_count: StatCounter,
 }
 
 impl FramedGenomeExecutionStats {
     pub fn empty() -> Self {
         Self {
-            eval_count: Cell::new(0),
+            eval_count: StatCounter::new(0),
             frames: vec![],
         }
     }
 
     pub fn new(frames: &Vec<Frame>) -> Self {
         let mut s = Self {
-            eval_count: Cell::new(0),
+            eval_count: StatCounter::new(0),
             frames: vec![],
         };
 
@@ -28,7 +58,7 @@ impl FramedGenomeExecutionStats {
     }
 
     pub fn mark_eval(&self) {
-        self.eval_count.set(self.eval_count.get() + 1);
+        self.eval_count.increment();
     }
 
     pub fn initialize(&mut self, frames: &Vec<Frame>) {
@@ -109,16 +139,16 @@ impl FramedGenomeExecutionStats {
 
 #[derive(Debug, Clone)]
 pub struct FrameExecutionStats {
-    pub eval_count: Cell<usize>,
-    pub eval_true_count: Cell<usize>,
+    pub eval_count: StatCounter,
+    pub eval_true_count: StatCounter,
     pub channels: [ChannelExecutionStats; NUM_CHANNELS],
 }
 
 impl FrameExecutionStats {
     pub fn new() -> Self {
         Self {
-            eval_count: Cell::new(0),
-            eval_true_count: Cell::new(0),
+            eval_count: StatCounter::new(0),
+            eval_true_count: StatCounter::new(0),
             channels: [
                 ChannelExecutionStats::new(),
                 ChannelExecutionStats::new(),
@@ -129,10 +159,10 @@ impl FrameExecutionStats {
     }
 
     pub fn mark_eval(&self) {
-        self.eval_count.set(self.eval_count.get() + 1);
+        self.eval_count.increment();
     }
     pub fn mark_eval_true(&self) {
-        self.eval_true_count.set(self.eval_true_count.get() + 1);
+        self.eval_true_count.increment();
     }
 
     pub fn pct_true(&self) -> f32 {
@@ -142,25 +172,25 @@ impl FrameExecutionStats {
 
 #[derive(Debug, Clone)]
 pub struct ChannelExecutionStats {
-    pub eval_count: Cell<usize>,
-    pub eval_true_count: Cell<usize>,
+    pub eval_count: StatCounter,
+    pub eval_true_count: StatCounter,
     pub genes: Vec<GeneExecutionStats>,
 }
 
 impl ChannelExecutionStats {
     pub fn new() -> Self {
         Self {
-            eval_count: Cell::new(0),
-            eval_true_count: Cell::new(0),
+            eval_count: StatCounter::new(0),
+            eval_true_count: StatCounter::new(0),
             genes: vec![],
         }
     }
     pub fn mark_eval(&self) {
-        self.eval_count.set(self.eval_count.get() + 1);
+        self.eval_count.increment();
     }
 
     pub fn mark_eval_true(&self) {
-        self.eval_true_count.set(self.eval_true_count.get() + 1);
+        self.eval_true_count.increment();
     }
 
     pub fn pct_true(&self) -> f32 {
@@ -170,8 +200,11 @@ impl ChannelExecutionStats {
 
 #[derive(Debug, Clone)]
 pub struct GeneExecutionStats {
-    pub eval_count: Cell<usize>,
-    pub eval_true_count: Cell<usize>,
+    pub eval_count: StatCounter,
+    pub eval_true_count: StatCounter,
+
+    // the number of this gene's reactions that the chemistry executed successfully
+    pub reaction_count: StatCounter,
 
     pub disjunction_expression: DisjunctionExpressionStats,
 }
@@ -179,16 +212,20 @@ pub struct GeneExecutionStats {
 impl GeneExecutionStats {
     pub fn new() -> Self {
         Self {
-            eval_count: Cell::new(0),
-            eval_true_count: Cell::new(0),
+            eval_count: StatCounter::new(0),
+            eval_true_count: StatCounter::new(0),
+            reaction_count: StatCounter::new(0),
             disjunction_expression: DisjunctionExpressionStats::new(),
         }
     }
     pub fn mark_eval(&self) {
-        self.eval_count.set(self.eval_count.get() + 1);
+        self.eval_count.increment();
     }
     pub fn mark_eval_true(&self) {
-        self.eval_true_count.set(self.eval_true_count.get() + 1);
+        self.eval_true_count.increment();
+    }
+    pub fn mark_reaction(&self) {
+        self.reaction_count.increment();
     }
 
     pub fn pct_true(&self) -> f32 {
@@ -198,25 +235,25 @@ impl GeneExecutionStats {
 
 #[derive(Debug, Clone)]
 pub struct DisjunctionExpressionStats {
-    pub eval_count: Cell<usize>, // tracked on the gene stat object
-    pub eval_true_count: Cell<usize>,
+    pub eval_count: StatCounter, // tracked on the gene stat object
+    pub eval_true_count: StatCounter,
     pub conjunctive_expressions: Vec<ConjunctionExpressionStats>,
 }
 
 impl DisjunctionExpressionStats {
     pub fn new() -> Self {
         Self {
-            eval_count: Cell::new(0),
-            eval_true_count: Cell::new(0),
+            eval_count: StatCounter::new(0),
+            eval_true_count: StatCounter::new(0),
             conjunctive_expressions: vec![],
         }
     }
     pub fn mark_eval(&self) {
-        self.eval_count.set(self.eval_count.get() + 1);
+        self.eval_count.increment();
     }
 
     pub fn mark_eval_true(&self) {
-        self.eval_true_count.set(self.eval_true_count.get() + 1);
+        self.eval_true_count.increment();
     }
 
     pub fn pct_true(&self) -> f32 {
@@ -226,8 +263,8 @@ impl DisjunctionExpressionStats {
 
 #[derive(Debug, Clone)]
 pub struct ConjunctionExpressionStats {
-    pub eval_count: Cell<usize>,
-    pub eval_true_count: Cell<usize>,
+    pub eval_count: StatCounter,
+    pub eval_true_count: StatCounter,
 
     pub bool_conditionals: Vec<BooleanVariableStats>,
 }
@@ -235,18 +272,18 @@ pub struct ConjunctionExpressionStats {
 impl ConjunctionExpressionStats {
     pub fn new() -> Self {
         Self {
-            eval_count: Cell::new(0),
-            eval_true_count: Cell::new(0),
+            eval_count: StatCounter::new(0),
+            eval_true_count: StatCounter::new(0),
             bool_conditionals: vec![],
         }
     }
 
     pub fn mark_eval(&self) {
-        self.eval_count.set(self.eval_count.get() + 1);
+        self.eval_count.increment();
     }
 
     pub fn mark_eval_true(&self) {
-        self.eval_true_count.set(self.eval_true_count.get() + 1);
+        self.eval_true_count.increment();
     }
 
     pub fn pct_true(&self) -> f32 {
@@ -256,24 +293,24 @@ impl ConjunctionExpressionStats {
 
 #[derive(Debug, Clone)]
 pub struct BooleanVariableStats {
-    pub eval_count: Cell<usize>,
-    pub eval_true_count: Cell<usize>,
+    pub eval_count: StatCounter,
+    pub eval_true_count: StatCounter,
 }
 
 impl BooleanVariableStats {
     pub fn new() -> Self {
         Self {
-            eval_count: Cell::new(0),
-            eval_true_count: Cell::new(0),
+            eval_count: StatCounter::new(0),
+            eval_true_count: StatCounter::new(0),
         }
     }
 
     pub fn mark_eval(&self) {
-        self.eval_count.set(self.eval_count.get() + 1);
+        self.eval_count.increment();
     }
 
     pub fn mark_eval_true(&self) {
-        self.eval_true_count.set(self.eval_true_count.get() + 1);
+        self.eval_true_count.increment();
     }
 
     pub fn pct_true(&self) -> f32 {
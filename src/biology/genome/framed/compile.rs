@@ -429,6 +429,19 @@ impl<'a> FramedGenomeCompiler<'a> {
             let meta_reaction: ParamedMetaReactionCall = match &MetaReaction::from_val(op_id) {
                 MetaReaction::JumpAheadFrames => ParamedMetaReactionCall::JumpAheadFrames(param1),
                 MetaReaction::SetChannel => ParamedMetaReactionCall::SetChannel(param1),
+                MetaReaction::IncrementRegister => {
+                    ParamedMetaReactionCall::IncrementRegister(param1)
+                }
+                MetaReaction::DecrementRegister => {
+                    ParamedMetaReactionCall::DecrementRegister(param1)
+                }
+                MetaReaction::AddToRegister => {
+                    ParamedMetaReactionCall::AddToRegister(param1, param2)
+                }
+                MetaReaction::CopyRegister => ParamedMetaReactionCall::CopyRegister(param1, param2),
+                MetaReaction::RandomIntoRegister => {
+                    ParamedMetaReactionCall::RandomIntoRegister(param1, param2)
+                }
                 MetaReaction::SetRegister => ParamedMetaReactionCall::SetRegister(param1, param2),
                 MetaReaction::Nil => ParamedMetaReactionCall::Nil,
             };
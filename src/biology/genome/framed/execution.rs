@@ -3,6 +3,7 @@ use super::common::{BooleanVariable, Disjunction, Frame, RegisterId};
 use crate::biology::genome::framed::common::NUM_CHANNELS;
 use crate::biology::unit_behavior::framed::types::*;
 // use crate::chemistry::properties::RawPropertyId;
+use crate::chemistry::helpers::action_points::ActionPointEconomy;
 use crate::chemistry::reactions::ReactionCall;
 use crate::simulation::common::*;
 use std::convert::TryInto;
@@ -26,6 +27,22 @@ pub enum ExecutableGeneOperation {
     JumpAheadFrames(u8),
     SetChannel(u8),
     SetRegister(RegisterId, PhenotypeRegisterValue),
+    IncrementRegister(RegisterId),
+    DecrementRegister(RegisterId),
+    AddToRegister(RegisterId, i32),
+
+    // (from, to)
+    CopyRegister(RegisterId, RegisterId),
+}
+
+/**
+ * Adds val to a register's value, staying within the register's range.  val comes straight from
+ * the genome, so the sum saturates rather than overflowing before it's clamped.
+ */
+fn add_to_register(value: PhenotypeRegisterValue, val: i32) -> PhenotypeRegisterValue {
+    (value as i32)
+        .saturating_add(val)
+        .clamp(0, PhenotypeRegisterValue::MAX as i32) as PhenotypeRegisterValue
 }
 
 pub struct GenomeExecutionContext<'a> {
@@ -36,22 +53,29 @@ pub struct GenomeExecutionContext<'a> {
     pub consumed_compute_points: u64,
     pub allotted_compute_points: u64,
 
+    // the (frame, channel, gene) that called each of the reactions
+    pub reaction_genes: Vec<(usize, usize, usize)>,
+
     pub sensor_context: &'a SensorContext<'a>,
     pub registers: PhenotypeRegisters,
 
-    pub stats: &'a mut FramedGenomeExecutionStats,
+    pub stats: &'a FramedGenomeExecutionStats,
 }
 
 impl<'a> GenomeExecutionContext<'a> {
+    /**
+     * The stats are only ever bumped, so they can be shared between contexts running on
+     * different threads.  They need to be initialized for the frames beforehand, see
+     * `FramedGenomeExecutionStats::new`.
+     */
     pub fn new(
         frames: &'a Vec<Frame>,
         sensor_context: &'a SensorContext,
         registers: PhenotypeRegisters,
         gm: &'a GeneticManifest,
         compute_points: u64,
-        stats: &'a mut FramedGenomeExecutionStats,
+        stats: &'a FramedGenomeExecutionStats,
     ) -> Self {
-        stats.initialize(frames);
         Self {
             stats: stats,
             genetic_manifest: gm,
@@ -62,6 +86,7 @@ impl<'a> GenomeExecutionContext<'a> {
             registers,
             consumed_compute_points: 0,
             allotted_compute_points: compute_points,
+            reaction_genes: vec![],
         }
     }
 
@@ -71,13 +96,10 @@ impl<'a> GenomeExecutionContext<'a> {
         let mut reactions = vec![];
         while self.current_frame < self.frames.len()
             && self.consumed_compute_points < self.allotted_compute_points
+            && !self.economy().is_covered_by(&reactions)
         {
             // println!("frame {:?}", &self.frames[self.current_frame]);
-            let result = self.execute_frame();
-            if result.is_some() {
-                reactions.push(result.unwrap());
-                return reactions; // ie. we can have only one reaction, for right now
-            }
+            self.execute_frame(&mut reactions);
 
             self.current_frame += 1;
         }
@@ -85,7 +107,19 @@ impl<'a> GenomeExecutionContext<'a> {
         reactions
     }
 
-    pub fn execute_frame(&mut self) -> Option<ReactionCall> {
+    fn economy(&self) -> &'a ActionPointEconomy {
+        &self
+            .genetic_manifest
+            .chemistry_manifest
+            .action_point_economy
+    }
+
+    /**
+     * Executes the genes of the current frame, collecting the reactions it calls into
+     * `reactions`.  Execution stops once the reactions cost all of the unit's action points,
+     * which of them it actually performs is decided when they are scheduled.
+     */
+    pub fn execute_frame(&mut self, reactions: &mut Vec<ReactionCall>) {
         let frame = &self.frames[self.current_frame];
 
         let channel =
@@ -110,7 +144,7 @@ impl<'a> GenomeExecutionContext<'a> {
             }}
 
             if self.consumed_compute_points > self.allotted_compute_points {
-                return None;
+                return;
             }
 
             // println!("executing conditional: {:?}", &cond);
@@ -135,7 +169,12 @@ impl<'a> GenomeExecutionContext<'a> {
                 if let Some(result) = _result {
                     match result {
                         ExecutableGeneOperation::ReactionCall(reaction_call) => {
-                            return Some(reaction_call);
+                            reactions.push(reaction_call);
+                            self.reaction_genes.push((self.current_frame, channel, i));
+
+                            if self.economy().is_covered_by(reactions) {
+                                return;
+                            }
                         }
                         ExecutableGeneOperation::JumpAheadFrames(frames) => {
                             self.current_frame += frames as usize;
@@ -148,12 +187,22 @@ impl<'a> GenomeExecutionContext<'a> {
                         ExecutableGeneOperation::SetRegister(reg_id, reg_val) => {
                             self.registers[reg_id] = reg_val;
                         }
+                        ExecutableGeneOperation::IncrementRegister(reg_id) => {
+                            self.registers[reg_id] = self.registers[reg_id].saturating_add(1);
+                        }
+                        ExecutableGeneOperation::DecrementRegister(reg_id) => {
+                            self.registers[reg_id] = self.registers[reg_id].saturating_sub(1);
+                        }
+                        ExecutableGeneOperation::AddToRegister(reg_id, val) => {
+                            self.registers[reg_id] = add_to_register(self.registers[reg_id], val);
+                        }
+                        ExecutableGeneOperation::CopyRegister(from, to) => {
+                            self.registers[to] = self.registers[from];
+                        }
                     }
                 }
             }
         }
-
-        None
     }
     pub fn evaluate_gene_operation_call(
         &mut self,
@@ -163,27 +212,66 @@ impl<'a> GenomeExecutionContext<'a> {
 
         match operation {
             ParamedGeneOperationCall::MetaReaction(meta_reaction) => match meta_reaction {
+                // sensors like nearest_direction can be negative
                 ParamedMetaReactionCall::SetChannel(param) => {
                     let param_val = self.eval_param(&param);
-                    let channel: u8 = (param_val % NUM_CHANNELS as i32).try_into().unwrap();
+                    let channel: u8 = param_val
+                        .rem_euclid(NUM_CHANNELS as i32)
+                        .try_into()
+                        .unwrap();
                     Some(ExecutableGeneOperation::SetChannel(channel))
                 }
                 ParamedMetaReactionCall::JumpAheadFrames(param) => {
-                    let mut frame_count: u8 = (self.eval_param(&param)
-                        % MAX_JUMP_AHEAD_FRAMES as i32)
+                    let mut frame_count: u8 = self
+                        .eval_param(&param)
+                        .rem_euclid(MAX_JUMP_AHEAD_FRAMES as i32)
                         .try_into()
                         .unwrap();
                     Some(ExecutableGeneOperation::JumpAheadFrames(frame_count))
                 }
                 ParamedMetaReactionCall::SetRegister(r, v) => {
-                    let reg_id = (self.eval_param(&r)
-                        % self.genetic_manifest.number_of_registers as i32)
+                    let reg_id = self.eval_register_id(r);
+                    let reg_val = self
+                        .eval_param(&v)
+                        .rem_euclid(u16::MAX as i32)
                         .try_into()
                         .unwrap();
-                    let reg_val = (self.eval_param(&v) % u16::MAX as i32).try_into().unwrap();
 
                     Some(ExecutableGeneOperation::SetRegister(reg_id, reg_val))
                 }
+                ParamedMetaReactionCall::IncrementRegister(r) => Some(
+                    ExecutableGeneOperation::IncrementRegister(self.eval_register_id(r)),
+                ),
+                ParamedMetaReactionCall::DecrementRegister(r) => Some(
+                    ExecutableGeneOperation::DecrementRegister(self.eval_register_id(r)),
+                ),
+                ParamedMetaReactionCall::AddToRegister(r, v) => {
+                    let reg_id = self.eval_register_id(r);
+                    Some(ExecutableGeneOperation::AddToRegister(
+                        reg_id,
+                        self.eval_param(v),
+                    ))
+                }
+                ParamedMetaReactionCall::CopyRegister(from, to) => {
+                    let from = self.eval_register_id(from);
+                    let to = self.eval_register_id(to);
+                    Some(ExecutableGeneOperation::CopyRegister(from, to))
+                }
+                ParamedMetaReactionCall::RandomIntoRegister(r, max) => {
+                    use rand::Rng;
+
+                    let reg_id = self.eval_register_id(r);
+                    let max = self.eval_param(max).clamp(0, u16::MAX as i32);
+                    let val = if max == 0 {
+                        0
+                    } else {
+                        rand::thread_rng().gen_range(0..max)
+                    };
+                    Some(ExecutableGeneOperation::SetRegister(
+                        reg_id,
+                        val as PhenotypeRegisterValue,
+                    ))
+                }
 
                 ParamedMetaReactionCall::Nil => None,
             },
@@ -195,11 +283,12 @@ impl<'a> GenomeExecutionContext<'a> {
                 let param_val3 = self.eval_param(&paramed_reaction_call.3);
 
                 //flog!("REACTION TO EXECUTE: {:?}", paramed_reaction_call);
+                // sensors like nearest_direction can be negative
                 Some(ExecutableGeneOperation::ReactionCall((
                     paramed_reaction_call.0,
-                    (param_val1 % (u16::MAX as i32)).try_into().unwrap(),
-                    (param_val2 % (u16::MAX as i32)).try_into().unwrap(),
-                    (param_val3 % (u16::MAX as i32)).try_into().unwrap(),
+                    param_val1.rem_euclid(u16::MAX as i32).try_into().unwrap(),
+                    param_val2.rem_euclid(u16::MAX as i32).try_into().unwrap(),
+                    param_val3.rem_euclid(u16::MAX as i32).try_into().unwrap(),
                 )))
             }
             ParamedGeneOperationCall::Nil => None,
@@ -288,6 +377,14 @@ impl<'a> GenomeExecutionContext<'a> {
             false
         }
     }
+    /**
+     * Any value names a register, wrapping around the number of registers.
+     */
+    pub fn eval_register_id(&mut self, parsed_param: &ParsedGenomeParam) -> RegisterId {
+        self.eval_param(parsed_param)
+            .rem_euclid(self.genetic_manifest.number_of_registers as i32) as RegisterId
+    }
+
     pub fn eval_param(&mut self, parsed_param: &ParsedGenomeParam) -> i32 {
         use rand::Rng;
 
@@ -300,10 +397,7 @@ impl<'a> GenomeExecutionContext<'a> {
             }
 
             ParsedGenomeParam::Register(register_id) => {
-                // panic!("registers not supported yet");
-                // TODO!
-                //self.registers[*register_id as usize] as i32
-                0
+                self.registers[*register_id as usize] as i32
             }
             ParsedGenomeParam::Random(max_val) => {
                 let mut rng = rand::thread_rng();
@@ -357,14 +451,19 @@ pub mod tests {
             unit_behavior::framed::GenomeExecutionContext,
         },
         simulation::common::{
-            helpers::place_units::PlaceUnitsMethod, properties::CheeseChemistry,
-            variants::FooChemistry, Chemistry, ChemistryConfiguration, ChemistryInstance,
-            GeneticManifest, NullBehavior, SensorContext, SimulationBuilder, UnitEntry,
-            UnitManifest,
+            builder::ChemistryBuilder, helpers::place_units::PlaceUnitsMethod,
+            properties::CheeseChemistry, variants::FooChemistry, Chemistry, ChemistryConfigBuilder,
+            ChemistryConfiguration, ChemistryInstance, GeneticManifest, NullBehavior,
+            SensorContext, SimulationBuilder, UnitEntry, UnitManifest,
         },
     };
 
     use super::super::common::*;
+    use super::add_to_register;
+    use crate::biology::unit_behavior::framed::types::PhenotypeRegisterValue;
+    use crate::chemistry::helpers::action_points::{
+        schedule_unit_reactions, ReactionConflictPolicy, ReactionOrdering,
+    };
 
     pub fn sim_builder(chemistry: ChemistryInstance) -> SimulationBuilder {
         SimulationBuilder::default()
@@ -381,8 +480,8 @@ pub mod tests {
     #[test]
     pub fn test_set_register() {
         let chemistry = FooChemistry::construct_with_default_config();
-        let gm =
-            GeneticManifest::construct::<CheeseChemistry>(&ChemistryConfiguration::new()).wrap_rc();
+        let gm = GeneticManifest::construct::<CheeseChemistry>(&ChemistryConfiguration::new())
+            .wrap_arc();
 
         let mut frame1 = frame_from_single_channel(vec![
             gene(
@@ -422,7 +521,7 @@ pub mod tests {
         let sim = sim_builder(chemistry).to_simulation();
         let sensor_context = SensorContext::from(&sim.world, &sim.attributes, &(1, 1));
 
-        let mut stats = FramedGenomeExecutionStats::empty();
+        let stats = FramedGenomeExecutionStats::new(&compiled.frames);
 
         let mut execution = GenomeExecutionContext::new(
             &compiled.frames,
@@ -430,7 +529,7 @@ pub mod tests {
             registers,
             &gm,
             10000,
-            &mut stats,
+            &stats,
         );
         let result = execution.execute();
 
@@ -441,6 +540,63 @@ pub mod tests {
         assert_eq!(execution.registers, vec![111, 101, 102, 103, 104]);
     }
 
+    #[test]
+    pub fn test_register_arithmetic() {
+        let chemistry = FooChemistry::construct_with_default_config();
+        let gm = GeneticManifest::construct::<CheeseChemistry>(&ChemistryConfiguration::new())
+            .wrap_arc();
+
+        let always = || if_any(vec![if_all(vec![conditional!(is_truthy, 1)])]);
+        let mut frame1 = frame_from_single_channel(vec![
+            gene(always(), then_do!(set_register, 0, 5)),
+            gene(always(), then_do!(increment_register, 0)),
+            gene(always(), then_do!(increment_register, 0)),
+            gene(always(), then_do!(decrement_register, 1)),
+            gene(always(), then_do!(add_to_register, 2, register(0))),
+            gene(always(), then_do!(copy_register, 2, 3)),
+            gene(always(), then_do!(random_into_register, 4, 10)),
+            gene(
+                if_any(vec![if_all(vec![conditional!(gt, register(3), 6)])]),
+                then_do!(set_register, 1, 42),
+            ),
+        ])
+        .build(&gm);
+
+        let mut genome_words = vec![];
+        genome_words.append(&mut frame1);
+        let compiled = FramedGenomeCompiler::compile(genome_words, &gm);
+
+        let rendered = compiled.display(&gm);
+        assert!(rendered.contains("IncrementRegister(Constant(0))"));
+        assert!(rendered.contains("CopyRegister(Constant(2), Constant(3))"));
+
+        let sim = sim_builder(chemistry).to_simulation();
+        let sensor_context = SensorContext::from(&sim.world, &sim.attributes, &(1, 1));
+        let stats = FramedGenomeExecutionStats::new(&compiled.frames);
+        let mut execution = GenomeExecutionContext::new(
+            &compiled.frames,
+            &sensor_context,
+            gm.empty_registers(),
+            &gm,
+            10000,
+            &stats,
+        );
+        execution.execute();
+
+        // decrementing an empty register leaves it at zero
+        assert_eq!(execution.registers[0..4], [7, 42, 7, 7]);
+        assert!(execution.registers[4] < 10);
+    }
+
+    #[test]
+    pub fn test_add_to_register_bounds() {
+        let max = PhenotypeRegisterValue::MAX;
+        assert_eq!(add_to_register(5, 3), 8);
+        assert_eq!(add_to_register(5, -10), 0);
+        assert_eq!(add_to_register(max, i32::MAX), max);
+        assert_eq!(add_to_register(0, i32::MIN), 0);
+    }
+
     #[test]
     pub fn test_set_channel() {
         let chemistry = FooChemistry::construct_with_default_config();
@@ -485,14 +641,14 @@ pub mod tests {
         let sim = sim_builder(chemistry).to_simulation();
         let sensor_context = SensorContext::from(&sim.world, &sim.attributes, &(1, 1));
 
-        let mut stats = FramedGenomeExecutionStats::empty();
+        let stats = FramedGenomeExecutionStats::new(&compiled.frames);
         let mut execution = GenomeExecutionContext::new(
             &compiled.frames,
             &sensor_context,
             registers,
             &gm,
             10000,
-            &mut stats,
+            &stats,
         );
         let result = execution.execute();
 
@@ -572,14 +728,14 @@ pub mod tests {
         let sim = sim_builder(chemistry).to_simulation();
         let sensor_context = SensorContext::from(&sim.world, &sim.attributes, &(1, 1));
 
-        let mut stats = FramedGenomeExecutionStats::empty();
+        let stats = FramedGenomeExecutionStats::new(&compiled.frames);
         let mut execution = GenomeExecutionContext::new(
             &compiled.frames,
             &sensor_context,
             registers,
             &gm,
             10000,
-            &mut stats,
+            &stats,
         );
         let result = execution.execute();
 
@@ -626,4 +782,162 @@ pub mod tests {
             0
         );
     }
+
+    #[test]
+    pub fn test_multiple_reactions_per_tick() {
+        let execute_with_config = |config: ChemistryConfiguration| -> (Vec<usize>, Vec<usize>) {
+            let chemistry = ChemistryBuilder::with_key("cheese").config(config).build();
+            let gm = GeneticManifest::from_chemistry(&chemistry);
+
+            let raw_genome = framed_genome(vec![frame(
+                vec![
+                    gene(
+                        if_any(vec![if_all(vec![conditional!(true)])]),
+                        then_do!(move_unit, 1, 0, 0),
+                    ),
+                    gene(
+                        if_any(vec![if_all(vec![conditional!(true)])]),
+                        then_do!(make_cheese, 0, 0, 0),
+                    ),
+                    gene(
+                        if_any(vec![if_all(vec![conditional!(true)])]),
+                        then_do!(move_unit, 2, 0, 0),
+                    ),
+                ],
+                vec![],
+                vec![],
+                vec![],
+            )])
+            .build(&gm);
+
+            let compiled = FramedGenomeCompiler::compile(raw_genome, &gm);
+            let registers = gm.empty_registers();
+
+            let sim = sim_builder(chemistry).to_simulation();
+            let sensor_context = SensorContext::from(&sim.world, &sim.attributes, &(1, 1));
+
+            let stats = FramedGenomeExecutionStats::new(&compiled.frames);
+            let mut execution = GenomeExecutionContext::new(
+                &compiled.frames,
+                &sensor_context,
+                registers,
+                &gm,
+                10000,
+                &stats,
+            );
+            let reactions = execution.execute();
+
+            let eval_counts = execution.stats.frames[0].channels[0]
+                .genes
+                .iter()
+                .map(|gene| gene.eval_count.get())
+                .collect::<Vec<_>>();
+
+            // the genome stops once its reactions cost all of the action points
+            let scheduled = schedule_unit_reactions(
+                &reactions,
+                &gm.chemistry_manifest.action_point_economy,
+                &ReactionOrdering::AsEmitted,
+                &ReactionConflictPolicy::ExecuteAll,
+                0,
+            );
+            assert_eq!(scheduled, reactions);
+
+            (
+                eval_counts,
+                reactions.iter().map(|r| r.1 as usize).collect(),
+            )
+        };
+
+        // by default only the first gene gets to call its reaction
+        assert_eq!(
+            execute_with_config(ChemistryConfiguration::new()),
+            (vec![1, 0, 0], vec![1])
+        );
+
+        let config = ChemistryConfigBuilder::new()
+            .set_integer("action_points_per_tick", 3)
+            .set_integer("reaction_cost::make_cheese", 2)
+            .build();
+        assert_eq!(execute_with_config(config), (vec![1, 1, 0], vec![1, 0]));
+    }
+
+    #[test]
+    pub fn test_negative_sensor_params() {
+        use super::ExecutableGeneOperation;
+        use crate::biology::unit_behavior::framed::types::{
+            ParamedGeneOperationCall, ParamedMetaReactionCall, ParsedGenomeParam,
+        };
+
+        let chemistry = ChemistryBuilder::with_key("cheese").build();
+        let gm = GeneticManifest::from_chemistry(&chemistry);
+        let milk = chemistry.get_manifest().position_resource_by_key("milk").id;
+
+        let mut sim = sim_builder(chemistry).to_simulation();
+        for x in 0..5 {
+            for y in 0..5 {
+                sim.world.set_pos_resource_at(&(x, y), milk, 0);
+            }
+        }
+        let sensor_context = SensorContext::from(&sim.world, &sim.attributes, &(1, 1));
+
+        // there's no milk around, so the sensor reads -1
+        let sensor = gm
+            .sensor_manifest
+            .identify_sensor_from_key("nearest_direction(pos_res::milk, 3)")
+            .unwrap();
+        assert_eq!(sensor.calculate(&sensor_context), -1);
+        let negative = || ParsedGenomeParam::SensorLookup(sensor.id);
+
+        let frames = vec![];
+        let stats = FramedGenomeExecutionStats::new(&frames);
+        let mut execution = GenomeExecutionContext::new(
+            &frames,
+            &sensor_context,
+            gm.empty_registers(),
+            &gm,
+            10000,
+            &stats,
+        );
+        let mut evaluate = |meta_reaction| {
+            execution.evaluate_gene_operation_call(&ParamedGeneOperationCall::MetaReaction(
+                meta_reaction,
+            ))
+        };
+
+        assert!(matches!(
+            evaluate(ParamedMetaReactionCall::SetChannel(negative())),
+            Some(ExecutableGeneOperation::SetChannel(3))
+        ));
+        assert!(matches!(
+            evaluate(ParamedMetaReactionCall::JumpAheadFrames(negative())),
+            Some(ExecutableGeneOperation::JumpAheadFrames(2))
+        ));
+        assert!(matches!(
+            evaluate(ParamedMetaReactionCall::SetRegister(negative(), negative())),
+            Some(ExecutableGeneOperation::SetRegister(4, 65534))
+        ));
+        assert!(matches!(
+            evaluate(ParamedMetaReactionCall::AddToRegister(
+                negative(),
+                negative()
+            )),
+            Some(ExecutableGeneOperation::AddToRegister(4, -1))
+        ));
+        assert!(matches!(
+            evaluate(ParamedMetaReactionCall::RandomIntoRegister(
+                negative(),
+                negative()
+            )),
+            Some(ExecutableGeneOperation::SetRegister(4, 0))
+        ));
+
+        let reaction = ParamedGeneOperationCall::Reaction((0, negative(), negative(), negative()));
+        assert!(matches!(
+            execution.evaluate_gene_operation_call(&reaction),
+            Some(ExecutableGeneOperation::ReactionCall((
+                0, 65534, 65534, 65534
+            )))
+        ));
+    }
 }
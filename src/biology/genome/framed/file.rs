@@ -0,0 +1,393 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::biology::genetic_manifest::{GeneticManifest, GeneticManifestData};
+
+use super::translate::GenomeTranslator;
+use super::types::{FramedGenomeValue, RawFramedGenome};
+
+/**
+ * Version 2 added the register arithmetic meta reactions.  Meta reaction ids in version 1 files
+ * are read modulo the original four when they're loaded.  Version 3 reads register params as the
+ * register's value, they read as 0 before, so older files have them replaced with constant 0
+ * params when they're loaded.
+ */
+pub const GENOME_FILE_VERSION: u32 = 3;
+const VERSION_1_NUM_META_REACTIONS: FramedGenomeValue = 4;
+
+/**
+ * Raw genomes saved together with the manifest they were evolved against.  A raw genome is only
+ * meaningful next to the manifest that compiled it, since sensors, operators and reactions are
+ * referred to by position.
+ */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GenomeFile {
+    pub version: u32,
+    pub manifest: GeneticManifestData,
+    pub genomes: Vec<RawFramedGenome>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ManifestMismatch {
+    Sensor {
+        id: usize,
+        saved: Option<String>,
+        current: Option<String>,
+    },
+    Operator {
+        id: usize,
+        saved: Option<String>,
+        current: Option<String>,
+    },
+    Reaction {
+        id: usize,
+        saved: Option<String>,
+        current: Option<String>,
+    },
+    OperatorParams {
+        key: String,
+        saved: usize,
+        current: usize,
+    },
+    NumberOfRegisters {
+        saved: usize,
+        current: usize,
+    },
+}
+
+impl std::fmt::Display for ManifestMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let key = |key: &Option<String>| key.clone().unwrap_or("(none)".to_string());
+
+        match self {
+            Self::Sensor { id, saved, current } => {
+                write!(f, "sensor {}: {} -> {}", id, key(saved), key(current))
+            }
+            Self::Operator { id, saved, current } => {
+                write!(f, "operator {}: {} -> {}", id, key(saved), key(current))
+            }
+            Self::Reaction { id, saved, current } => {
+                write!(f, "reaction {}: {} -> {}", id, key(saved), key(current))
+            }
+            Self::OperatorParams {
+                key,
+                saved,
+                current,
+            } => {
+                write!(f, "params of operator {}: {} -> {}", key, saved, current)
+            }
+            Self::NumberOfRegisters { saved, current } => {
+                write!(f, "number of registers: {} -> {}", saved, current)
+            }
+        }
+    }
+}
+
+/**
+ * Lists every position where the two key lists disagree, including positions that only exist
+ * in one of them.
+ */
+fn key_mismatches<F>(saved: &[String], current: &[String], f: F) -> Vec<ManifestMismatch>
+where
+    F: Fn(usize, Option<String>, Option<String>) -> ManifestMismatch,
+{
+    (0..saved.len().max(current.len()))
+        .filter(|i| saved.get(*i) != current.get(*i))
+        .map(|i| f(i, saved.get(i).cloned(), current.get(i).cloned()))
+        .collect()
+}
+
+/**
+ * The ways in which a genome written against saved would be read differently against current.
+ */
+pub fn manifest_mismatches(
+    saved: &GeneticManifestData,
+    current: &GeneticManifestData,
+) -> Vec<ManifestMismatch> {
+    let mut mismatches = vec![];
+
+    mismatches.extend(key_mismatches(
+        &saved.sensor_keys(),
+        &current.sensor_keys(),
+        |id, saved, current| ManifestMismatch::Sensor { id, saved, current },
+    ));
+    mismatches.extend(key_mismatches(
+        &saved.operator_keys(),
+        &current.operator_keys(),
+        |id, saved, current| ManifestMismatch::Operator { id, saved, current },
+    ));
+    mismatches.extend(key_mismatches(
+        &saved.reaction_keys(),
+        &current.reaction_keys(),
+        |id, saved, current| ManifestMismatch::Reaction { id, saved, current },
+    ));
+
+    for saved_op in saved.operator_manifest.operators.iter() {
+        let current_op = current
+            .operator_manifest
+            .operators
+            .iter()
+            .find(|op| op.name == saved_op.name);
+        if let Some(current_op) = current_op {
+            if current_op.num_params != saved_op.num_params {
+                mismatches.push(ManifestMismatch::OperatorParams {
+                    key: saved_op.name.clone(),
+                    saved: saved_op.num_params,
+                    current: current_op.num_params,
+                });
+            }
+        }
+    }
+
+    if saved.number_of_registers != current.number_of_registers {
+        mismatches.push(ManifestMismatch::NumberOfRegisters {
+            saved: saved.number_of_registers,
+            current: current.number_of_registers,
+        });
+    }
+
+    mismatches
+}
+
+impl GenomeFile {
+    pub fn new(genomes: Vec<RawFramedGenome>, gm: &GeneticManifest) -> Self {
+        Self {
+            version: GENOME_FILE_VERSION,
+            manifest: GeneticManifestData::from_manifest(gm),
+            genomes,
+        }
+    }
+
+    pub fn to_ron(&self) -> String {
+        ron::to_string(self).unwrap()
+    }
+
+    pub fn from_ron(s: &str) -> Self {
+        let file: Self = ron::from_str(s).unwrap_or_else(|e| panic!("Invalid genome file: {}", e));
+        if file.version > GENOME_FILE_VERSION {
+            panic!(
+                "Genome file version {} is newer than the supported version {}",
+                file.version, GENOME_FILE_VERSION
+            );
+        }
+        file
+    }
+
+    pub fn save(&self, path: &Path) {
+        std::fs::write(path, self.to_ron())
+            .unwrap_or_else(|e| panic!("Could not write genome file {:?}: {}", path, e));
+    }
+
+    pub fn load(path: &Path) -> Self {
+        let s = std::fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("Could not read genome file {:?}: {}", path, e));
+        Self::from_ron(&s)
+    }
+
+    pub fn mismatches(&self, gm: &GeneticManifest) -> Vec<ManifestMismatch> {
+        manifest_mismatches(&self.manifest, &GeneticManifestData::from_manifest(gm))
+    }
+
+    /**
+     * The saved genomes, as long as gm reads them the same way the saved manifest did.
+     */
+    pub fn genomes_for(&self, gm: &GeneticManifest) -> Vec<RawFramedGenome> {
+        if self.version != GENOME_FILE_VERSION {
+            panic!(
+                "Genome file version {} needs to be translated to version {}",
+                self.version, GENOME_FILE_VERSION
+            );
+        }
+
+        let mismatches = self.mismatches(gm);
+        if !mismatches.is_empty() {
+            panic!(
+                "Genome file was saved against a different manifest (chemistry {}):\n{}",
+                self.manifest.chemistry_manifest.chemistry_key,
+                mismatches
+                    .iter()
+                    .map(|m| format!("\t{}", m))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            );
+        }
+
+        self.genomes.clone()
+    }
+
+    /**
+     * Like genomes_for, but genomes saved against a different manifest are translated to gm by
+     * key.  Only refuses when a genome uses something gm doesn't have.  Also returns how many
+     * genomes were translated, which is 0 when the file already matches gm.
+     */
+    pub fn remapped_genomes_for(&self, gm: &GeneticManifest) -> (Vec<RawFramedGenome>, usize) {
+        if self.mismatches(gm).is_empty() && self.version == GENOME_FILE_VERSION {
+            return (self.genomes.clone(), 0);
+        }
+
+        let mut translator =
+            GenomeTranslator::new(&self.manifest, &GeneticManifestData::from_manifest(gm));
+        if self.version == 1 {
+            translator = translator.with_num_meta_reactions(VERSION_1_NUM_META_REACTIONS);
+        }
+        if self.version <= 2 {
+            translator = translator.with_registers_read_as_zero();
+        }
+        let translations = self
+            .genomes
+            .iter()
+            .map(|genome| translator.translate(genome))
+            .collect::<Vec<_>>();
+
+        let untranslatable = translations
+            .iter()
+            .enumerate()
+            .flat_map(|(i, translation)| {
+                translation
+                    .untranslatable
+                    .iter()
+                    .map(move |u| format!("\tgenome {}: {}", i, u))
+            })
+            .collect::<Vec<_>>();
+        if !untranslatable.is_empty() {
+            panic!(
+                "Genome file can't be translated from chemistry {}:\n{}",
+                self.manifest.chemistry_manifest.chemistry_key,
+                untranslatable.join("\n")
+            );
+        }
+
+        let num_remapped = translations.len();
+        let genomes = translations
+            .into_iter()
+            .map(|translation| translation.genome)
+            .collect();
+
+        (genomes, num_remapped)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::biology::experiments::util::random_genome_of_length;
+    use crate::chemistry::builder::ChemistryBuilder;
+
+    fn gm(chemistry_key: &str) -> GeneticManifest {
+        GeneticManifest::from_chemistry(&ChemistryBuilder::with_key(chemistry_key).build())
+    }
+
+    #[test]
+    fn round_trip() {
+        let gm = gm("cheese");
+        let genomes = vec![random_genome_of_length(20), random_genome_of_length(5)];
+
+        let file = GenomeFile::from_ron(&GenomeFile::new(genomes.clone(), &gm).to_ron());
+        assert_eq!(file.version, GENOME_FILE_VERSION);
+        assert_eq!(file.manifest.chemistry_manifest.chemistry_key, "cheese");
+        assert!(file.mismatches(&gm).is_empty());
+        assert_eq!(file.genomes_for(&gm), genomes);
+    }
+
+    #[test]
+    fn mismatches() {
+        let gm = gm("cheese");
+        let mut file = GenomeFile::new(vec![random_genome_of_length(5)], &gm);
+
+        let removed = file.manifest.sensor_manifest.sensors.remove(0);
+        file.manifest.number_of_registers += 1;
+
+        let mismatches = file.mismatches(&gm);
+        assert_eq!(
+            mismatches[0],
+            ManifestMismatch::Sensor {
+                id: 0,
+                saved: Some(file.manifest.sensor_manifest.sensors[0].key.clone()),
+                current: Some(removed.key),
+            }
+        );
+        assert_eq!(
+            mismatches.last().unwrap(),
+            &ManifestMismatch::NumberOfRegisters {
+                saved: gm.number_of_registers + 1,
+                current: gm.number_of_registers,
+            }
+        );
+
+        let mut file = GenomeFile::new(vec![random_genome_of_length(5)], &gm);
+        let is_even = file.manifest.operator_manifest.by_string_key["is_even"];
+        file.manifest.operator_manifest.operators[is_even].num_params = 0;
+        assert_eq!(
+            file.mismatches(&gm),
+            vec![ManifestMismatch::OperatorParams {
+                key: "is_even".to_string(),
+                saved: 0,
+                current: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn remapping() {
+        use crate::biology::genome::framed::samples::sample_genome_by_name;
+
+        let gm = gm("cheese");
+        let genome = sample_genome_by_name("cheese::get_genome2", &gm);
+        let mut file = GenomeFile::new(vec![genome.clone()], &gm);
+        assert_eq!(file.remapped_genomes_for(&gm), (vec![genome.clone()], 0));
+
+        // as if the sensors had been reordered since the file was saved
+        file.manifest.sensor_manifest.sensors.reverse();
+        assert_ne!(file.remapped_genomes_for(&gm).0, vec![genome]);
+    }
+
+    #[test]
+    fn version_1_meta_reactions() {
+        use crate::biology::genome::framed::common::*;
+
+        let gm = gm("cheese");
+        let genome = frame_from_single_channel(vec![gene(
+            if_any(vec![if_all(vec![conditional!(is_truthy, 1)])]),
+            then_do!(set_channel, 1),
+        )])
+        .build(&gm);
+
+        // the meta reaction id comes after the frame's metadata, the clause headers and the
+        // conditional.  version 1 read 7 as set_channel
+        let mut old_genome = genome.clone();
+        old_genome[15] += 4;
+
+        let mut file = GenomeFile::new(vec![old_genome], &gm);
+        file.version = 1;
+        assert_eq!(file.remapped_genomes_for(&gm), (vec![genome], 1));
+    }
+
+    #[test]
+    fn version_2_register_params() {
+        use crate::biology::genome::framed::common::*;
+
+        let gm = gm("cheese");
+        let genome = frame_from_single_channel(vec![gene(
+            if_any(vec![if_all(vec![conditional!(gt, register(2), 5)])]),
+            then_do!(make_cheese, register(1)),
+        )])
+        .build(&gm);
+        let translated = frame_from_single_channel(vec![gene(
+            if_any(vec![if_all(vec![conditional!(gt, 0, 5)])]),
+            then_do!(make_cheese, 0),
+        )])
+        .build(&gm);
+
+        let mut file = GenomeFile::new(vec![genome], &gm);
+        file.version = 2;
+        assert_eq!(file.remapped_genomes_for(&gm), (vec![translated], 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "different manifest")]
+    fn refuses_other_chemistry() {
+        let file = GenomeFile::new(vec![random_genome_of_length(5)], &gm("lever"));
+        file.genomes_for(&gm("cheese"));
+    }
+}
@@ -15,7 +15,9 @@ pub mod render;
 pub mod compile;
 
 pub mod execution;
+pub mod file;
 pub mod samples;
+pub mod translate;
 
 pub mod common {
     pub use crate::biology::genome::framed::builders::*;
@@ -4,7 +4,7 @@ use crate::simulation::common::*;
 
 use crate::biology::genome::framed::builders::*;
 
-pub fn get_genome1(gm: &GeneticManifest) -> Rc<CompiledFramedGenome> {
+pub fn get_genome1(gm: &GeneticManifest) -> Arc<CompiledFramedGenome> {
     let framed_vals = frame_from_single_channel(vec![
         gene(
             if_any(vec![if_all(vec![
@@ -24,7 +24,7 @@ pub fn get_genome1(gm: &GeneticManifest) -> Rc<CompiledFramedGenome> {
     ])
     .build(&gm);
 
-    FramedGenomeCompiler::compile(framed_vals, &gm).wrap_rc()
+    FramedGenomeCompiler::compile(framed_vals, &gm).wrap_arc()
 }
 
 pub fn get_genome2_raw(gm: &GeneticManifest) -> Vec<u64> {
@@ -72,8 +72,8 @@ pub fn get_genome2_raw(gm: &GeneticManifest) -> Vec<u64> {
     .build(&gm)
 }
 
-pub fn get_genome2(gm: &GeneticManifest) -> Rc<CompiledFramedGenome> {
-    FramedGenomeCompiler::compile(get_genome2_raw(gm), &gm.clone()).wrap_rc()
+pub fn get_genome2(gm: &GeneticManifest) -> Arc<CompiledFramedGenome> {
+    FramedGenomeCompiler::compile(get_genome2_raw(gm), &gm.clone()).wrap_arc()
 }
 
 // pub fn get_genome2() -> GenomeBuilderLegacy {
@@ -1,3 +1,20 @@
 pub mod cheese;
 pub mod legacy;
 pub mod lever;
+
+use crate::biology::genome::framed::common::RawFramedGenome;
+use crate::simulation::common::GeneticManifest;
+
+/**
+ * Looks up a sample genome by its module and function name, eg. "cheese::get_genome2".  The
+ * samples are written for a specific chemistry, so the manifest has to be of that chemistry.
+ */
+pub fn sample_genome_by_name(name: &str, gm: &GeneticManifest) -> RawFramedGenome {
+    match name {
+        "cheese::get_genome1" => cheese::get_genome1(gm).raw_values.clone(),
+        "cheese::get_genome2" => cheese::get_genome2_raw(gm),
+        "cheese::get_genome3" => cheese::get_genome3(gm),
+        "lever::genome1" => lever::genome1(gm).raw_values.clone(),
+        _ => panic!("Unknown sample genome: {}", name),
+    }
+}
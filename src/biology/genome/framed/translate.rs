@@ -0,0 +1,403 @@
+use crate::biology::genetic_manifest::{GeneticManifest, GeneticManifestData};
+
+use super::convert::{
+    merge_value_into_word, operation, param_meta, val_to_n_and_clauses, val_to_n_or_clauses,
+    RawFrameParser,
+};
+use super::types::{
+    FramedGenomeValue, RawFramedGenome, FIXED_NUM_CONDITIONAL_PARAMS, FIXED_NUM_OPERATION_PARAMS,
+    FRAME_META_DATA_SIZE, NUM_CHANNELS, NUM_META_REACTIONS,
+};
+
+/**
+ * Something a genome refers to that the target manifest doesn't have.  The address is the
+ * index of the word holding the value.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub enum Untranslatable {
+    Sensor { address: usize, key: String },
+    Operator { address: usize, key: String },
+    Reaction { address: usize, key: String },
+    Register { address: usize, register: usize },
+}
+
+impl std::fmt::Display for Untranslatable {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Sensor { address, key } => write!(f, "sensor {} at word {}", key, address),
+            Self::Operator { address, key } => write!(f, "operator {} at word {}", key, address),
+            Self::Reaction { address, key } => write!(f, "reaction {} at word {}", key, address),
+            Self::Register { address, register } => {
+                write!(f, "register {} at word {}", register, address)
+            }
+        }
+    }
+}
+
+pub struct GenomeTranslation {
+    pub genome: RawFramedGenome,
+
+    // values that could not be translated are left as they were
+    pub untranslatable: Vec<Untranslatable>,
+}
+
+/**
+ * Maps the positional ids a genome was written with onto another manifest's ids, matching
+ * sensors, operators and reactions by key.
+ */
+struct IdMap {
+    keys: Vec<String>,
+    to_ids: Vec<Option<usize>>,
+}
+
+impl IdMap {
+    fn new(from_keys: Vec<String>, to_keys: &[String]) -> Self {
+        Self {
+            to_ids: from_keys
+                .iter()
+                .map(|key| to_keys.iter().position(|k| k == key))
+                .collect(),
+            keys: from_keys,
+        }
+    }
+
+    /**
+     * The compiler reads ids modulo the number of entries, so the value is first resolved the
+     * same way against the source manifest.
+     */
+    fn translate(&self, val: FramedGenomeValue) -> Result<FramedGenomeValue, String> {
+        if self.keys.is_empty() {
+            return Ok(val);
+        }
+
+        let from_id = val as usize % self.keys.len();
+        match self.to_ids[from_id] {
+            Some(to_id) => Ok(to_id as FramedGenomeValue),
+            None => Err(self.keys[from_id].clone()),
+        }
+    }
+}
+
+/**
+ * Genomes refer to sensors, operators and reactions by position and read the ids modulo the
+ * number of entries, so adding to a manifest changes what an existing raw genome reads, even when
+ * the existing entries keep their positions.  Genome files keep the manifest they were saved with
+ * and are translated by key when they're loaded.  Other raw genomes have to be translated from
+ * the manifest they were evolved against, eg. SensorManifest::version_1 or
+ * version_1_default_operators.
+ */
+pub struct GenomeTranslator {
+    sensors: IdMap,
+    operators: IdMap,
+    operator_num_params: Vec<usize>,
+    reactions: IdMap,
+    from_registers: usize,
+    to_registers: usize,
+
+    // meta reactions aren't part of the manifest, but genomes written before some were added
+    // read their ids modulo a smaller number
+    from_num_meta_reactions: FramedGenomeValue,
+
+    // genomes written before registers could be read got 0 for every register param
+    registers_read_as_zero: bool,
+}
+
+/**
+ * Walks the values of one channel in the order the compiler consumes them.  How many values
+ * each part of a gene takes doesn't depend on the manifest, so the walk is the same for both.
+ */
+struct ChannelCursor<'a> {
+    values: &'a [FramedGenomeValue],
+    start_address: usize,
+    idx: usize,
+}
+
+impl<'a> ChannelCursor<'a> {
+    fn pop(&mut self) -> Option<(FramedGenomeValue, usize)> {
+        let val = *self.values.get(self.idx)?;
+        let address = self.start_address + self.idx;
+        self.idx += 1;
+        Some((val, address))
+    }
+
+    fn pop_n(&mut self, n: usize) -> Option<Vec<(FramedGenomeValue, usize)>> {
+        let popped = (0..n).filter_map(|_| self.pop()).collect::<Vec<_>>();
+        if popped.len() == n {
+            Some(popped)
+        } else {
+            None
+        }
+    }
+}
+
+impl GenomeTranslator {
+    pub fn new(from: &GeneticManifestData, to: &GeneticManifestData) -> Self {
+        let num_params = |data: &GeneticManifestData| {
+            data.operator_manifest
+                .operators
+                .iter()
+                .map(|op| op.num_params)
+                .collect::<Vec<_>>()
+        };
+        let (operator_num_params, to_num_params) = (num_params(from), num_params(to));
+
+        // an operator that reads a different number of params under the same key doesn't do
+        // the same thing anymore, so it can't be translated
+        let mut operators = IdMap::new(from.operator_keys(), &to.operator_keys());
+        for (from_id, to_id) in operators.to_ids.iter_mut().enumerate() {
+            if to_id.map_or(false, |to_id| {
+                to_num_params[to_id] != operator_num_params[from_id]
+            }) {
+                *to_id = None;
+            }
+        }
+
+        Self {
+            sensors: IdMap::new(from.sensor_keys(), &to.sensor_keys()),
+            operators,
+            operator_num_params,
+            reactions: IdMap::new(from.reaction_keys(), &to.reaction_keys()),
+            from_registers: from.number_of_registers,
+            to_registers: to.number_of_registers,
+            from_num_meta_reactions: NUM_META_REACTIONS,
+            registers_read_as_zero: false,
+        }
+    }
+
+    pub fn with_num_meta_reactions(mut self, from_num_meta_reactions: FramedGenomeValue) -> Self {
+        self.from_num_meta_reactions = from_num_meta_reactions;
+        self
+    }
+
+    /**
+     * Rewrites register params into constant 0 params, which is what they used to read as.
+     */
+    pub fn with_registers_read_as_zero(mut self) -> Self {
+        self.registers_read_as_zero = true;
+        self
+    }
+
+    pub fn between(from: &GeneticManifest, to: &GeneticManifest) -> Self {
+        Self::new(
+            &GeneticManifestData::from_manifest(from),
+            &GeneticManifestData::from_manifest(to),
+        )
+    }
+
+    pub fn translate(&self, genome: &RawFramedGenome) -> GenomeTranslation {
+        let mut translation = GenomeTranslation {
+            genome: genome.clone(),
+            untranslatable: vec![],
+        };
+
+        for frame in RawFrameParser::parse(genome.clone()) {
+            for channel in 0..NUM_CHANNELS {
+                let mut cursor = ChannelCursor {
+                    values: &frame.channel_values[channel],
+                    start_address: frame.address_range.0 + FRAME_META_DATA_SIZE,
+                    idx: 0,
+                };
+
+                let mut writes = vec![];
+                while cursor.idx < cursor.values.len() {
+                    if self
+                        .translate_gene(&mut cursor, &mut writes, &mut translation)
+                        .is_none()
+                    {
+                        break;
+                    }
+                }
+
+                for (address, val) in writes {
+                    translation.genome[address] =
+                        merge_value_into_word(translation.genome[address], val, channel as u8);
+                }
+            }
+        }
+
+        translation
+    }
+
+    fn translate_gene(
+        &self,
+        cursor: &mut ChannelCursor,
+        writes: &mut Vec<(usize, FramedGenomeValue)>,
+        translation: &mut GenomeTranslation,
+    ) -> Option<()> {
+        let (n_or_clauses, _) = cursor.pop()?;
+        cursor.pop()?;
+        for _ in 0..val_to_n_or_clauses(n_or_clauses) {
+            let (n_and_clauses, _) = cursor.pop()?;
+            cursor.pop()?;
+            for _ in 0..val_to_n_and_clauses(n_and_clauses) {
+                self.translate_conditional(cursor, writes, translation)?;
+            }
+        }
+
+        let (op_type, _) = cursor.pop()?;
+        let (op_id, op_address) = cursor.pop()?;
+        if !operation::is_meta_reaction(op_type) {
+            match self.reactions.translate(op_id) {
+                Ok(val) => writes.push((op_address, val)),
+                Err(key) => translation.untranslatable.push(Untranslatable::Reaction {
+                    address: op_address,
+                    key,
+                }),
+            }
+        } else {
+            writes.push((op_address, op_id % self.from_num_meta_reactions));
+        }
+
+        for _ in 0..FIXED_NUM_OPERATION_PARAMS {
+            match cursor.pop_n(2) {
+                Some(param) => self.translate_param(param[0], param[1], writes, translation),
+                None => return None,
+            }
+        }
+
+        Some(())
+    }
+
+    fn translate_conditional(
+        &self,
+        cursor: &mut ChannelCursor,
+        writes: &mut Vec<(usize, FramedGenomeValue)>,
+        translation: &mut GenomeTranslation,
+    ) -> Option<()> {
+        let (operator_val, operator_address) = cursor.pop()?;
+        cursor.pop()?;
+        let params = cursor.pop_n(FIXED_NUM_CONDITIONAL_PARAMS * 2)?;
+
+        match self.operators.translate(operator_val) {
+            Ok(val) => writes.push((operator_address, val)),
+            Err(key) => translation.untranslatable.push(Untranslatable::Operator {
+                address: operator_address,
+                key,
+            }),
+        }
+
+        // only the operator's own params are read, the rest are padding
+        let num_params =
+            self.operator_num_params[operator_val as usize % self.operators.keys.len()];
+        for i in 0..num_params {
+            self.translate_param(params[i * 2], params[i * 2 + 1], writes, translation);
+        }
+
+        Some(())
+    }
+
+    fn translate_param(
+        &self,
+        (meta, meta_address): (FramedGenomeValue, usize),
+        (val, address): (FramedGenomeValue, usize),
+        writes: &mut Vec<(usize, FramedGenomeValue)>,
+        translation: &mut GenomeTranslation,
+    ) {
+        if self.registers_read_as_zero && param_meta::is_register_lookup(meta) {
+            writes.push((
+                meta_address,
+                param_meta::val_for_constant() as FramedGenomeValue,
+            ));
+            writes.push((address, 0));
+        } else if param_meta::is_sensor_lookup(meta) {
+            match self.sensors.translate(val) {
+                Ok(val) => writes.push((address, val)),
+                Err(key) => translation
+                    .untranslatable
+                    .push(Untranslatable::Sensor { address, key }),
+            }
+        } else if param_meta::is_register_lookup(meta) {
+            let register = val as usize % self.from_registers;
+            if register < self.to_registers {
+                writes.push((address, register as FramedGenomeValue));
+            } else {
+                translation
+                    .untranslatable
+                    .push(Untranslatable::Register { address, register });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::biology::experiments::util::random_genome_of_length;
+    use crate::biology::genetic_manifest::predicates::OperatorManifest;
+    use crate::biology::genome::framed::common::FramedGenomeCompiler;
+    use crate::chemistry::builder::ChemistryBuilder;
+
+    fn cheese_gm() -> GeneticManifest {
+        GeneticManifest::from_chemistry(&ChemistryBuilder::with_key("cheese").build())
+    }
+
+    /**
+     * The same sensors, operators and reactions as gm, in reverse order.
+     */
+    fn reversed(gm: &GeneticManifest) -> GeneticManifest {
+        let mut reversed = gm.clone();
+
+        let mut sensor_manifest = gm.sensor_manifest.as_ref().clone();
+        sensor_manifest.sensors.reverse();
+        for (i, sensor) in sensor_manifest.sensors.iter_mut().enumerate() {
+            sensor.id = i;
+        }
+        reversed.sensor_manifest = Arc::new(sensor_manifest);
+
+        let mut operators = gm.operator_manifest.operators.clone();
+        operators.reverse();
+        reversed.operator_manifest = Arc::new(OperatorManifest::new(operators));
+
+        let mut chemistry_manifest = gm.chemistry_manifest.as_ref().clone();
+        chemistry_manifest.reactions.reverse();
+        for (i, reaction) in chemistry_manifest.reactions.iter_mut().enumerate() {
+            reaction.id = i;
+        }
+        reversed.chemistry_manifest = Arc::new(chemistry_manifest);
+
+        reversed
+    }
+
+    #[test]
+    fn translation_preserves_meaning() {
+        let gm = cheese_gm();
+        let other = reversed(&gm);
+        let translator = GenomeTranslator::between(&gm, &other);
+
+        for _ in 0..20 {
+            let genome = random_genome_of_length(60);
+            let translation = translator.translate(&genome);
+            assert!(translation.untranslatable.is_empty());
+
+            assert_eq!(
+                FramedGenomeCompiler::compile(translation.genome, &other).display(&other),
+                FramedGenomeCompiler::compile(genome, &gm).display(&gm)
+            );
+        }
+    }
+
+    #[test]
+    fn reports_missing_keys() {
+        let gm = cheese_gm();
+        let mut other = gm.clone();
+        let mut chemistry_manifest = gm.chemistry_manifest.as_ref().clone();
+        chemistry_manifest.reactions.truncate(1);
+        other.chemistry_manifest = Arc::new(chemistry_manifest);
+
+        let translator = GenomeTranslator::between(&gm, &other);
+        let untranslatable = (0..20)
+            .flat_map(|_| {
+                translator
+                    .translate(&random_genome_of_length(60))
+                    .untranslatable
+            })
+            .collect::<Vec<_>>();
+
+        assert!(!untranslatable.is_empty());
+        assert!(untranslatable
+            .iter()
+            .all(|u| matches!(u, Untranslatable::Reaction { key, .. } if key != &gm.chemistry_manifest.reactions[0].key)));
+    }
+}
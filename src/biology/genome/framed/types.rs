@@ -14,6 +14,7 @@ use crate::simulation::world::World;
 use std::cell::Cell;
 use std::fmt::{Debug, Formatter, Result};
 use std::rc::Rc;
+use std::sync::Arc;
 
 //use crate::biology::genome::framed::util as util;
 
@@ -33,7 +34,7 @@ pub const FIXED_NUM_CONDITIONAL_PARAMS: usize = 3;
 pub const FIXED_NUM_OPERATION_PARAMS: usize = 3;
 
 pub const MIN_FRAME_SIZE: usize = 4;
-pub const NUM_META_REACTIONS: FramedGenomeValue = 4;
+pub const NUM_META_REACTIONS: FramedGenomeValue = 9;
 
 #[derive(Clone)]
 pub struct FramedGenome {
@@ -44,7 +45,7 @@ pub struct FramedGenome {
 // impl FramedGenome {
 //     pub fn from_raw(
 //         raw_frames: RawFramedGenome,
-//         genetic_manifest: Rc<GeneticManifest>,
+//         genetic_manifest: Arc<GeneticManifest>,
 //         // cm: &ChemistryManifest,
 //         // gm: &GeneticManifest,
 //         // sm: &SensorManifest,
@@ -78,8 +79,8 @@ impl CompiledFramedGenome {
         }
     }
 
-    pub fn wrap_rc(self) -> Rc<Self> {
-        Rc::new(self)
+    pub fn wrap_arc(self) -> Arc<Self> {
+        Arc::new(self)
     }
 
     pub fn new_stats(&self) -> FramedGenomeExecutionStats {
@@ -1,11 +1,11 @@
 use serde::{Deserialize, Serialize};
 
 use crate::simulation::common::{
-    Chemistry, ChemistryManifest, Coord, CoordOffset, Property, PropertyId, SimulationAttributes,
-    World,
+    Chemistry, ChemistryManifest, Coord, CoordOffset, GridNeighborhood, Property, PropertyId,
+    SimulationAttributes, World,
 };
 use crate::util::coord_by_coord_offset;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub type SensorValue = i32;
 
@@ -35,7 +35,7 @@ pub struct CustomSensorImplementation {
 }
 
 pub type CustomSensorFunction =
-    Rc<dyn Fn(&World, &SimulationAttributes, &SensorContext) -> SensorValue>;
+    Arc<dyn Fn(&World, &SimulationAttributes, &SensorContext) -> SensorValue + Send + Sync>;
 use std;
 
 pub type CustomSensorId = u16;
@@ -52,6 +52,61 @@ pub enum SensorType {
 
     #[serde(skip_serializing, skip_deserializing)]
     CustomSensorFn(CustomSensorFunction, CustomSensorKey), // TODO: this should hold an ID to a preset list of custom sensor functions
+
+    // properties over every cell within a distance, including the unit's own
+    NeighborhoodAggregate(PropertyId, SensorAggregate, GridNeighborhood, u8),
+
+    // the neighborhood direction number of the closest cell where the property is nonzero,
+    // scanning outward along each direction.  -1 if there isn't one within the distance
+    NearestDirection(PropertyId, GridNeighborhood, u8),
+
+    // units within a distance, not counting the unit itself
+    NeighborUnitCount(UnitSpeciesFilter, GridNeighborhood, u8),
+
+    // the neighborhood direction number of the adjacent cell with the highest value, as long as
+    // it's higher than the unit's own cell.  -1 otherwise
+    GradientDirection(PropertyId, GridNeighborhood),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SensorAggregate {
+    Sum,
+    Max,
+
+    // the number of cells where the property is nonzero
+    Count,
+}
+
+impl SensorAggregate {
+    pub fn key(&self) -> &'static str {
+        match self {
+            SensorAggregate::Sum => "sum",
+            SensorAggregate::Max => "max",
+            SensorAggregate::Count => "count",
+        }
+    }
+}
+
+/**
+ * Species are the units' species names, so the entries bred during a simulation stay in their
+ * parents' species.  Genomes don't know which species they belong to, so units are counted
+ * relative to the sensing unit.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum UnitSpeciesFilter {
+    Any,
+    SameSpecies,
+    OtherSpecies,
+}
+
+impl UnitSpeciesFilter {
+    pub fn key(&self) -> &'static str {
+        match self {
+            UnitSpeciesFilter::Any => "any",
+            UnitSpeciesFilter::SameSpecies => "same_species",
+            UnitSpeciesFilter::OtherSpecies => "other_species",
+        }
+    }
 }
 
 pub type SensorId = usize;
@@ -100,6 +155,18 @@ impl SensorDefinition {
             SensorType::CustomSensor(custom_sensor_key) => {
                 panic!("not implemented")
             }
+            SensorType::NeighborhoodAggregate(prop_id, aggregate, neighborhood, distance) => {
+                calc_neighborhood_aggregate(prop_id, aggregate, neighborhood, *distance, context)
+            }
+            SensorType::NearestDirection(prop_id, neighborhood, distance) => {
+                calc_nearest_direction(prop_id, neighborhood, *distance, context)
+            }
+            SensorType::NeighborUnitCount(filter, neighborhood, distance) => {
+                calc_neighbor_unit_count(filter, neighborhood, *distance, context)
+            }
+            SensorType::GradientDirection(prop_id, neighborhood) => {
+                calc_gradient_direction(prop_id, neighborhood, context)
+            }
         };
 
         0
@@ -180,6 +247,91 @@ pub fn calc_local_chemical_property(
     // SimulationAttributeId(SimulationAttributeIndex)
 }
 
+pub fn calc_neighborhood_aggregate(
+    prop_id: &PropertyId,
+    aggregate: &SensorAggregate,
+    neighborhood: &GridNeighborhood,
+    distance: u8,
+    context: &SensorContext,
+) -> SensorValue {
+    let offsets = neighborhood_sensor_offsets(neighborhood, distance as i32);
+    let values = offsets
+        .iter()
+        .filter_map(|offset| calc_local_chemical_property(prop_id, offset, context));
+
+    match aggregate {
+        SensorAggregate::Sum => values.fold(0, |sum, val| sum.saturating_add(val)),
+        SensorAggregate::Max => values.max().unwrap_or(0),
+        SensorAggregate::Count => values.filter(|val| *val != 0).count() as SensorValue,
+    }
+}
+
+pub fn calc_nearest_direction(
+    prop_id: &PropertyId,
+    neighborhood: &GridNeighborhood,
+    distance: u8,
+    context: &SensorContext,
+) -> SensorValue {
+    for step in 1..=distance as i32 {
+        for (i, (offset, _)) in neighborhood.offsets().iter().enumerate() {
+            let offset = (offset.0 * step, offset.1 * step);
+            if let Some(val) = calc_local_chemical_property(prop_id, &offset, context) {
+                if val != 0 {
+                    return i as SensorValue;
+                }
+            }
+        }
+    }
+
+    -1
+}
+
+pub fn calc_neighbor_unit_count(
+    filter: &UnitSpeciesFilter,
+    neighborhood: &GridNeighborhood,
+    distance: u8,
+    context: &SensorContext,
+) -> SensorValue {
+    let species_id = context
+        .world
+        .get_unit_at(context.coord)
+        .map(|unit| unit.species_id);
+
+    neighborhood_sensor_offsets(neighborhood, distance as i32)
+        .iter()
+        .filter(|offset| **offset != (0, 0))
+        .filter_map(|offset| {
+            coord_by_coord_offset(context.coord, offset.clone(), context.world.size.clone())
+        })
+        .filter_map(|coord| context.world.get_unit_at(&coord))
+        .filter(|unit| match filter {
+            UnitSpeciesFilter::Any => true,
+            UnitSpeciesFilter::SameSpecies => Some(unit.species_id) == species_id,
+            UnitSpeciesFilter::OtherSpecies => Some(unit.species_id) != species_id,
+        })
+        .count() as SensorValue
+}
+
+pub fn calc_gradient_direction(
+    prop_id: &PropertyId,
+    neighborhood: &GridNeighborhood,
+    context: &SensorContext,
+) -> SensorValue {
+    let mut highest = calc_local_chemical_property(prop_id, &(0, 0), context).unwrap_or(0);
+    let mut direction = -1;
+
+    for (i, (offset, _)) in neighborhood.offsets().iter().enumerate() {
+        if let Some(val) = calc_local_chemical_property(prop_id, offset, context) {
+            if val > highest {
+                highest = val;
+                direction = i as SensorValue;
+            }
+        }
+    }
+
+    direction
+}
+
 pub type SensorManifestData = SensorManifest;
 
 /**
@@ -217,6 +369,32 @@ impl SensorManifest {
 
         sensors.append(&mut Self::standard_sensors(chemistry_manifest));
 
+        // after the standard sensors so that the positions of the version 1 sensors don't change
+        sensors.append(&mut Self::construct_neighborhood_sensors(
+            local_properties,
+            chemistry_manifest,
+        ));
+        sensors.append(&mut Self::construct_signal_sensors(chemistry_manifest));
+
+        SensorManifest {
+            sensors: Self::normalize_sensors(sensors),
+        }
+    }
+
+    /**
+     * The sensors as version 1 of the manifest listed them, before the neighborhood sensors were
+     * added and cheese was given three of them.  Raw genomes evolved against it need to be
+     * translated, see GenomeTranslator.
+     */
+    pub fn version_1(
+        chemistry_manifest: &ChemistryManifest,
+        local_properties: &LocalPropertySensorManifest,
+    ) -> Self {
+        let mut sensors =
+            Self::construct_local_property_sensors(local_properties, chemistry_manifest);
+
+        sensors.append(&mut Self::standard_sensors(chemistry_manifest));
+
         SensorManifest {
             sensors: Self::normalize_sensors(sensors),
         }
@@ -247,7 +425,10 @@ impl SensorManifest {
         for local_prop_entry in &local_prop_manifest.entries {
             let property = &chemistry_manifest.all_properties[local_prop_entry.property_offset_idx];
 
-            let offsets = sensor_local_offsets(local_prop_entry.distance.into());
+            let offsets = neighborhood_sensor_offsets(
+                &chemistry_manifest.neighborhood,
+                local_prop_entry.distance.into(),
+            );
 
             let mut _defs = offsets
                 .iter()
@@ -281,6 +462,101 @@ impl SensorManifest {
         //     .collect::<Vec<_>>();
     }
 
+    pub fn construct_neighborhood_sensors(
+        local_prop_manifest: &LocalPropertySensorManifest,
+        chemistry_manifest: &ChemistryManifest,
+    ) -> Vec<SensorDefinition> {
+        let neighborhood = chemistry_manifest.neighborhood;
+
+        local_prop_manifest
+            .neighborhood_entries
+            .iter()
+            .map(|entry| {
+                let (key, prop_key, sensor_type) = match entry {
+                    NeighborhoodSensorEntry::Aggregate {
+                        property_offset_idx,
+                        aggregate,
+                        distance,
+                    } => {
+                        let property = &chemistry_manifest.all_properties[*property_offset_idx];
+                        (
+                            format!("{}({}, {})", aggregate.key(), property.long_key, distance),
+                            property.long_key.clone(),
+                            SensorType::NeighborhoodAggregate(
+                                property.property_id.clone(),
+                                *aggregate,
+                                neighborhood,
+                                *distance,
+                            ),
+                        )
+                    }
+                    NeighborhoodSensorEntry::NearestDirection {
+                        property_offset_idx,
+                        distance,
+                    } => {
+                        let property = &chemistry_manifest.all_properties[*property_offset_idx];
+                        (
+                            format!("nearest_direction({}, {})", property.long_key, distance),
+                            property.long_key.clone(),
+                            SensorType::NearestDirection(
+                                property.property_id.clone(),
+                                neighborhood,
+                                *distance,
+                            ),
+                        )
+                    }
+                    NeighborhoodSensorEntry::UnitCount { filter, distance } => (
+                        format!("unit_count({}, {})", filter.key(), distance),
+                        "unit_count".to_string(),
+                        SensorType::NeighborUnitCount(*filter, neighborhood, *distance),
+                    ),
+                };
+
+                SensorDefinition {
+                    id: 0,
+                    key,
+                    prop_key,
+                    sensor_type,
+                }
+            })
+            .collect::<Vec<_>>()
+    }
+
+    /**
+     * Every signal can be sensed at the unit's own cell and as the direction it gets stronger in,
+     * whether or not its position resource is otherwise visible.
+     */
+    pub fn construct_signal_sensors(
+        chemistry_manifest: &ChemistryManifest,
+    ) -> Vec<SensorDefinition> {
+        chemistry_manifest
+            .signals
+            .iter()
+            .flat_map(|signal| {
+                let property_id = PropertyId::PositionResourceId(signal.position_resource_id);
+                let prop_key = format!("pos_res::{}", signal.resource_key());
+
+                vec![
+                    SensorDefinition {
+                        id: 0,
+                        key: format!("signal::{}", signal.key),
+                        prop_key: prop_key.clone(),
+                        sensor_type: SensorType::LocalChemicalProperty(property_id.clone(), (0, 0)),
+                    },
+                    SensorDefinition {
+                        id: 0,
+                        key: format!("signal_gradient::{}", signal.key),
+                        prop_key,
+                        sensor_type: SensorType::GradientDirection(
+                            property_id,
+                            chemistry_manifest.neighborhood,
+                        ),
+                    },
+                ]
+            })
+            .collect::<Vec<_>>()
+    }
+
     /**
      *  Construct a list of sensors available from the chemistry manifest.
      *  Note, this assumes that *all* chemical properties are visible to the genome.
@@ -355,6 +631,26 @@ impl SensorManifest {
     }
 }
 
+/**
+ * The von neumann and moore neighborhoods both sense the full square around the unit, which
+ * keeps their sensor manifests identical.  The hex neighborhood only senses the cells that are
+ * within `distance` steps on the hex grid.
+ */
+pub fn neighborhood_sensor_offsets(
+    neighborhood: &GridNeighborhood,
+    distance: i32,
+) -> Vec<SensorCoordOffset> {
+    let offsets = sensor_local_offsets(distance);
+
+    match neighborhood {
+        GridNeighborhood::Hex => offsets
+            .into_iter()
+            .filter(|offset| neighborhood.distance(*offset) <= distance as u32)
+            .collect::<Vec<_>>(),
+        _ => offsets,
+    }
+}
+
 pub fn sensor_local_offsets(distance: i32) -> Vec<SensorCoordOffset> {
     let mut coords: Vec<SensorCoordOffset> = vec![];
 
@@ -393,6 +689,7 @@ pub fn sensor_local_offsets(distance: i32) -> Vec<SensorCoordOffset> {
 }
 pub struct LocalPropertySensorManifest {
     entries: Vec<LocalPropertySensorEntry>,
+    neighborhood_entries: Vec<NeighborhoodSensorEntry>,
 }
 
 impl LocalPropertySensorManifest {
@@ -408,6 +705,7 @@ impl LocalPropertySensorManifest {
                     distance: 1,
                 })
                 .collect::<Vec<_>>(),
+            neighborhood_entries: vec![],
         }
     }
     pub fn from_blacklist(blacklisted_prop_keys: &[String], all_properties: &[Property]) -> Self {
@@ -423,6 +721,7 @@ impl LocalPropertySensorManifest {
                     distance: 1,
                 })
                 .collect::<Vec<_>>(),
+            neighborhood_entries: vec![],
         }
     }
     pub fn from_whitelist(
@@ -447,7 +746,54 @@ impl LocalPropertySensorManifest {
             })
             .collect::<Vec<_>>();
 
-        Self { entries }
+        Self {
+            entries,
+            neighborhood_entries: vec![],
+        }
+    }
+
+    fn property_offset_idx(prop_key: &str, all_properties: &[Property]) -> usize {
+        all_properties
+            .iter()
+            .find(|prop| prop.long_key == prop_key)
+            .unwrap_or_else(|| panic!("Unknown sensor property: {}", prop_key))
+            .id
+    }
+
+    pub fn with_aggregate(
+        mut self,
+        prop_key: &str,
+        aggregate: SensorAggregate,
+        distance: u8,
+        all_properties: &[Property],
+    ) -> Self {
+        self.neighborhood_entries
+            .push(NeighborhoodSensorEntry::Aggregate {
+                property_offset_idx: Self::property_offset_idx(prop_key, all_properties),
+                aggregate,
+                distance,
+            });
+        self
+    }
+
+    pub fn with_nearest_direction(
+        mut self,
+        prop_key: &str,
+        distance: u8,
+        all_properties: &[Property],
+    ) -> Self {
+        self.neighborhood_entries
+            .push(NeighborhoodSensorEntry::NearestDirection {
+                property_offset_idx: Self::property_offset_idx(prop_key, all_properties),
+                distance,
+            });
+        self
+    }
+
+    pub fn with_unit_count(mut self, filter: UnitSpeciesFilter, distance: u8) -> Self {
+        self.neighborhood_entries
+            .push(NeighborhoodSensorEntry::UnitCount { filter, distance });
+        self
     }
 }
 
@@ -458,6 +804,22 @@ pub struct LocalPropertySensorEntry {
     pub distance: u8,
 }
 
+pub enum NeighborhoodSensorEntry {
+    Aggregate {
+        property_offset_idx: usize,
+        aggregate: SensorAggregate,
+        distance: u8,
+    },
+    NearestDirection {
+        property_offset_idx: usize,
+        distance: u8,
+    },
+    UnitCount {
+        filter: UnitSpeciesFilter,
+        distance: u8,
+    },
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -510,4 +872,116 @@ pub mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_neighborhood_sensor_offsets() {
+        assert_eq!(
+            neighborhood_sensor_offsets(&GridNeighborhood::Moore, 2),
+            sensor_local_offsets(2)
+        );
+        assert_eq!(
+            neighborhood_sensor_offsets(&GridNeighborhood::Hex, 1),
+            vec![(0, 0), (0, 1), (1, 1), (1, 0), (0, -1), (-1, -1), (-1, 0)]
+        );
+        assert_eq!(
+            neighborhood_sensor_offsets(&GridNeighborhood::Hex, 2).len(),
+            19
+        );
+    }
+
+    #[test]
+    fn test_neighborhood_sensors() {
+        use crate::simulation::common::{builder::ChemistryBuilder, NullBehavior, UnitEntry};
+
+        let chemistry = ChemistryBuilder::with_key("cheese").build();
+        let manifest = chemistry.get_manifest();
+        let all_properties = manifest.all_properties.as_slice();
+        let milk = manifest.position_resource_by_key("milk").id;
+
+        let local_properties = LocalPropertySensorManifest::from_whitelist(&[], all_properties)
+            .with_aggregate("pos_res::milk", SensorAggregate::Sum, 2, all_properties)
+            .with_aggregate("pos_res::milk", SensorAggregate::Max, 1, all_properties)
+            .with_aggregate("pos_res::milk", SensorAggregate::Count, 3, all_properties)
+            .with_nearest_direction("pos_res::milk", 3, all_properties)
+            .with_nearest_direction("pos_res::milk", 1, all_properties)
+            .with_unit_count(UnitSpeciesFilter::Any, 1)
+            .with_unit_count(UnitSpeciesFilter::SameSpecies, 1)
+            .with_unit_count(UnitSpeciesFilter::OtherSpecies, 1);
+        let sensor_manifest = SensorManifest::new(manifest, &local_properties);
+
+        let mut world = World::new((7, 7), &chemistry);
+        for x in 0..7 {
+            for y in 0..7 {
+                world.set_pos_resource_at(&(x, y), milk, 0);
+            }
+        }
+        world.set_pos_resource_at(&(3, 6), milk, 10);
+        world.set_pos_resource_at(&(5, 3), milk, 4);
+        world.set_pos_resource_at(&(2, 2), milk, 1);
+
+        let mut unit_entry = UnitEntry::new("foo_unit", NullBehavior::construct());
+        for coord in [(3, 3), (4, 4)] {
+            world.seed_unit_at(&coord, &unit_entry.info, None, chemistry.as_ref());
+        }
+
+        // an entry bred from the first one is still the same species
+        unit_entry.info.unit_entry_id = 1;
+        world.seed_unit_at(&(2, 3), &unit_entry.info, None, chemistry.as_ref());
+        unit_entry.info.unit_entry_id = 2;
+        unit_entry.info.species_id = 1;
+        world.seed_unit_at(&(3, 2), &unit_entry.info, None, chemistry.as_ref());
+
+        let sim_attr = chemistry.get_default_simulation_attributes();
+        let context = SensorContext::from(&world, &sim_attr, &(3, 3));
+        let sense = |key: &str| {
+            sensor_manifest
+                .identify_sensor_from_key(key)
+                .unwrap()
+                .calculate(&context)
+        };
+
+        assert_eq!(sense("sum(pos_res::milk, 2)"), 5);
+        assert_eq!(sense("max(pos_res::milk, 1)"), 1);
+        assert_eq!(sense("count(pos_res::milk, 3)"), 3);
+
+        // von neumann, so the direction numbers are up, right, down, left
+        assert_eq!(sense("nearest_direction(pos_res::milk, 3)"), 1);
+        assert_eq!(sense("nearest_direction(pos_res::milk, 1)"), -1);
+
+        assert_eq!(sense("unit_count(any, 1)"), 3);
+        assert_eq!(sense("unit_count(same_species, 1)"), 2);
+        assert_eq!(sense("unit_count(other_species, 1)"), 1);
+    }
+
+    #[test]
+    fn test_version_1_sensors() {
+        use crate::biology::experiments::util::random_genome_of_length;
+        use crate::biology::genome::framed::common::FramedGenomeCompiler;
+        use crate::biology::genome::framed::translate::GenomeTranslator;
+        use crate::simulation::common::{builder::ChemistryBuilder, GeneticManifest};
+
+        let chemistry = ChemistryBuilder::with_key("cheese").build();
+        let gm = GeneticManifest::from_chemistry(&chemistry);
+        let mut version_1_gm = gm.clone();
+        version_1_gm.sensor_manifest = std::sync::Arc::new(SensorManifest::version_1(
+            chemistry.get_manifest(),
+            &chemistry.default_local_property_sensor_manifest(),
+        ));
+        assert_eq!(
+            version_1_gm.sensor_manifest.sensors.len() + 3,
+            gm.sensor_manifest.sensors.len()
+        );
+
+        let translator = GenomeTranslator::between(&version_1_gm, &gm);
+        for _ in 0..20 {
+            let genome = random_genome_of_length(60);
+            let translation = translator.translate(&genome);
+            assert!(translation.untranslatable.is_empty());
+
+            assert_eq!(
+                FramedGenomeCompiler::compile(translation.genome, &gm).display(&gm),
+                FramedGenomeCompiler::compile(genome, &version_1_gm).display(&version_1_gm)
+            );
+        }
+    }
 }
@@ -7,21 +7,22 @@ use crate::biology::genetic_manifest::predicates::{
     OperatorParamType,
 };
 use crate::biology::genetic_manifest::GeneticManifest;
+use crate::biology::genome::framed::compile::FramedGenomeCompiler;
 pub use crate::biology::genome::framed::execution::GenomeExecutionContext;
+use crate::biology::genome::framed::render::with_stats::render_frames_with_stats;
 use crate::biology::genome::framed::types::{
     BooleanVariable, CompiledFramedGenome, Disjunction, Frame, FramedGenomeWord,
 };
 use crate::biology::sensor_manifest::SensorId;
 pub use crate::biology::unit_behavior::framed::types::*;
-use crate::biology::unit_behavior::UnitBehavior;
+use crate::biology::unit_behavior::{UnitBehavior, UnitBehaviorInspection};
 use crate::chemistry;
 use crate::chemistry::reactions::ReactionCallParam;
 use crate::chemistry::{ChemistryInstance, ReactionId};
 use crate::simulation::common::*;
 use crate::simulation::world::World;
 use crate::util::Coord;
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub mod common {
     pub use super::FramedGenomeUnitBehavior;
@@ -31,14 +32,14 @@ pub mod common {
 use crate::biology::genome::framed::annotated::FramedGenomeExecutionStats;
 
 pub struct FramedGenomeUnitBehavior {
-    pub genome: Rc<CompiledFramedGenome>,
-    pub genetic_manifest: Rc<GeneticManifest>,
-    pub execution_stats: Rc<RefCell<FramedGenomeExecutionStats>>,
+    pub genome: Arc<CompiledFramedGenome>,
+    pub genetic_manifest: Arc<GeneticManifest>,
+    pub execution_stats: Arc<FramedGenomeExecutionStats>,
 }
 
 impl UnitBehavior for FramedGenomeUnitBehavior {
     fn get_behavior(
-        &mut self,
+        &self,
         coord: &Coord,
         sim_attr: &SimulationAttributes,
         world: &World,
@@ -46,19 +47,17 @@ impl UnitBehavior for FramedGenomeUnitBehavior {
     ) -> UnitBehaviorResult {
         let sensor_context = SensorContext::from(world, sim_attr, coord);
 
-        //let computation_points = if world.tick > 5000 { 20 } else { 100 };
-        let computation_points = 100;
+        let computation_points = chemistry.get_unit_execution_point_budget(world, coord);
 
         let new_registers = vec![0; 10];
 
-        let mut mut_stats = self.execution_stats.borrow_mut();
         let mut execution_context = GenomeExecutionContext::new(
             &self.genome.frames,
             &sensor_context,
             new_registers,
             &self.genetic_manifest,
             computation_points,
-            &mut mut_stats,
+            &self.execution_stats,
         );
 
         // let mut rng = rand::thread_rng();
@@ -71,47 +70,106 @@ impl UnitBehavior for FramedGenomeUnitBehavior {
         UnitBehaviorResult {
             reactions: reactions.clone(),
             consumed_execution_points: execution_context.consumed_compute_points,
+            reaction_genes: execution_context.reaction_genes,
         }
     }
+
+    fn mark_executed_reactions(&self, result: &UnitBehaviorResult, executed: &[usize]) {
+        #[cfg(any(not(feature = "skip_genome_stats"), test))]
+        for &i in executed {
+            let (frame, channel, gene) = result.reaction_genes[i];
+            self.execution_stats.frames[frame].channels[channel].genes[gene].mark_reaction();
+        }
+    }
+
+    fn inspect(
+        &self,
+        coord: &Coord,
+        sim_attr: &SimulationAttributes,
+        world: &World,
+    ) -> Option<UnitBehaviorInspection> {
+        let sensor_context = SensorContext::from(world, sim_attr, coord);
+        let sensor_values = self
+            .genetic_manifest
+            .sensor_manifest
+            .sensors
+            .iter()
+            .map(|sensor| (sensor.key.clone(), sensor.calculate(&sensor_context)))
+            .collect::<Vec<_>>();
+
+        // the stats only mean something once the genome executed
+        let stats = &self.execution_stats;
+        let has_stats = stats.eval_count.get() > 0;
+
+        Some(UnitBehaviorInspection {
+            genome: render_frames_with_stats(
+                &self.genome.frames,
+                &self.genetic_manifest,
+                if has_stats { Some(&stats) } else { None },
+            ),
+            sensor_values,
+        })
+    }
+
+    fn genome(&self) -> Option<Arc<CompiledFramedGenome>> {
+        Some(self.genome.clone())
+    }
+
+    fn with_genome(&self, raw_genome: Vec<FramedGenomeWord>) -> Option<Arc<dyn UnitBehavior>> {
+        let genome = FramedGenomeCompiler::compile(raw_genome, &self.genetic_manifest);
+        Some(Self::new(Arc::new(genome), self.genetic_manifest.clone()).construct())
+    }
 }
 
 impl FramedGenomeUnitBehavior {
+    /**
+     * The stats are shared with the caller, who gets to read them once the simulation ends.
+     */
     pub fn new_with_stats(
-        genome: Rc<CompiledFramedGenome>,
-        genetic_manifest: Rc<GeneticManifest>,
-        execution_stats: Rc<RefCell<FramedGenomeExecutionStats>>,
+        genome: Arc<CompiledFramedGenome>,
+        genetic_manifest: Arc<GeneticManifest>,
+        execution_stats: Arc<FramedGenomeExecutionStats>,
     ) -> Self {
+        assert_eq!(
+            execution_stats.frames.len(),
+            genome.frames.len(),
+            "execution stats need to be initialized for the genome"
+        );
         Self {
             genome,
             genetic_manifest,
             execution_stats,
         }
     }
-    pub fn new(genome: Rc<CompiledFramedGenome>, genetic_manifest: Rc<GeneticManifest>) -> Self {
+    pub fn new(genome: Arc<CompiledFramedGenome>, genetic_manifest: Arc<GeneticManifest>) -> Self {
         Self {
+            execution_stats: Arc::new(genome.new_stats()),
             genome,
             genetic_manifest,
-            execution_stats: Rc::new(RefCell::new(FramedGenomeExecutionStats::empty())),
         }
     }
 
-    pub fn construct(self) -> Rc<RefCell<FramedGenomeUnitBehavior>> {
-        Rc::new(RefCell::new(self))
+    pub fn construct(self) -> Arc<FramedGenomeUnitBehavior> {
+        Arc::new(self)
     }
 }
 
 pub mod test {
     use super::{FramedGenomeUnitBehavior, GenomeExecutionContext};
+    use crate::biology::genome::framed::annotated::GeneExecutionStats;
+    use crate::biology::genome::framed::builders::legacy::util::GenomeBuilderLegacy;
     use crate::biology::genome::framed::common::*;
     use crate::biology::genome::framed::compile::FramedGenomeCompiler;
     use crate::biology::genome::framed::convert::simple_convert_into_frames;
     use crate::biology::genome::framed::render::render_frames;
     use crate::biology::genome::framed::*;
+    use crate::chemistry::config::ChemistryConfigValue;
     use crate::chemistry::helpers::place_units::PlaceUnitsMethod;
+    use crate::chemistry::helpers::signals::SignalDefinition;
     use crate::chemistry::variants::CheeseChemistry;
     use crate::simulation::common::builder::ChemistryBuilder;
     use crate::simulation::common::*;
-    use std::rc::Rc;
+    use std::sync::Arc;
     pub fn count_units(world: &World) -> u64 {
         let mut count: u64 = 0;
         for coord in CoordIterator::new(world.size) {
@@ -123,22 +181,285 @@ pub mod test {
         return count;
     }
 
+    fn genome_entry(
+        gm: &Arc<GeneticManifest>,
+        genome: GenomeBuilderLegacy,
+        species_name: &str,
+        cheese: i32,
+    ) -> UnitEntry {
+        let genome_values = genome.build(gm);
+        let frames =
+            FramedGenomeCompiler::compile(simple_convert_into_frames(genome_values), gm).wrap_arc();
+
+        UnitEntryBuilder::default()
+            .species_name(species_name.to_string())
+            .behavior(FramedGenomeUnitBehavior::new(frames, gm.clone()).construct())
+            .default_resources(vec![("cheese".to_string(), cheese)])
+            .build(&gm.chemistry_manifest)
+    }
+
+    /**
+     * A 3x3 cheese simulation with a single entry running the genome, with a unit at each of
+     * the coords.
+     */
+    fn genome_sim(
+        config: ChemistryConfiguration,
+        genome: GenomeBuilderLegacy,
+        coords: Vec<Coord>,
+        cheese: i32,
+    ) -> (Simulation, Arc<GeneticManifest>) {
+        let chemistry = ChemistryBuilder::with_key("cheese").config(config).build();
+        let gm = Arc::new(GeneticManifest::from_chemistry(&chemistry));
+
+        let sim = SimulationBuilder::default()
+            .chemistry(chemistry)
+            .size((3, 3))
+            .iterations(100)
+            .place_units_method(PlaceUnitsMethod::ManualSingleEntry {
+                attributes: None,
+                coords,
+            })
+            .unit_manifest(UnitManifest {
+                units: vec![genome_entry(&gm, genome, "main", cheese)],
+            })
+            .to_simulation();
+
+        (sim, gm)
+    }
+
     #[test]
     fn genome_execution__execute() {
-        let chemistry = ChemistryBuilder::with_key("cheese").build();
-        let gm = Rc::new(GeneticManifest::from_chemistry(&chemistry));
+        let genome = genome!(gene(
+            if_any(all((is_truthy, 1, 0, 0))),
+            then_do(new_unit(0, 0, 0))
+        ));
+        let (mut sim, _) = genome_sim(ChemistryConfiguration::new(), genome, vec![(1, 1)], 1000);
 
-        let cm = &gm.chemistry_manifest;
-        let genome_values = genome!(gene(
+        assert_eq!(count_units(&sim.world), 1);
+        sim.tick();
+        assert_eq!(count_units(&sim.world), 2);
+
+        assert!(sim.world.has_unit_at(&(1, 2)));
+        assert!(sim.world.has_unit_at(&(1, 1)));
+    }
+
+    #[test]
+    fn genome_execution__simultaneous_update_mode() {
+        for policy in vec![
+            IntentConflictPolicy::RandomPriority,
+            IntentConflictPolicy::ResourceWeighted("cheese".to_string()),
+            IntentConflictPolicy::CancelBoth,
+        ] {
+            let config = ChemistryConfigBuilder::new()
+                .set_update_mode(UnitUpdateMode::Simultaneous(policy))
+                .build();
+            let genome = genome!(gene(
+                if_any(all((is_truthy, 1, 0, 0))),
+                then_do(new_unit(0, 0, 0))
+            ));
+            let (mut sim, _) = genome_sim(config, genome, vec![(0, 0), (2, 0)], 1000);
+
+            sim.tick();
+
+            // the new units were not around when the intents were collected
+            assert_eq!(count_units(&sim.world), 4);
+            assert!(sim.world.has_unit_at(&(0, 1)));
+            assert!(sim.world.has_unit_at(&(2, 1)));
+        }
+    }
+
+    /**
+     * A 3x3 cheese simulation in the simultaneous update mode, with a single unit for each of
+     * the genomes, placed at its coord and holding the given cheese.  Ticked once.
+     */
+    fn simultaneous_sim(
+        policy: IntentConflictPolicy,
+        seed: u64,
+        units: Vec<(GenomeBuilderLegacy, Coord, i32)>,
+    ) -> Simulation {
+        let config = ChemistryConfigBuilder::new()
+            .set_update_mode(UnitUpdateMode::Simultaneous(policy))
+            .set_update_seed(seed)
+            .build();
+        let chemistry = ChemistryBuilder::with_key("cheese").config(config).build();
+        let gm = Arc::new(GeneticManifest::from_chemistry(&chemistry));
+
+        let coords = units.iter().map(|(_, coord, _)| *coord).collect::<Vec<_>>();
+        let entries = units
+            .into_iter()
+            .enumerate()
+            .map(|(i, (genome, _, cheese))| {
+                genome_entry(&gm, genome, &format!("unit_{}", i), cheese)
+            })
+            .collect::<Vec<_>>();
+
+        let mut sim = SimulationBuilder::default()
+            .chemistry(chemistry)
+            .size((3, 3))
+            .iterations(100)
+            .place_units_method(PlaceUnitsMethod::Skip)
+            .unit_manifest(UnitManifest { units: entries })
+            .to_simulation();
+
+        for (entry_id, coord) in coords.iter().enumerate() {
+            let info = sim.unit_manifest.units[entry_id].info.clone();
+            sim.world
+                .seed_unit_at(coord, &info, None, sim.chemistry.as_ref());
+        }
+
+        sim.tick();
+        sim
+    }
+
+    /**
+     * Two units spawning into the same cell, (1, 1), from (1, 0) going up and from (0, 1) going
+     * right.  The first unit holds more cheese.  Returns the entry of the unit born at (1, 1).
+     */
+    fn simultaneous_conflict(policy: IntentConflictPolicy, seed: u64) -> Option<UnitEntryId> {
+        let up = genome!(gene(
             if_any(all((is_truthy, 1, 0, 0))),
             then_do(new_unit(0, 0, 0))
-        ))
-        .build(&gm);
+        ));
+        let right = genome!(gene(
+            if_any(all((is_truthy, 1, 0, 0))),
+            then_do(new_unit(1, 0, 0))
+        ));
+
+        let sim = simultaneous_sim(policy, seed, vec![(up, (1, 0), 1000), (right, (0, 1), 500)]);
+        sim.world.get_unit_at(&(1, 1)).map(|unit| unit.entry_id)
+    }
+
+    #[test]
+    fn genome_execution__simultaneous_update_mode_conflicts() {
+        // the richer unit always wins
+        assert_eq!(
+            simultaneous_conflict(
+                IntentConflictPolicy::ResourceWeighted("cheese".to_string()),
+                0
+            ),
+            Some(0)
+        );
+
+        // neither unit gets the cell
+        assert_eq!(
+            simultaneous_conflict(IntentConflictPolicy::CancelBoth, 0),
+            None
+        );
+
+        // exactly one unit wins, the same one for the same seed
+        let mut winners = vec![];
+        for seed in 0..8 {
+            let winner = simultaneous_conflict(IntentConflictPolicy::RandomPriority, seed);
+            assert!(winner.is_some());
+            assert_eq!(
+                simultaneous_conflict(IntentConflictPolicy::RandomPriority, seed),
+                winner
+            );
+            winners.push(winner.unwrap());
+        }
+        assert!(winners.contains(&0) && winners.contains(&1));
+    }
+
+    #[test]
+    fn genome_execution__simultaneous_update_mode_uses_the_snapshot() {
+        // the unit at (1, 0) spawns into (1, 1), which the unit there is leaving to the right
+        let mut outcomes = vec![];
+        for seed in 0..8 {
+            let spawn = genome!(gene(
+                if_any(all((is_truthy, 1, 0, 0))),
+                then_do(new_unit(0, 0, 0))
+            ));
+            let leave = genome!(gene(
+                if_any(all((is_truthy, 1, 0, 0))),
+                then_do(move_unit(1, 0, 0))
+            ));
+            let sim = simultaneous_sim(
+                IntentConflictPolicy::RandomPriority,
+                seed,
+                vec![(spawn, (1, 0), 1000), (leave, (1, 1), 1000)],
+            );
+
+            // the cell was taken when the units decided, so no unit is ever born into it
+            assert_eq!(count_units(&sim.world), 2);
+            outcomes.push(sim.world.has_unit_at(&(2, 1)));
+        }
+
+        // the two intents touch the same cell, so only one of them goes through
+        assert!(outcomes.contains(&true) && outcomes.contains(&false));
+    }
+
+    #[test]
+    fn genome_execution__execution_points_are_charged() {
+        let run_sim = |config: ChemistryConfiguration, cheese: i32| -> Simulation {
+            let genome = genome!(gene(
+                if_any(all((is_truthy, 1, 0, 0))),
+                then_do(new_unit(0, 0, 0))
+            ));
+            let (mut sim, _) = genome_sim(config, genome, vec![(1, 1)], cheese);
+
+            sim.tick();
+            sim
+        };
 
-        let framed_vals = simple_convert_into_frames(genome_values);
-        let frames = FramedGenomeCompiler::compile(framed_vals, &gm).wrap_rc();
+        let charged_config = ChemistryConfigBuilder::new()
+            .set_unit_resource_key("execution_point_resource", "cheese")
+            .set_integer("execution_points_per_resource", 1)
+            .build();
+
+        let free_sim = run_sim(ChemistryConfiguration::new(), 1000);
+        let charged_sim = run_sim(charged_config.clone(), 1000);
+
+        let cheese_idx = free_sim
+            .chemistry
+            .get_manifest()
+            .unit_resource_by_key("cheese")
+            .id;
+        assert_eq!(count_units(&charged_sim.world), 2);
+        assert!(
+            charged_sim.world.get_unit_resource_at(&(1, 1), cheese_idx)
+                < free_sim.world.get_unit_resource_at(&(1, 1), cheese_idx)
+        );
 
-        println!("genome: \n{}", frames.display(&gm));
+        // a unit without any execution points doesn't get to act
+        let mut exhausted_config = charged_config.clone();
+        exhausted_config.insert(
+            "max_execution_points".to_string(),
+            ChemistryConfigValue::Integer(0),
+        );
+        let exhausted_sim = run_sim(exhausted_config, 1000);
+        assert_eq!(count_units(&exhausted_sim.world), 1);
+    }
+
+    #[test]
+    fn genome_execution__counts_executed_reactions() {
+        let config = ChemistryConfigBuilder::new()
+            .set_integer("action_points_per_tick", 2)
+            .build();
+        let chemistry = ChemistryBuilder::with_key("cheese").config(config).build();
+        let gm = Arc::new(GeneticManifest::from_chemistry(&chemistry));
+
+        // the second unit can't be born into the cell the first one just took, and the third
+        // gene doesn't get evaluated since the unit is out of action points.  The newborn unit
+        // at (1, 2) gets its turn as well, but has no room above it.
+        let genome = genome!(
+            gene(
+                if_any(all((is_truthy, 1, 0, 0))),
+                then_do(new_unit(0, 0, 0))
+            ),
+            gene(
+                if_any(all((is_truthy, 1, 0, 0))),
+                then_do(new_unit(0, 0, 0))
+            ),
+            gene(
+                if_any(all((is_truthy, 1, 0, 0))),
+                then_do(new_unit(1, 0, 0))
+            )
+        );
+        let frames =
+            FramedGenomeCompiler::compile(simple_convert_into_frames(genome.build(&gm)), &gm)
+                .wrap_arc();
+        let stats = Arc::new(frames.new_stats());
+        let behavior = FramedGenomeUnitBehavior::new_with_stats(frames, gm.clone(), stats.clone());
 
         let mut sim = SimulationBuilder::default()
             .chemistry(chemistry)
@@ -151,17 +472,49 @@ pub mod test {
             .unit_manifest(UnitManifest {
                 units: vec![UnitEntryBuilder::default()
                     .species_name("main".to_string())
-                    .behavior(FramedGenomeUnitBehavior::new(frames, gm.clone()).construct())
+                    .behavior(behavior.construct())
                     .default_resources(vec![("cheese".to_string(), 1000)])
-                    .build(&cm)],
+                    .build(&gm.chemistry_manifest)],
             })
             .to_simulation();
 
-        assert_eq!(count_units(&sim.world), 1);
         sim.tick();
         assert_eq!(count_units(&sim.world), 2);
 
-        assert!(sim.world.has_unit_at(&(1, 2)));
-        assert!(sim.world.has_unit_at(&(1, 1)));
+        let genes = &stats.frames[0].channels[0].genes;
+        let counts = |f: fn(&GeneExecutionStats) -> usize| genes.iter().map(f).collect::<Vec<_>>();
+        assert_eq!(counts(|gene| gene.eval_count.get()), vec![2, 2, 0]);
+        assert_eq!(counts(|gene| gene.reaction_count.get()), vec![1, 0, 0]);
+    }
+
+    #[test]
+    fn genome_execution__emits_signals() {
+        let config = ChemistryConfigBuilder::new()
+            .add_signal(SignalDefinition::new("alarm", 0, 100, 1))
+            .build();
+        let genome = genome!(gene(
+            if_any(all((is_truthy, 1, 0, 0))),
+            then_do(emit_alarm(60, 0, 0))
+        ));
+        let (mut sim, gm) = genome_sim(config, genome, vec![(1, 1)], 1000);
+        let cm = &gm.chemistry_manifest;
+
+        sim.tick();
+
+        let signal_idx = cm.position_resource_by_key("signal_alarm").id;
+        assert_eq!(sim.world.get_pos_resource_at(&(1, 1), signal_idx), 60);
+        assert_eq!(sim.world.get_pos_resource_at(&(1, 2), signal_idx), 30);
+
+        let sense = |key: &str, coord: &Coord| {
+            gm.sensor_manifest
+                .identify_sensor_from_key(key)
+                .unwrap()
+                .calculate(&SensorContext::from(&sim.world, &sim.attributes, coord))
+        };
+        assert_eq!(sense("signal::alarm", &(1, 2)), 30);
+
+        // von neumann, so down is direction 2
+        assert_eq!(sense("signal_gradient::alarm", &(1, 2)), 2);
+        assert_eq!(sense("signal_gradient::alarm", &(1, 1)), -1);
     }
 }
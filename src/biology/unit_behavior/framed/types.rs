@@ -103,6 +103,17 @@ pub enum ParamedMetaReactionCall {
     JumpAheadFrames(ParsedGenomeParam),
     SetRegister(ParsedGenomeParam, ParsedGenomeParam),
     SetChannel(ParsedGenomeParam),
+    IncrementRegister(ParsedGenomeParam),
+    DecrementRegister(ParsedGenomeParam),
+
+    // (register, value), where the value is usually a sensor
+    AddToRegister(ParsedGenomeParam, ParsedGenomeParam),
+
+    // (from register, to register)
+    CopyRegister(ParsedGenomeParam, ParsedGenomeParam),
+
+    // (register, max value)
+    RandomIntoRegister(ParsedGenomeParam, ParsedGenomeParam),
     Nil,
 }
 
@@ -125,6 +136,33 @@ impl ParamedMetaReactionCall {
             ParamedMetaReactionCall::SetChannel(channel) => {
                 format!("SetChannel({})", render_param(channel, sm))
             }
+            ParamedMetaReactionCall::IncrementRegister(id) => {
+                format!("IncrementRegister({})", render_param(id, sm))
+            }
+            ParamedMetaReactionCall::DecrementRegister(id) => {
+                format!("DecrementRegister({})", render_param(id, sm))
+            }
+            ParamedMetaReactionCall::AddToRegister(id, val) => {
+                format!(
+                    "AddToRegister({}, {})",
+                    render_param(id, sm),
+                    render_param(val, sm)
+                )
+            }
+            ParamedMetaReactionCall::CopyRegister(from, to) => {
+                format!(
+                    "CopyRegister({}, {})",
+                    render_param(from, sm),
+                    render_param(to, sm)
+                )
+            }
+            ParamedMetaReactionCall::RandomIntoRegister(id, max) => {
+                format!(
+                    "RandomIntoRegister({}, {})",
+                    render_param(id, sm),
+                    render_param(max, sm)
+                )
+            }
             ParamedMetaReactionCall::Nil => {
                 format!("DoNothing")
             }
@@ -147,6 +185,21 @@ impl Debug for ParamedMetaReactionCall {
             ParamedMetaReactionCall::SetRegister(r, v) => {
                 write!(f, "SetRegister({:?}, {:?}", r, v)
             }
+            ParamedMetaReactionCall::IncrementRegister(r) => {
+                write!(f, "IncrementRegister({:?})", r)
+            }
+            ParamedMetaReactionCall::DecrementRegister(r) => {
+                write!(f, "DecrementRegister({:?})", r)
+            }
+            ParamedMetaReactionCall::AddToRegister(r, v) => {
+                write!(f, "AddToRegister({:?}, {:?})", r, v)
+            }
+            ParamedMetaReactionCall::CopyRegister(from, to) => {
+                write!(f, "CopyRegister({:?}, {:?})", from, to)
+            }
+            ParamedMetaReactionCall::RandomIntoRegister(r, max) => {
+                write!(f, "RandomIntoRegister({:?}, {:?})", r, max)
+            }
 
             ParamedMetaReactionCall::Nil => {
                 write!(f, "DoNothing")
@@ -162,6 +215,11 @@ pub enum MetaReaction {
 
     // question: when a channel changes during genome execution, does it change immediately or does it wait until the next frame?
     SetChannel,
+    IncrementRegister,
+    DecrementRegister,
+    AddToRegister,
+    CopyRegister,
+    RandomIntoRegister,
     Nil,
 }
 
@@ -173,6 +231,16 @@ impl MetaReaction {
             return Some(Self::SetRegister);
         } else if val == "set_channel" {
             return Some(Self::SetChannel);
+        } else if val == "increment_register" {
+            return Some(Self::IncrementRegister);
+        } else if val == "decrement_register" {
+            return Some(Self::DecrementRegister);
+        } else if val == "add_to_register" {
+            return Some(Self::AddToRegister);
+        } else if val == "copy_register" {
+            return Some(Self::CopyRegister);
+        } else if val == "random_into_register" {
+            return Some(Self::RandomIntoRegister);
         } else if val == "nil" {
             return Some(Self::Nil);
         } else {
@@ -185,6 +253,11 @@ impl MetaReaction {
             1 => Self::JumpAheadFrames,
             2 => Self::SetRegister,
             3 => Self::SetChannel,
+            4 => Self::IncrementRegister,
+            5 => Self::DecrementRegister,
+            6 => Self::AddToRegister,
+            7 => Self::CopyRegister,
+            8 => Self::RandomIntoRegister,
             _ => Self::Nil,
         }
     }
@@ -194,6 +267,11 @@ impl MetaReaction {
             Self::JumpAheadFrames => 1,
             Self::SetRegister => 2,
             Self::SetChannel => 3,
+            Self::IncrementRegister => 4,
+            Self::DecrementRegister => 5,
+            Self::AddToRegister => 6,
+            Self::CopyRegister => 7,
+            Self::RandomIntoRegister => 8,
         }
     }
 }
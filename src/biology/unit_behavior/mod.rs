@@ -3,16 +3,20 @@ pub mod lever;
 pub mod mouse;
 
 use crate::biology::genetic_manifest::predicates::OperatorImplementation;
+use crate::biology::genome::framed::types::{CompiledFramedGenome, FramedGenomeWord};
 pub use crate::biology::unit_behavior::framed::ParsedGenomeParam;
 use crate::chemistry::reactions::ReactionCall;
 use crate::simulation::common::*;
-use std::cell::RefCell;
 use std::fmt::{Debug, Formatter, Result};
-use std::rc::Rc;
+use std::sync::Arc;
 
-pub trait UnitBehavior {
+/**
+ * Behaviors are shared by every unit of an entry and may be asked for decisions from several
+ * threads at once, see `UnitUpdateMode::Tiled`.
+ */
+pub trait UnitBehavior: Send + Sync {
     fn get_behavior(
-        &mut self,
+        &self,
         coord: &Coord,
         sim_attr: &SimulationAttributes,
         world: &World,
@@ -20,6 +24,46 @@ pub trait UnitBehavior {
     ) -> UnitBehaviorResult {
         UnitBehaviorResult::with_reactions(vec![])
     }
+
+    /**
+     * A readable snapshot of the behavior as seen from the given coord, for the ui inspector.
+     */
+    fn inspect(
+        &self,
+        coord: &Coord,
+        sim_attr: &SimulationAttributes,
+        world: &World,
+    ) -> Option<UnitBehaviorInspection> {
+        None
+    }
+
+    /**
+     * The genome behind the behavior, for behaviors that can pass it on to offspring.
+     */
+    fn genome(&self) -> Option<Arc<CompiledFramedGenome>> {
+        None
+    }
+
+    /**
+     * A behavior of the same kind running the given genome instead, for offspring.
+     */
+    fn with_genome(&self, raw_genome: Vec<FramedGenomeWord>) -> Option<Arc<dyn UnitBehavior>> {
+        None
+    }
+
+    /**
+     * Called once the chemistry executed the reactions of a result, with the indices of the
+     * reactions that went through.
+     */
+    fn mark_executed_reactions(&self, result: &UnitBehaviorResult, executed: &[usize]) {}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnitBehaviorInspection {
+    pub genome: String,
+
+    // sensor key and the value the behavior currently sees
+    pub sensor_values: Vec<(String, SensorValue)>,
 }
 
 // #[derive(Clone)]
@@ -31,6 +75,9 @@ pub struct UnitBehaviorResult {
     pub reactions: Vec<ReactionCall>,
     //pub register_changes: PhenotypeRegisterChanges,
     pub consumed_execution_points: u64,
+
+    // the (frame, channel, gene) behind each reaction, only filled in by framed genomes
+    pub reaction_genes: Vec<(usize, usize, usize)>,
 }
 
 impl UnitBehaviorResult {
@@ -38,6 +85,7 @@ impl UnitBehaviorResult {
         UnitBehaviorResult {
             reactions,
             consumed_execution_points: 0,
+            reaction_genes: vec![],
         }
     }
 
@@ -64,7 +112,7 @@ impl UnitBehavior for NullBehavior {}
 // pub type BoxedUnitBehavior = Rc<Box<dyn UnitBehavior>>;
 
 impl NullBehavior {
-    pub fn construct() -> Rc<RefCell<dyn UnitBehavior>> {
-        Rc::new(RefCell::new(Self {}))
+    pub fn construct() -> Arc<dyn UnitBehavior> {
+        Arc::new(Self {})
     }
 }
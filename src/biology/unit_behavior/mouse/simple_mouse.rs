@@ -5,14 +5,14 @@ use crate::chemistry::{ChemistryInstance, ReactionId};
 use crate::simulation::common::*;
 use crate::simulation::iterators::CoordOffsetIterator;
 use crate::simulation::world::World;
-use crate::util::{grid_direction_to_num, Coord, GridDirection};
+use crate::util::{Coord, GridDirection};
 
 use rand::Rng;
 
 pub struct SimpleMouse {}
 impl UnitBehavior for SimpleMouse {
     fn get_behavior(
-        &mut self,
+        &self,
         coord: &Coord,
         sim_attr: &SimulationAttributes,
         world: &World,
@@ -21,6 +21,7 @@ impl UnitBehavior for SimpleMouse {
         let reactions = &chemistry.get_manifest().reactions;
 
         let pos_resources = defs::PositionResourcesLookup::new();
+        let neighborhood = chemistry.get_manifest().neighborhood;
 
         if world.get_pos_resource_at(coord, pos_resources.milk) > 10 {
             return UnitBehaviorResult::with_reactions(vec![(
@@ -31,11 +32,13 @@ impl UnitBehavior for SimpleMouse {
             )]);
         }
 
-        for (_coord, _dir) in CoordOffsetIterator::new(coord, &world.size) {
+        for (_coord, _dir) in
+            CoordOffsetIterator::with_neighborhood(coord, &world.size, &neighborhood)
+        {
             if world.get_pos_resource_at(&_coord, pos_resources.milk) > 10 {
                 return UnitBehaviorResult::with_reactions(vec![(
                     defs::REACTION_ID_MOVE_UNIT,
-                    grid_direction_to_num(_dir) as u16,
+                    neighborhood.direction_to_num(&_dir).unwrap(),
                     0,
                     0,
                 )]);
@@ -44,7 +47,9 @@ impl UnitBehavior for SimpleMouse {
 
         UnitBehaviorResult::with_reactions(vec![(
             defs::REACTION_ID_MOVE_UNIT,
-            grid_direction_to_num(GridDirection::Up) as u16,
+            neighborhood
+                .direction_to_num(&GridDirection::Up)
+                .unwrap_or(0),
             0,
             0,
         )])
@@ -7,13 +7,18 @@ use crate::chemistry::{ChemistryInstance, ReactionId};
 use crate::simulation::common::*;
 use crate::simulation::iterators::CoordOffsetIterator;
 use crate::simulation::world::World;
-use crate::util::{grid_direction_to_num, Coord, GridDirection};
+use crate::util::{Coord, GridDirection, GridNeighborhood};
 
-pub fn get_direction_where<F>(world: &World, coord: &Coord, f: F) -> Option<GridDirection>
+pub fn get_direction_where<F>(
+    world: &World,
+    coord: &Coord,
+    neighborhood: &GridNeighborhood,
+    f: F,
+) -> Option<GridDirection>
 where
     F: Fn(&World, &Coord) -> bool,
 {
-    for (_coord, _dir) in CoordOffsetIterator::new(coord, &world.size) {
+    for (_coord, _dir) in CoordOffsetIterator::with_neighborhood(coord, &world.size, neighborhood) {
         if f(world, &_coord) {
             return Some(_dir);
         }
@@ -25,7 +30,7 @@ where
 pub struct SmartMouse {}
 impl UnitBehavior for SmartMouse {
     fn get_behavior(
-        &mut self,
+        &self,
         coord: &Coord,
         sim_attr: &SimulationAttributes,
         world: &World,
@@ -34,6 +39,7 @@ impl UnitBehavior for SmartMouse {
         let reactions = &chemistry.get_manifest().reactions;
         let pos_resources = defs::PositionResourcesLookup::new();
         let pos_attributes = defs::PositionAttributesLookup::new();
+        let neighborhood = chemistry.get_manifest().neighborhood;
 
         if world.get_pos_resource_at(coord, pos_resources.milk) > 50 / 2 {
             return UnitBehaviorResult::with_reactions(vec![(
@@ -44,18 +50,20 @@ impl UnitBehavior for SmartMouse {
             )]);
         }
 
-        for (_coord, _dir) in CoordOffsetIterator::new(coord, &world.size) {
+        for (_coord, _dir) in
+            CoordOffsetIterator::with_neighborhood(coord, &world.size, &neighborhood)
+        {
             if world.get_pos_resource_at(&_coord, pos_resources.milk) > 10 {
                 return UnitBehaviorResult::with_reactions(vec![(
                     defs::REACTION_ID_MOVE_UNIT,
-                    grid_direction_to_num(_dir) as u16,
+                    neighborhood.direction_to_num(&_dir).unwrap(),
                     0,
                     0,
                 )]);
             }
         }
 
-        let dir_of_cheese_source = get_direction_where(world, coord, |w, _coord| {
+        let dir_of_cheese_source = get_direction_where(world, coord, &neighborhood, |w, _coord| {
             w.get_pos_attribute_at(_coord, pos_attributes.is_cheese_dispenser)
                 .unwrap_bool()
         });
@@ -63,14 +71,14 @@ impl UnitBehavior for SmartMouse {
         if let Some(dir) = dir_of_cheese_source {
             return UnitBehaviorResult::with_reactions(vec![(
                 defs::REACTION_ID_MOVE_UNIT,
-                grid_direction_to_num(dir) as u16,
+                neighborhood.direction_to_num(&dir).unwrap(),
                 0,
                 0,
             )]);
         }
 
         let mut rnd = rand::thread_rng();
-        let direction = rnd.gen_range(0..4);
+        let direction = rnd.gen_range(0..neighborhood.size());
 
         UnitBehaviorResult::with_reactions(vec![(
             defs::REACTION_ID_MOVE_UNIT,
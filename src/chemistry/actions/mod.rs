@@ -1,7 +1,8 @@
 pub mod tests;
 
-use std::rc::Rc;
+use std::sync::Arc;
 
+use crate::chemistry::helpers::signals::emit_signal;
 use crate::chemistry::ChemistryInstance;
 use serde::{Deserialize, Serialize};
 
@@ -28,7 +29,8 @@ use std::fmt::{Debug, Formatter, Result};
 
 pub type ActionParamNumber = i32;
 pub type ActionDefinitionIndex = usize;
-pub type ExecuteActionFunction = dyn Fn(&mut SimCell, &ActionExecutionContext) -> bool;
+pub type ExecuteActionFunction =
+    dyn Fn(&mut SimCell, &ActionExecutionContext) -> bool + Send + Sync;
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum ActionParamType {
@@ -49,27 +51,27 @@ pub enum ActionParamType {
 pub enum ActionParam {
     UnitResourceAmount(ActionParamNumber),
     UnitResourceIndex(UnitResourceIndex),
-    UnitResourceKey(&'static str),
+    UnitResourceKey(String),
 
     UnitAttributeValue(UnitAttributeValue),
     UnitAttributeIndex(UnitAttributeIndex),
-    UnitAttributeKey(&'static str),
+    UnitAttributeKey(String),
 
     UnitEntryAttributeValue(UnitEntryAttributeValue),
     UnitEntryAttributeIndex(UnitEntryAttributeIndex),
-    UnitEntryAttributeKey(&'static str),
+    UnitEntryAttributeKey(String),
 
     PositionResourceAmount(PositionResourceAmount),
     PositionResourceIndex(PositionResourceIndex),
-    PositionResourceKey(&'static str),
+    PositionResourceKey(String),
 
     PositionAttributeIndex(PositionAttributeIndex),
     PositionAttributeValue(PositionAttributeValue),
-    PositionAttributeKey(&'static str),
+    PositionAttributeKey(String),
 
     SimulationAttributeIndex(PositionAttributeIndex),
     SimulationAttributeValue(PositionAttributeValue),
-    SimulationAttributeKey(&'static str),
+    SimulationAttributeKey(String),
 
     Constant(ActionParamNumber),
     Boolean(bool),
@@ -156,7 +158,7 @@ pub type ActionLibrary = Vec<ActionDefinition>;
 #[derive(Clone)]
 pub struct ActionDefinition {
     pub key: String,
-    pub execute: Rc<ExecuteActionFunction>,
+    pub execute: Arc<ExecuteActionFunction>,
     pub params: Vec<ActionParamDefinition>,
 }
 
@@ -164,7 +166,7 @@ impl ActionDefinition {
     pub fn new(
         key: &str,
         params: Vec<ActionParamDefinition>,
-        execute: Rc<ExecuteActionFunction>,
+        execute: Arc<ExecuteActionFunction>,
     ) -> Self {
         Self {
             key: key.to_string(),
@@ -197,7 +199,7 @@ impl Debug for ActionDefinition {
 pub struct CompiledActionDefinition {
     pub key: String,
     pub index: ActionDefinitionIndex,
-    pub execute: Rc<ExecuteActionFunction>,
+    pub execute: Arc<ExecuteActionFunction>,
     pub params: Vec<ActionParamDefinition>,
 }
 
@@ -417,11 +419,16 @@ pub fn default_actions() -> Vec<ActionDefinition> {
                 param_type: ActionParamType::Direction,
             }],
             // execute action
-            Rc::new(
+            Arc::new(
                 |sim_cell: &mut SimCell, context: &ActionExecutionContext| -> bool {
                     let dir = context.params[0].to_direction();
+                    let neighborhood = sim_cell.chemistry.get_manifest().neighborhood;
                     //println!("moving {:?}", dir);
-                    match coord_by_direction_offset(context.coord, &dir, sim_cell.world.size) {
+                    match neighborhood.coord_by_direction_offset(
+                        context.coord,
+                        &dir,
+                        sim_cell.world.size,
+                    ) {
                         Some(dest_coord) => {
                             let pos = sim_cell.world.get_position_at(&dest_coord).unwrap();
                             if !sim_cell.world.has_unit_at(&dest_coord) {
@@ -449,12 +456,17 @@ pub fn default_actions() -> Vec<ActionDefinition> {
                 param_type: ActionParamType::Direction,
             }],
             // execute action
-            Rc::new(
+            Arc::new(
                 |sim_cell: &mut SimCell, context: &ActionExecutionContext| -> bool {
                     let dir = context.params[0].to_direction();
 
-                    let dest_coord =
-                        coord_by_direction_offset(context.coord, &dir, sim_cell.world.size);
+                    let neighborhood = sim_cell.chemistry.get_manifest().neighborhood;
+
+                    let dest_coord = neighborhood.coord_by_direction_offset(
+                        context.coord,
+                        &dir,
+                        sim_cell.world.size,
+                    );
                     //println!("dest coord ______________: {:?}", &dest_coord);
 
                     if let Some(_dest_coord) = dest_coord {
@@ -473,6 +485,49 @@ pub fn default_actions() -> Vec<ActionDefinition> {
                 },
             ),
         ),
+        ActionDefinition::new(
+            &"mate",
+            vec![
+                ActionParamDefinition {
+                    name: "mate_direction".to_string(),
+                    param_type: ActionParamType::Direction,
+                },
+                ActionParamDefinition {
+                    name: "direction".to_string(),
+                    param_type: ActionParamType::Direction,
+                },
+            ],
+            // like new_unit, but needs a neighboring mate.  when evolving in-sim the child gets a
+            // crossover of both parents' genomes
+            Arc::new(
+                |sim_cell: &mut SimCell, context: &ActionExecutionContext| -> bool {
+                    let mate_coord =
+                        match neighbor_unit_coord(sim_cell, context, &context.params[0]) {
+                            Some(coord) => coord,
+                            None => return false,
+                        };
+
+                    let neighborhood = sim_cell.chemistry.get_manifest().neighborhood;
+                    match neighborhood.coord_by_direction_offset(
+                        context.coord,
+                        &context.params[1].to_direction(),
+                        sim_cell.world.size,
+                    ) {
+                        Some(dest_coord) if !sim_cell.world.has_unit_at(&dest_coord) => {
+                            sim_cell.world.mate_units(
+                                context.coord,
+                                &mate_coord,
+                                &dest_coord,
+                                &sim_cell.unit_manifest,
+                                sim_cell.chemistry.as_ref(),
+                            );
+                            true
+                        }
+                        _ => false,
+                    }
+                },
+            ),
+        ),
         ActionDefinition::new(
             &"set_unit_resource",
             vec![
@@ -486,7 +541,7 @@ pub fn default_actions() -> Vec<ActionDefinition> {
                 },
             ],
             // execute action
-            Rc::new(
+            Arc::new(
                 |sim_cell: &mut SimCell, context: &ActionExecutionContext| -> bool {
                     let unit = sim_cell
                         .world
@@ -520,7 +575,7 @@ pub fn default_actions() -> Vec<ActionDefinition> {
                 },
             ],
             // execute action
-            Rc::new(
+            Arc::new(
                 |sim_cell: &mut SimCell, context: &ActionExecutionContext| -> bool {
                     let unit = sim_cell
                         .world
@@ -550,9 +605,205 @@ pub fn default_actions() -> Vec<ActionDefinition> {
                 },
             ),
         ),
+        ActionDefinition::new(
+            &"absorb_unit_resource",
+            vec![
+                ActionParamDefinition {
+                    name: "resource".to_string(),
+                    param_type: ActionParamType::UnitResourceIndex,
+                },
+                ActionParamDefinition {
+                    name: "amount".to_string(),
+                    param_type: ActionParamType::UnitResourceAmount,
+                },
+                ActionParamDefinition {
+                    name: "direction".to_string(),
+                    param_type: ActionParamType::Direction,
+                },
+            ],
+            // takes up to amount from the neighboring unit, never more than it has
+            Arc::new(
+                |sim_cell: &mut SimCell, context: &ActionExecutionContext| -> bool {
+                    let resource_idx = context.params[0].to_unit_resource_index();
+                    let amount = context.params[1].to_unit_resource_amount().max(0);
+
+                    match neighbor_unit_coord(sim_cell, context, &context.params[2]) {
+                        Some(target) => {
+                            move_unit_resource(
+                                sim_cell.world,
+                                &target,
+                                context.coord,
+                                resource_idx,
+                                amount,
+                            );
+                            true
+                        }
+                        None => false,
+                    }
+                },
+            ),
+        ),
+        ActionDefinition::new(
+            &"transfer_unit_resource",
+            vec![
+                ActionParamDefinition {
+                    name: "resource".to_string(),
+                    param_type: ActionParamType::UnitResourceIndex,
+                },
+                ActionParamDefinition {
+                    name: "amount".to_string(),
+                    param_type: ActionParamType::UnitResourceAmount,
+                },
+                ActionParamDefinition {
+                    name: "direction".to_string(),
+                    param_type: ActionParamType::Direction,
+                },
+            ],
+            // gives up to amount to the neighboring unit, never more than the unit has
+            Arc::new(
+                |sim_cell: &mut SimCell, context: &ActionExecutionContext| -> bool {
+                    let resource_idx = context.params[0].to_unit_resource_index();
+                    let amount = context.params[1].to_unit_resource_amount().max(0);
+
+                    match neighbor_unit_coord(sim_cell, context, &context.params[2]) {
+                        Some(target) => {
+                            move_unit_resource(
+                                sim_cell.world,
+                                context.coord,
+                                &target,
+                                resource_idx,
+                                amount,
+                            );
+                            true
+                        }
+                        None => false,
+                    }
+                },
+            ),
+        ),
+        ActionDefinition::new(
+            &"kill_unit",
+            vec![
+                ActionParamDefinition {
+                    name: "direction".to_string(),
+                    param_type: ActionParamType::Direction,
+                },
+                ActionParamDefinition {
+                    name: "resource".to_string(),
+                    param_type: ActionParamType::UnitResourceIndex,
+                },
+                ActionParamDefinition {
+                    name: "pct_absorbed".to_string(),
+                    param_type: ActionParamType::ConstantNum,
+                },
+            ],
+            // destroys the neighboring unit.  pct_absorbed percent of its resource goes to the
+            // killer and the rest is lost
+            Arc::new(
+                |sim_cell: &mut SimCell, context: &ActionExecutionContext| -> bool {
+                    let resource_idx = context.params[1].to_unit_resource_index();
+                    let pct_absorbed = context.params[2].to_constant().clamp(0, 100);
+
+                    match neighbor_unit_coord(sim_cell, context, &context.params[0]) {
+                        Some(target) => {
+                            kill_unit_at(
+                                sim_cell.world,
+                                context.coord,
+                                &target,
+                                resource_idx,
+                                pct_absorbed,
+                            );
+                            true
+                        }
+                        None => false,
+                    }
+                },
+            ),
+        ),
+        ActionDefinition::new(
+            &"emit_signal",
+            vec![
+                ActionParamDefinition {
+                    name: "signal".to_string(),
+                    param_type: ActionParamType::ConstantNum,
+                },
+                ActionParamDefinition {
+                    name: "strength".to_string(),
+                    param_type: ActionParamType::ConstantNum,
+                },
+            ],
+            // execute action
+            Arc::new(
+                |sim_cell: &mut SimCell, context: &ActionExecutionContext| -> bool {
+                    let manifest = sim_cell.chemistry.get_manifest();
+                    let signal = &manifest.signals[context.params[0].to_constant() as usize];
+
+                    emit_signal(
+                        sim_cell.world,
+                        context.coord,
+                        signal,
+                        context.params[1].to_constant(),
+                        &manifest.neighborhood,
+                    );
+                    true
+                },
+            ),
+        ),
     ]
 }
 
+/**
+ * The coord of the unit in the given direction, if there is one.
+ */
+pub fn neighbor_unit_coord(
+    sim_cell: &SimCell,
+    context: &ActionExecutionContext,
+    direction: &ActionParam,
+) -> Option<Coord> {
+    let neighborhood = sim_cell.chemistry.get_manifest().neighborhood;
+    neighborhood
+        .coord_by_direction_offset(
+            context.coord,
+            &direction.to_direction(),
+            sim_cell.world.size,
+        )
+        .filter(|coord| sim_cell.world.has_unit_at(coord))
+}
+
+/**
+ * Destroys the unit at target, handing pct_absorbed percent of its resource to the killer.
+ */
+pub fn kill_unit_at(
+    world: &mut World,
+    killer: &Coord,
+    target: &Coord,
+    resource_idx: UnitResourceIndex,
+    pct_absorbed: i32,
+) {
+    let amount = world.get_unit_resource_at(target, resource_idx);
+    let absorbed = amount.max(0) * pct_absorbed / 100;
+
+    world.destroy_unit(target);
+    world.add_unit_resource_at(killer, resource_idx, absorbed);
+}
+
+/**
+ * Moves up to amount of a resource between two units, so that nothing is created or lost.
+ */
+fn move_unit_resource(
+    world: &mut World,
+    from: &Coord,
+    to: &Coord,
+    resource_idx: UnitResourceIndex,
+    amount: UnitResourceAmount,
+) {
+    let available = world.get_unit_resource_at(from, resource_idx).max(0);
+    let amount = amount.min(available);
+
+    world.add_unit_resource_at(from, resource_idx, -amount);
+    world.add_unit_resource_at(to, resource_idx, amount);
+}
+
 pub struct ActionExecutionContext<'a> {
     pub coord: &'a Coord,
     pub params: &'a [ActionParam],
@@ -133,7 +133,12 @@ pub mod grow_unit {
         let params = vec![ActionParam::Direction(direction.clone())];
         let result = execute_action(&action, &src_coord, &mut sim, &params);
         assert_eq!(result, true, "The action failed");
-        let dest_coord = coord_by_direction_offset(&src_coord, &direction, sim.world.size).unwrap();
+        let dest_coord = sim
+            .chemistry
+            .get_manifest()
+            .neighborhood
+            .coord_by_direction_offset(&src_coord, &direction, sim.world.size)
+            .unwrap();
 
         assert_unit_at!(sim, &dest_coord);
         assert_unit_at!(sim, &src_coord);
@@ -149,6 +154,102 @@ pub mod grow_unit {
     }
 }
 
+pub mod unit_interactions {
+    use super::*;
+    use crate::{
+        chemistry::variants::cheese,
+        simulation::common::{builder::ChemistryBuilder, helpers::place_units::PlaceUnitsMethod},
+    };
+
+    fn two_unit_sim() -> Simulation {
+        SimulationBuilder::default()
+            .chemistry(ChemistryBuilder::with_key("cheese").build())
+            .size((5, 5))
+            .place_units_method(PlaceUnitsMethod::ManualSingleEntry {
+                attributes: None,
+                coords: vec![(2, 2), (2, 3)],
+            })
+            .unit_manifest(UnitManifest {
+                units: vec![UnitEntry::new("main", NullBehavior::construct())],
+            })
+            .to_simulation()
+    }
+
+    fn find_action(key: &str) -> ActionDefinition {
+        default_actions()
+            .into_iter()
+            .find(|a| a.key == key)
+            .unwrap()
+    }
+
+    #[test]
+    fn absorb_and_transfer() {
+        let mut sim = two_unit_sim();
+        let cheese = cheese::defs::UnitResourcesLookup::new().cheese;
+        sim.world.set_unit_resource_at(&(2, 2), cheese, 10);
+        sim.world.set_unit_resource_at(&(2, 3), cheese, 5);
+
+        let params = vec![
+            ActionParam::UnitResourceIndex(cheese),
+            ActionParam::UnitResourceAmount(8),
+            ActionParam::Direction(GridDirection::Up),
+        ];
+
+        // only takes what the neighbor has
+        assert!(execute_action(
+            &find_action("absorb_unit_resource"),
+            &(2, 2),
+            &mut sim,
+            &params
+        ));
+        assert_eq!(sim.world.get_unit_resource_at(&(2, 2), cheese), 15);
+        assert_eq!(sim.world.get_unit_resource_at(&(2, 3), cheese), 0);
+
+        assert!(execute_action(
+            &find_action("transfer_unit_resource"),
+            &(2, 2),
+            &mut sim,
+            &params
+        ));
+        assert_eq!(sim.world.get_unit_resource_at(&(2, 2), cheese), 7);
+        assert_eq!(sim.world.get_unit_resource_at(&(2, 3), cheese), 8);
+
+        // nobody to the left
+        let params = vec![
+            ActionParam::UnitResourceIndex(cheese),
+            ActionParam::UnitResourceAmount(8),
+            ActionParam::Direction(GridDirection::Left),
+        ];
+        assert!(!execute_action(
+            &find_action("absorb_unit_resource"),
+            &(2, 2),
+            &mut sim,
+            &params
+        ));
+    }
+
+    #[test]
+    fn kill() {
+        let mut sim = two_unit_sim();
+        let cheese = cheese::defs::UnitResourcesLookup::new().cheese;
+        sim.world.set_unit_resource_at(&(2, 2), cheese, 10);
+        sim.world.set_unit_resource_at(&(2, 3), cheese, 20);
+
+        let params = vec![
+            ActionParam::Direction(GridDirection::Up),
+            ActionParam::UnitResourceIndex(cheese),
+            ActionParam::Constant(50),
+        ];
+        let action = find_action("kill_unit");
+
+        assert!(execute_action(&action, &(2, 2), &mut sim, &params));
+        assert!(!sim.world.has_unit_at(&(2, 3)));
+        assert_eq!(sim.world.get_unit_resource_at(&(2, 2), cheese), 20);
+
+        assert!(!execute_action(&action, &(2, 2), &mut sim, &params));
+    }
+}
+
 // pub fn can_execute(action: &ActionDefinition, coord: &Coord, simulation: &Simulation, params: &[ActionParam]) -> bool {
 //
 //     //unit_manifest: &UnitManifest, chemistry: &ChemistryInstance
@@ -1,3 +1,5 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -26,6 +28,8 @@ use crate::simulation::{
 };
 use crate::util::Coord;
 
+use super::helpers::signals::SignalDefinition;
+
 // use self::defs::ConfigurableParam;
 
 // pub enum CheeseChemistryParamName {
@@ -82,6 +86,10 @@ pub enum ChemistryConfigValue {
     Boolean(bool),
     Float64(f64),
     Direction(GridDirection),
+    Neighborhood(GridNeighborhood),
+    UnitResourceKey(String),
+    UpdateMode(UnitUpdateMode),
+    Signals(Vec<SignalDefinition>),
 }
 
 impl ChemistryConfigValue {
@@ -128,6 +136,95 @@ impl ChemistryConfigValue {
             }
         }
     }
+
+    pub fn unwrap_unit_resource_key(&self) -> String {
+        match self {
+            Self::UnitResourceKey(x) => x.clone(),
+            _ => {
+                panic!("Expected a unit resource key but found a {:?}", self);
+            }
+        }
+    }
+
+    pub fn unwrap_update_mode(&self) -> UnitUpdateMode {
+        match self {
+            Self::UpdateMode(x) => x.clone(),
+            _ => {
+                panic!("Expected an update mode but found a {:?}", self);
+            }
+        }
+    }
+
+    pub fn unwrap_signals(&self) -> Vec<SignalDefinition> {
+        match self {
+            Self::Signals(x) => x.clone(),
+            _ => {
+                panic!("Expected signals but found a {:?}", self);
+            }
+        }
+    }
+
+    pub fn unwrap_neighborhood(&self) -> GridNeighborhood {
+        match self {
+            Self::Neighborhood(x) => *x,
+            _ => {
+                panic!("Expected a neighborhood but found a {:?}", self);
+            }
+        }
+    }
+}
+
+/**
+ * Chemistries select their neighborhood through the "neighborhood" configuration key.  When
+ * it isn't set, the von neumann neighborhood is used.
+ */
+pub fn neighborhood_from_config(config: &ChemistryConfiguration) -> GridNeighborhood {
+    config
+        .get("neighborhood")
+        .map(|value| value.unwrap_neighborhood())
+        .unwrap_or_default()
+}
+
+/**
+ * Set through the "update_mode" configuration key, units are updated sequentially by default.
+ */
+pub fn update_mode_from_config(config: &ChemistryConfiguration) -> UnitUpdateMode {
+    config
+        .get("update_mode")
+        .map(|value| value.unwrap_update_mode())
+        .unwrap_or_default()
+}
+
+/**
+ * Set through the "update_seed" configuration key, seeds the random tie breaking of the
+ * simultaneous update modes so that a run can be replayed.
+ */
+pub fn update_seed_from_config(config: &ChemistryConfiguration) -> u64 {
+    config
+        .get("update_seed")
+        .map(|value| value.unwrap_integer())
+        .unwrap_or(0)
+}
+
+/**
+ * The separate random draws made while setting up a world, so that they don't repeat each
+ * other under the same world seed.
+ */
+pub const WORLD_RNG_INIT: u64 = 0;
+pub const WORLD_RNG_PLACEMENT: u64 = 1;
+
+/**
+ * Set through the "world_seed" configuration key, seeds the initial position resources and the
+ * random placement of units so that a world can be recreated.  Without it every world is a new
+ * random draw.
+ */
+pub fn world_rng_from_config(config: &ChemistryConfiguration, stream: u64) -> StdRng {
+    match config.get("world_seed") {
+        Some(value) => {
+            StdRng::seed_from_u64(value.unwrap_integer().wrapping_mul(2).wrapping_add(stream))
+        }
+        None => StdRng::from_entropy(),
+    }
 }
 
 pub fn convert_configurable_to_action_param(
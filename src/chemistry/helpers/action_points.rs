@@ -0,0 +1,302 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use crate::chemistry::reactions::ReactionCall;
+use crate::chemistry::ChemistryConfiguration;
+use crate::chemistry::ChemistryManifest;
+
+pub const DEFAULT_ACTION_POINTS_PER_TICK: u64 = 1;
+pub const DEFAULT_REACTION_COST: u64 = 1;
+
+/**
+ * Determines how many reactions a unit can perform in a single tick.  Each unit gets
+ * `points_per_tick` action points per tick and each reaction costs some of those points.
+ * The defaults give each unit exactly one reaction per tick.
+ *
+ * Configured through the chemistry configuration:
+ *  - action_points_per_tick: Integer
+ *  - reaction_cost::<reaction_key>: Integer
+ */
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ActionPointEconomy {
+    pub points_per_tick: u64,
+
+    // indexed by reaction id
+    pub reaction_costs: Vec<u64>,
+}
+
+impl Default for ActionPointEconomy {
+    fn default() -> Self {
+        Self {
+            points_per_tick: DEFAULT_ACTION_POINTS_PER_TICK,
+            reaction_costs: vec![],
+        }
+    }
+}
+
+impl ActionPointEconomy {
+    pub fn from_config(config: &ChemistryConfiguration, manifest: &ChemistryManifest) -> Self {
+        let points_per_tick = config
+            .get("action_points_per_tick")
+            .map(|val| val.unwrap_integer())
+            .unwrap_or(DEFAULT_ACTION_POINTS_PER_TICK);
+
+        let reaction_costs = manifest
+            .reactions
+            .iter()
+            .map(|reaction| {
+                config
+                    .get(&format!("reaction_cost::{}", reaction.key))
+                    .map(|val| val.unwrap_integer())
+                    .unwrap_or(DEFAULT_REACTION_COST)
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            points_per_tick,
+            reaction_costs,
+        }
+    }
+
+    /**
+     * Whether the reactions already cost as much as a unit can spend in a tick, after which
+     * the unit's behavior need not call any further ones.
+     */
+    pub fn is_covered_by(&self, reactions: &[ReactionCall]) -> bool {
+        let cost: u64 = reactions.iter().map(|r| self.reaction_cost(r)).sum();
+        cost >= self.points_per_tick
+    }
+
+    pub fn reaction_cost(&self, reaction_call: &ReactionCall) -> u64 {
+        *self
+            .reaction_costs
+            .get(reaction_call.0 as usize)
+            .unwrap_or(&DEFAULT_REACTION_COST)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReactionOrdering {
+    AsEmitted,
+
+    // shuffled from the seed passed to `schedule_unit_reactions`
+    Shuffled,
+    CheapestFirst,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReactionConflictPolicy {
+    /**
+     * Every affordable reaction is attempted, even when an earlier one failed.
+     */
+    ExecuteAll,
+
+    /**
+     * The remaining reactions are dropped as soon as one of them fails.
+     */
+    StopOnFailure,
+
+    /**
+     * Repeated calls of the same reaction are dropped, so that eg. a unit can only move once.
+     */
+    FirstOfEachReaction,
+}
+
+/**
+ * Orders the reactions, applies the conflict policy and drops whatever doesn't fit within
+ * the unit's action points.  Failures are handled at execution time by the caller.
+ */
+pub fn schedule_unit_reactions(
+    reactions: &[ReactionCall],
+    economy: &ActionPointEconomy,
+    ordering: &ReactionOrdering,
+    conflict_policy: &ReactionConflictPolicy,
+    seed: u64,
+) -> Vec<ReactionCall> {
+    schedule_unit_reaction_indices(reactions, economy, ordering, conflict_policy, seed)
+        .into_iter()
+        .map(|i| reactions[i])
+        .collect()
+}
+
+/**
+ * Like `schedule_unit_reactions`, but returns the indices of the scheduled reactions, so that
+ * the caller can tell which of them were executed.
+ */
+pub fn schedule_unit_reaction_indices(
+    reactions: &[ReactionCall],
+    economy: &ActionPointEconomy,
+    ordering: &ReactionOrdering,
+    conflict_policy: &ReactionConflictPolicy,
+    seed: u64,
+) -> Vec<usize> {
+    let mut ordered = (0..reactions.len()).collect::<Vec<_>>();
+
+    match ordering {
+        ReactionOrdering::AsEmitted => {}
+        ReactionOrdering::Shuffled => {
+            ordered.shuffle(&mut StdRng::seed_from_u64(seed));
+        }
+        ReactionOrdering::CheapestFirst => {
+            ordered.sort_by_key(|&i| economy.reaction_cost(&reactions[i]));
+        }
+    }
+
+    let mut remaining_points = economy.points_per_tick;
+    let mut scheduled: Vec<usize> = vec![];
+
+    for i in ordered {
+        let reaction_call = &reactions[i];
+        if *conflict_policy == ReactionConflictPolicy::FirstOfEachReaction
+            && scheduled.iter().any(|&j| reactions[j].0 == reaction_call.0)
+        {
+            continue;
+        }
+
+        let cost = economy.reaction_cost(reaction_call);
+        if cost <= remaining_points {
+            remaining_points -= cost;
+            scheduled.push(i);
+        }
+    }
+
+    scheduled
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    fn economy() -> ActionPointEconomy {
+        ActionPointEconomy {
+            points_per_tick: 4,
+            reaction_costs: vec![1, 2, 3],
+        }
+    }
+
+    #[test]
+    fn schedule_within_points() {
+        let reactions = vec![(2, 0, 0, 0), (1, 0, 0, 0), (0, 0, 0, 0)];
+
+        assert_eq!(
+            schedule_unit_reactions(
+                &reactions,
+                &economy(),
+                &ReactionOrdering::AsEmitted,
+                &ReactionConflictPolicy::ExecuteAll,
+                0
+            ),
+            vec![(2, 0, 0, 0), (0, 0, 0, 0)]
+        );
+
+        assert_eq!(
+            schedule_unit_reactions(
+                &reactions,
+                &economy(),
+                &ReactionOrdering::CheapestFirst,
+                &ReactionConflictPolicy::ExecuteAll,
+                0
+            ),
+            vec![(0, 0, 0, 0), (1, 0, 0, 0)]
+        );
+    }
+
+    #[test]
+    fn schedule_first_of_each_reaction() {
+        let reactions = vec![(0, 1, 0, 0), (0, 2, 0, 0), (1, 0, 0, 0)];
+
+        assert_eq!(
+            schedule_unit_reactions(
+                &reactions,
+                &economy(),
+                &ReactionOrdering::AsEmitted,
+                &ReactionConflictPolicy::FirstOfEachReaction,
+                0
+            ),
+            vec![(0, 1, 0, 0), (1, 0, 0, 0)]
+        );
+    }
+
+    #[test]
+    fn reactions_follow_a_moving_unit() {
+        use crate::biology::unit_behavior::UnitBehaviorResult;
+        use crate::chemistry::builder::ChemistryBuilder;
+        use crate::chemistry::variants::cheese::defs;
+        use crate::chemistry::ChemistryConfigBuilder;
+        use crate::simulation::common::helpers::place_units::PlaceUnitsMethod;
+        use crate::simulation::common::*;
+
+        let config = ChemistryConfigBuilder::new()
+            .set_integer("action_points_per_tick", 2)
+            .build();
+        let mut sim = SimulationBuilder::default()
+            .chemistry(ChemistryBuilder::with_key("cheese").config(config).build())
+            .size((5, 5))
+            .place_units_method(PlaceUnitsMethod::ManualSingleEntry {
+                attributes: None,
+                coords: vec![(2, 2)],
+            })
+            .unit_manifest(UnitManifest {
+                units: vec![UnitEntry::new("main", NullBehavior::construct())],
+            })
+            .to_simulation();
+
+        let cheese = defs::UnitResourcesLookup::new().cheese;
+        sim.world.set_unit_resource_at(&(2, 2), cheese, 100);
+
+        // up, then right from wherever the first move left the unit
+        let result = UnitBehaviorResult::with_reactions(vec![
+            (defs::REACTION_ID_MOVE_UNIT, 0, 0, 0),
+            (defs::REACTION_ID_MOVE_UNIT, 1, 0, 0),
+        ]);
+        let mut sim_cell = sim.editable();
+        let chemistry = sim_cell.chemistry;
+        chemistry.execute_unit_reaction(&mut sim_cell, &(2, 2), &result);
+
+        assert!(!sim.world.has_unit_at(&(2, 2)));
+        assert!(!sim.world.has_unit_at(&(2, 3)));
+        assert!(sim.world.has_unit_at(&(3, 3)));
+    }
+
+    #[test]
+    fn schedule_shuffled_from_seed() {
+        let economy = ActionPointEconomy {
+            points_per_tick: 1,
+            reaction_costs: vec![],
+        };
+        let reactions = (0..8).map(|i| (0, i, 0, 0)).collect::<Vec<_>>();
+        let schedule = |seed: u64| {
+            schedule_unit_reactions(
+                &reactions,
+                &economy,
+                &ReactionOrdering::Shuffled,
+                &ReactionConflictPolicy::ExecuteAll,
+                seed,
+            )
+        };
+
+        let picked = (0..16).map(|seed| schedule(seed)[0]).collect::<Vec<_>>();
+        for seed in 0..16 {
+            assert_eq!(schedule(seed), vec![picked[seed as usize]]);
+        }
+        assert!(picked.iter().any(|r| *r != picked[0]));
+    }
+
+    #[test]
+    fn default_economy_allows_one_reaction() {
+        let reactions = vec![(0, 1, 0, 0), (0, 2, 0, 0)];
+
+        assert_eq!(
+            schedule_unit_reactions(
+                &reactions,
+                &ActionPointEconomy::default(),
+                &ReactionOrdering::AsEmitted,
+                &ReactionConflictPolicy::ExecuteAll,
+                0
+            ),
+            vec![(0, 1, 0, 0)]
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use crate::chemistry::ChemistryConfiguration;
+use crate::chemistry::ChemistryManifest;
+use crate::simulation::unit::{Unit, UnitResourceAmount, UnitResourceIndex};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_MAX_EXECUTION_POINTS: u64 = 100;
+
+/**
+ * Determines how many execution points a unit's behavior gets to spend on each tick, and what
+ * those points cost.
+ *
+ * When `resource` is set, every unit of that resource buys `points_per_resource` execution
+ * points (up to `max_points`) and the points consumed by the behavior are deducted from the
+ * resource afterwards.  Otherwise every unit gets `max_points` for free.
+ *
+ * Configured through the chemistry configuration:
+ *  - execution_point_resource: UnitResourceKey
+ *  - execution_points_per_resource: Integer
+ *  - max_execution_points: Integer
+ */
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionPointBudget {
+    pub resource: Option<UnitResourceIndex>,
+    pub points_per_resource: u64,
+    pub max_points: u64,
+}
+
+impl Default for ExecutionPointBudget {
+    fn default() -> Self {
+        Self {
+            resource: None,
+            points_per_resource: 1,
+            max_points: DEFAULT_MAX_EXECUTION_POINTS,
+        }
+    }
+}
+
+impl ExecutionPointBudget {
+    pub fn from_config(config: &ChemistryConfiguration, manifest: &ChemistryManifest) -> Self {
+        let defaults = Self::default();
+
+        let resource = config.get("execution_point_resource").map(|val| {
+            manifest
+                .unit_resource_by_key(&val.unwrap_unit_resource_key())
+                .id
+        });
+
+        let points_per_resource = config
+            .get("execution_points_per_resource")
+            .map(|val| val.unwrap_integer())
+            .unwrap_or(defaults.points_per_resource);
+
+        if points_per_resource == 0 {
+            panic!("execution_points_per_resource must be greater than zero");
+        }
+
+        let max_points = config
+            .get("max_execution_points")
+            .map(|val| val.unwrap_integer())
+            .unwrap_or(defaults.max_points);
+
+        Self {
+            resource,
+            points_per_resource,
+            max_points,
+        }
+    }
+
+    pub fn points_available(&self, unit: &Unit) -> u64 {
+        match self.resource {
+            Some(resource_idx) => {
+                let amount = unit.get_resource(resource_idx).max(0) as u64;
+                (amount * self.points_per_resource).min(self.max_points)
+            }
+            None => self.max_points,
+        }
+    }
+
+    /**
+     * The amount of the resource that pays for the given number of points.  Partially used
+     * resource units are charged in full.
+     */
+    pub fn resource_cost(&self, points: u64) -> UnitResourceAmount {
+        ((points + self.points_per_resource - 1) / self.points_per_resource) as UnitResourceAmount
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::chemistry::{CheeseChemistry, Chemistry, ChemistryConfigBuilder};
+
+    #[test]
+    fn budget_from_config() {
+        let config = ChemistryConfigBuilder::new()
+            .set_unit_resource_key("execution_point_resource", "cheese")
+            .set_integer("execution_points_per_resource", 4)
+            .set_integer("max_execution_points", 50)
+            .build();
+        let chemistry = CheeseChemistry::construct(config);
+        let budget = &chemistry.get_manifest().execution_point_budget;
+
+        assert_eq!(
+            budget.resource,
+            Some(chemistry.get_manifest().unit_resource_by_key("cheese").id)
+        );
+        assert_eq!(budget.resource_cost(0), 0);
+        assert_eq!(budget.resource_cost(4), 1);
+        assert_eq!(budget.resource_cost(5), 2);
+    }
+
+    #[test]
+    fn budget_defaults_to_free_points() {
+        let chemistry = CheeseChemistry::construct(ChemistryConfiguration::new());
+        assert_eq!(
+            chemistry.get_manifest().execution_point_budget,
+            ExecutionPointBudget::default()
+        );
+    }
+}
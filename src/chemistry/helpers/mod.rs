@@ -1,4 +1,7 @@
+pub mod action_points;
+pub mod execution_points;
 pub mod place_units;
 pub mod resource_allocation;
 pub mod resource_transits;
+pub mod signals;
 pub mod unit_behavior_execution;
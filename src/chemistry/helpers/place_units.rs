@@ -1,3 +1,4 @@
+use crate::chemistry::config::{world_rng_from_config, WORLD_RNG_PLACEMENT};
 use crate::simulation::common::{ChemistryInstance, Coord, SimCell, UnitEntryData, UnitManifest};
 use crate::simulation::unit::UnitAttributes;
 use crate::simulation::world::*;
@@ -185,7 +186,7 @@ pub fn place_pct_region(
 ) {
     let c = chemistry.as_ref();
     let manifest = unit_manifest.clone();
-    let mut rng = rand::thread_rng();
+    let mut rng = world_rng_from_config(&c.get_configuration(), WORLD_RNG_PLACEMENT);
     let mut attempts = 0;
 
     let rect = [
@@ -302,6 +303,33 @@ mod tests {
             panic!("incorrect number of units placed: {}", unit_count);
         }
     }
+
+    #[test]
+    fn test_seeded_placement() {
+        use crate::chemistry::ChemistryConfigBuilder;
+        use crate::simulation::iterators::CoordIterator;
+
+        // the cheese chemistry places its units and cheese randomly by default
+        let unit_coords = |world_seed: u64| {
+            let config = ChemistryConfigBuilder::new()
+                .set_world_seed(world_seed)
+                .build();
+            let sim = SimulationBuilder::default()
+                .size((20, 20))
+                .chemistry(ChemistryBuilder::with_key("cheese").config(config).build())
+                .unit_manifest(UnitManifest {
+                    units: vec![UnitEntry::new("main", NullBehavior::construct())],
+                })
+                .to_simulation();
+
+            CoordIterator::new(sim.world.size)
+                .filter(|coord| sim.world.has_unit_at(coord))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(unit_coords(1), unit_coords(1));
+        assert!((2..10).any(|seed| unit_coords(seed) != unit_coords(1)));
+    }
 }
 
 // #[derive(Clone)]
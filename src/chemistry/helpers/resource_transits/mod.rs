@@ -37,25 +37,17 @@ pub fn calculate_linear_diff_transits(
     let diffusion_factor = 10;
     let known_resources = &chemistry.get_manifest().unit_resources;
 
+    let neighborhood = chemistry.get_manifest().neighborhood;
+    let directions = neighborhood.directions();
+
     let mut our_delta = chemistry.get_manifest().empty_unit_resources();
-    let mut their_deltas = vec![
-        chemistry.get_manifest().empty_unit_resources(),
-        chemistry.get_manifest().empty_unit_resources(),
-        chemistry.get_manifest().empty_unit_resources(),
-        chemistry.get_manifest().empty_unit_resources(),
-    ];
+    let mut their_deltas = vec![chemistry.get_manifest().empty_unit_resources(); directions.len()];
 
     let mut result = vec![];
-    let directions = vec![
-        GridDirection::Up,
-        GridDirection::Right,
-        GridDirection::Down,
-        GridDirection::Left,
-    ];
 
     for i in 0..directions.len() {
         let dir = directions[i].clone();
-        let __coord = coord_by_direction_offset(&coord, &dir, world.size);
+        let __coord = neighborhood.coord_by_direction_offset(&coord, &dir, world.size);
         if __coord.is_none() {
             continue;
         }
@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+
+use crate::chemistry::actions::{ActionParam, ActionParamType};
+use crate::chemistry::properties::{PositionResourceDefinition, ResourceAmount};
+use crate::chemistry::reactions::{ReactionDefinition, ReagentDefinition};
+use crate::chemistry::ChemistryConfiguration;
+use crate::chemistry::ChemistryManifest;
+use crate::simulation::position::PositionResourceIndex;
+use crate::simulation::world::World;
+use crate::util::{coord_by_coord_offset, Coord, GridNeighborhood};
+
+use crate::biology::sensor_manifest::neighborhood_sensor_offsets;
+
+/**
+ * A position level layer that units can write to with an emit reaction and read through
+ * sensors.  Each signal is stored as a position resource that loses decay_per_tick every tick
+ * until it reaches 0.  Emitting reaches every cell within radius, weaker the further away the
+ * cell is, which gives the receiving units a gradient to follow.
+ *
+ * Configured through the chemistry configuration:
+ *  - signals: Signals
+ */
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SignalDefinition {
+    pub key: String,
+    pub decay_per_tick: ResourceAmount,
+    pub max_strength: ResourceAmount,
+    pub radius: u8,
+
+    // set when the manifest is normalized
+    #[serde(default)]
+    pub position_resource_id: PositionResourceIndex,
+}
+
+impl SignalDefinition {
+    pub fn new(
+        key: &str,
+        decay_per_tick: ResourceAmount,
+        max_strength: ResourceAmount,
+        radius: u8,
+    ) -> Self {
+        Self {
+            key: key.to_string(),
+            decay_per_tick,
+            max_strength,
+            radius,
+            position_resource_id: 0,
+        }
+    }
+
+    pub fn resource_key(&self) -> String {
+        format!("signal_{}", self.key)
+    }
+
+    pub fn reaction_key(&self) -> String {
+        format!("emit_{}", self.key)
+    }
+}
+
+pub fn signals_from_config(config: &ChemistryConfiguration) -> Vec<SignalDefinition> {
+    config
+        .get("signals")
+        .map(|value| value.unwrap_signals())
+        .unwrap_or_default()
+}
+
+/**
+ * Adds a position resource and an emit reaction for each configured signal, after the
+ * chemistry's own resources and reactions, so chemistries without signals are unchanged.  Raw
+ * genomes evolved without signals need to be translated once signals are configured, see
+ * GenomeTranslator.
+ */
+pub fn add_signals_to_manifest(manifest: &mut ChemistryManifest, config: &ChemistryConfiguration) {
+    manifest.signals = signals_from_config(config);
+
+    for (i, signal) in manifest.signals.iter_mut().enumerate() {
+        let resource_key = signal.resource_key();
+        signal.position_resource_id = match manifest
+            .position_resources
+            .iter()
+            .position(|res| res.key == resource_key)
+        {
+            Some(id) => id,
+            None => {
+                let id = manifest.position_resources.len();
+                manifest
+                    .position_resources
+                    .push(PositionResourceDefinition::new(&resource_key, false, id));
+                id
+            }
+        };
+
+        let reaction_key = signal.reaction_key();
+        if !manifest.reactions.iter().any(|r| r.key == reaction_key) {
+            manifest.reactions.push(ReactionDefinition::new(
+                &reaction_key,
+                vec![ReagentDefinition::new(
+                    "emit_signal",
+                    vec![
+                        ActionParam::Constant(i as i32),
+                        ActionParam::UnitBehaviorArgument(ActionParamType::ConstantNum),
+                    ],
+                )],
+            ));
+        }
+    }
+}
+
+/**
+ * Adds to the signal around coord, on top of what's already there.  Cells at distance d of the
+ * radius r receive strength * (r + 1 - d) / (r + 1).
+ */
+pub fn emit_signal(
+    world: &mut World,
+    coord: &Coord,
+    signal: &SignalDefinition,
+    strength: ResourceAmount,
+    neighborhood: &GridNeighborhood,
+) {
+    let strength = strength.clamp(0, signal.max_strength);
+    let falloff = signal.radius as ResourceAmount + 1;
+
+    for offset in neighborhood_sensor_offsets(neighborhood, signal.radius as i32) {
+        let target = match coord_by_coord_offset(coord, offset, world.size) {
+            Some(target) => target,
+            None => continue,
+        };
+
+        let distance = neighborhood.distance(offset) as ResourceAmount;
+        let amount = strength * (falloff - distance) / falloff;
+        if amount <= 0 {
+            continue;
+        }
+
+        let idx = signal.position_resource_id;
+        let current = world.get_pos_resource_at(&target, idx);
+        world.set_pos_resource_at(&target, idx, (current + amount).min(signal.max_strength));
+        world.set_pos_resource_tab_offset(&target, idx, -signal.decay_per_tick, None);
+        world.set_pos_resource_tab_min(&target, idx, Some(0));
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::chemistry::builder::ChemistryBuilder;
+    use crate::chemistry::ChemistryConfigBuilder;
+
+    #[test]
+    fn signals() {
+        let config = ChemistryConfigBuilder::new()
+            .add_signal(SignalDefinition::new("alarm", 2, 100, 1))
+            .build();
+        let chemistry = ChemistryBuilder::with_key("cheese").config(config).build();
+        let manifest = chemistry.get_manifest();
+
+        let signal = manifest.signals[0].clone();
+        assert_eq!(
+            manifest.position_resources[signal.position_resource_id].key,
+            "signal_alarm"
+        );
+        assert_eq!(manifest.reactions.last().unwrap().key, "emit_alarm");
+        assert!(manifest
+            .all_properties
+            .iter()
+            .any(|prop| prop.long_key == "pos_res::signal_alarm"));
+
+        let mut world = World::new((5, 5), &chemistry);
+        let idx = signal.position_resource_id;
+        emit_signal(&mut world, &(2, 2), &signal, 80, &manifest.neighborhood);
+        emit_signal(&mut world, &(2, 2), &signal, 80, &manifest.neighborhood);
+        assert_eq!(world.get_pos_resource_at(&(2, 2), idx), 100);
+        assert_eq!(world.get_pos_resource_at(&(2, 3), idx), 80);
+        assert_eq!(world.get_pos_resource_at(&(3, 3), idx), 0);
+        assert_eq!(world.get_pos_resource_at(&(2, 4), idx), 0);
+
+        world.tick += 10;
+        assert_eq!(world.get_pos_resource_at(&(2, 2), idx), 80);
+        world.tick += 100;
+        assert_eq!(world.get_pos_resource_at(&(2, 2), idx), 0);
+    }
+}
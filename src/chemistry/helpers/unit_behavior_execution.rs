@@ -1,4 +1,5 @@
-use crate::chemistry::reactions::execute_reaction;
+use crate::biology::unit_behavior::UnitBehaviorResult;
+use crate::chemistry::reactions::{execute_reaction, reaction_target_coords, ReactionCall};
 use crate::chemistry::variants::{CheeseChemistry, NanobotsChemistry};
 use crate::chemistry::Chemistry;
 use crate::simulation::common::CoordIterator;
@@ -9,11 +10,85 @@ use crate::simulation::unit::{add_resources_to, UnitAttributes, UnitResources};
 use crate::simulation::unit_entry::UnitEntryId;
 use crate::util::text_grid::TextGridOptions;
 use crate::util::*;
+use ndarray::parallel::prelude::*;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
 use rand::Rng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+/**
+ * How the units' behaviors are applied to the world on each tick.
+ */
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum UnitUpdateMode {
+    /**
+     * Units decide and act one at a time in row order, each seeing the changes made by the
+     * units before it.
+     */
+    Sequential,
+
+    /**
+     * Every unit decides against the same world state, then the intents are resolved against
+     * each other and executed.  Removes the bias towards units that come first in scan order.
+     */
+    Simultaneous(IntentConflictPolicy),
+
+    /**
+     * Like Simultaneous, but the world is split into square tiles of the given size whose
+     * units decide and whose conflicts are resolved in parallel.  Intents reaching across a
+     * tile boundary are resolved afterwards, in tile order.
+     */
+    Tiled(IntentConflictPolicy, u32),
+}
+
+impl Default for UnitUpdateMode {
+    fn default() -> Self {
+        UnitUpdateMode::Sequential
+    }
+}
+
+/**
+ * Decides which intent goes through when several units reach into the same cell.
+ */
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum IntentConflictPolicy {
+    // priority is shuffled from the manifest's update_seed and the tick
+    RandomPriority,
+
+    // the unit holding more of the given resource wins, ties are broken randomly
+    ResourceWeighted(String),
+
+    // none of the competing intents are executed
+    CancelBoth,
+}
+
+/**
+ * What a unit decided to do during the first phase of a simultaneous update.
+ */
+pub struct UnitIntent {
+    pub coord: Coord,
+    pub entry_id: UnitEntryId,
+    pub result: UnitBehaviorResult,
+
+    // every cell the reactions might touch, see `intent_target_coords`
+    pub targets: Vec<Coord>,
+}
+
 pub fn behavior_execution(sim: &mut SimCell) {
+    match sim.chemistry.get_manifest().update_mode.clone() {
+        UnitUpdateMode::Sequential => sequential_behavior_execution(sim),
+        UnitUpdateMode::Simultaneous(policy) => simultaneous_behavior_execution(sim, &policy, None),
+        UnitUpdateMode::Tiled(policy, tile_size) => {
+            simultaneous_behavior_execution(sim, &policy, Some(tile_size))
+        }
+    }
+}
+
+pub fn sequential_behavior_execution(sim: &mut SimCell) {
     //let mut rng = rand::thread_rng();
 
     for coord in CoordIterator::new(sim.world.size) {
@@ -45,16 +120,15 @@ pub fn behavior_execution(sim: &mut SimCell) {
         sim.world.set_unit_last_update_tick(&coord, sim.world.tick);
 
         let entry = &sim.unit_manifest.units[entry_id];
-        let result = entry.behavior.borrow_mut().get_behavior(
-            &coord,
-            &sim.attributes,
-            &sim.world,
-            sim.chemistry,
-        );
+        let result =
+            entry
+                .behavior
+                .get_behavior(&coord, &sim.attributes, &sim.world, sim.chemistry);
 
         // chemistry.consume_execution_points(result.consumed_execution_points);
 
-        sim.chemistry.execute_unit_reaction(sim, &coord, &result);
+        let executed = sim.chemistry.execute_unit_reaction(sim, &coord, &result);
+        entry.behavior.mark_executed_reactions(&result, &executed);
 
         // for i in 0..result.reactions.len().min(1) {
         //     let reaction_call = result.reactions[i];
@@ -73,10 +147,416 @@ pub fn behavior_execution(sim: &mut SimCell) {
     }
 }
 
+pub fn simultaneous_behavior_execution(
+    sim: &mut SimCell,
+    policy: &IntentConflictPolicy,
+    tile_size: Option<u32>,
+) {
+    let intents = collect_unit_intents(sim, tile_size);
+
+    let seed = sim.chemistry.get_manifest().update_seed;
+    let mut rng = StdRng::seed_from_u64(seed.wrapping_add(sim.world.tick));
+    let mut priority = (0..intents.len()).collect::<Vec<_>>();
+    priority.shuffle(&mut rng);
+
+    if let IntentConflictPolicy::ResourceWeighted(resource_key) = policy {
+        let resource_idx = sim
+            .chemistry
+            .get_manifest()
+            .unit_resource_by_key(resource_key)
+            .id;
+
+        // the sort is stable, so the shuffle above breaks the ties
+        priority.sort_by_key(|&i| {
+            -sim.world
+                .get_unit_resource_at(&intents[i].coord, resource_idx)
+        });
+    }
+
+    let targets = intents
+        .iter()
+        .map(|intent| intent.targets.clone())
+        .collect::<Vec<_>>();
+    let cancel_both = *policy == IntentConflictPolicy::CancelBoth;
+    let winners = match tile_size {
+        Some(tile_size) => {
+            let coords = intents
+                .iter()
+                .map(|intent| intent.coord)
+                .collect::<Vec<_>>();
+            resolve_tiled_intent_conflicts(&coords, &targets, &priority, cancel_both, tile_size)
+        }
+        None => resolve_intent_conflicts(&targets, &priority, cancel_both),
+    };
+
+    let mut is_winner = vec![false; intents.len()];
+    for &i in winners.iter() {
+        is_winner[i] = true;
+    }
+
+    // cancelled units still pay for the thinking they did
+    for (i, intent) in intents.iter().enumerate() {
+        if !is_winner[i] {
+            sim.chemistry.deduct_unit_execution_points(
+                sim,
+                &intent.coord,
+                intent.result.consumed_execution_points,
+            );
+        }
+    }
+
+    // the winners' targets don't overlap, so none of them sees the changes made by another
+    for i in winners {
+        let intent = &intents[i];
+
+        let executed = sim
+            .chemistry
+            .execute_unit_reaction(sim, &intent.coord, &intent.result);
+        sim.unit_manifest.units[intent.entry_id]
+            .behavior
+            .mark_executed_reactions(&intent.result, &executed);
+    }
+}
+
+/**
+ * Phase one of a simultaneous update: every unit decides what to do without the world
+ * changing underneath it.  With a tile size, the tiles decide in parallel and the intents
+ * come back in tile order, each tile's in row order.
+ */
+pub fn collect_unit_intents(sim: &mut SimCell, tile_size: Option<u32>) -> Vec<UnitIntent> {
+    let manifest = sim.chemistry.get_manifest();
+    let world: &World = sim.world;
+    let attributes: &SimulationAttributes = sim.attributes;
+    let chemistry = sim.chemistry;
+    let unit_manifest = sim.unit_manifest;
+
+    let intent_at = |coord: Coord| -> Option<UnitIntent> {
+        let entry_id = match world.get_unit_at(&coord) {
+            Some(unit) if unit.last_update_tick < world.tick => unit.entry_id,
+            _ => return None,
+        };
+
+        let entry = &unit_manifest.units[entry_id];
+        let result = entry
+            .behavior
+            .get_behavior(&coord, attributes, world, chemistry);
+
+        let targets = intent_target_coords(&result.reactions, manifest, &coord, world.size);
+
+        Some(UnitIntent {
+            coord,
+            entry_id,
+            result,
+            targets,
+        })
+    };
+
+    let intents = match tile_size {
+        Some(tile_size) => tile_coords(world.size, tile_size)
+            .par_iter()
+            .map(|coords| {
+                coords
+                    .iter()
+                    .filter_map(|coord| intent_at(*coord))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>(),
+        None => CoordIterator::new(world.size)
+            .filter_map(intent_at)
+            .collect::<Vec<_>>(),
+    };
+
+    for intent in intents.iter() {
+        sim.world
+            .set_unit_last_update_tick(&intent.coord, sim.world.tick);
+    }
+
+    intents
+}
+
+/**
+ * The cells a unit's reactions might touch: its own cell and every cell a direction param
+ * reaches into.  A reaction may move the unit, so the reactions are also followed from each
+ * cell the earlier ones could have moved it to.
+ *
+ * The winners of a simultaneous update claim disjoint sets of these cells, so executing them
+ * one after the other against the world gives the same result as applying each of them to the
+ * snapshot the units decided against.
+ */
+pub fn intent_target_coords(
+    reactions: &[ReactionCall],
+    manifest: &ChemistryManifest,
+    coord: &Coord,
+    size: GridSize2D,
+) -> Vec<Coord> {
+    let mut targets = vec![*coord];
+    for _ in 0..reactions.len() {
+        let mut reached = vec![];
+        for from in targets.iter() {
+            for reaction_call in reactions {
+                reached.extend(reaction_target_coords(
+                    &manifest.reactions[reaction_call.0 as usize],
+                    *reaction_call,
+                    from,
+                    &manifest.neighborhood,
+                    size,
+                ));
+            }
+        }
+
+        for target in reached {
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+    }
+
+    targets
+}
+
+/**
+ * The coords of each square tile, tiles and the coords within them in row order.
+ */
+pub fn tile_coords(size: GridSize2D, tile_size: u32) -> Vec<Vec<Coord>> {
+    let tile_size = tile_size.max(1) as usize;
+    let mut tiles = vec![];
+    for tile_y in (0..size.1).step_by(tile_size) {
+        for tile_x in (0..size.0).step_by(tile_size) {
+            let mut coords = vec![];
+            for y in tile_y..(tile_y + tile_size).min(size.1) {
+                for x in tile_x..(tile_x + tile_size).min(size.0) {
+                    coords.push((x, y));
+                }
+            }
+            tiles.push(coords);
+        }
+    }
+
+    tiles
+}
+
+/**
+ * Phase two of a simultaneous update.  Given the cells each intent reaches into, returns the
+ * intents that get executed, in the order they should be executed.
+ *
+ * Intents are visited in priority order and an intent loses when a higher priority intent
+ * already claimed one of its targets.  With `cancel_both` every intent sharing a target with
+ * another one is dropped instead.
+ */
+pub fn resolve_intent_conflicts(
+    targets: &[Vec<Coord>],
+    priority: &[usize],
+    cancel_both: bool,
+) -> Vec<usize> {
+    if cancel_both {
+        let mut claims: HashMap<Coord, usize> = HashMap::new();
+        for &i in priority.iter() {
+            let mut unique_targets = targets[i].clone();
+            unique_targets.sort();
+            unique_targets.dedup();
+            for target in unique_targets {
+                *claims.entry(target).or_insert(0) += 1;
+            }
+        }
+
+        return priority
+            .iter()
+            .cloned()
+            .filter(|&i| targets[i].iter().all(|target| claims[target] == 1))
+            .collect();
+    }
+
+    let mut claimed: HashMap<Coord, usize> = HashMap::new();
+    let mut winners = vec![];
+    for &i in priority.iter() {
+        let is_contested = targets[i]
+            .iter()
+            .any(|target| claimed.get(target).map_or(false, |&owner| owner != i));
+        if is_contested {
+            continue;
+        }
+
+        for target in targets[i].iter() {
+            claimed.insert(*target, i);
+        }
+        winners.push(i);
+    }
+
+    winners
+}
+
+/**
+ * Resolves the conflicts tile by tile, in parallel.  An intent stays within its tile when all
+ * of its targets are in the same tile as the unit itself; those are resolved with
+ * `resolve_intent_conflicts` using the tile's share of the priority order.
+ *
+ * The intents crossing a boundary are resolved afterwards by tile and then priority order.
+ * Those reaching into a cell already claimed inside a tile are dropped first, so they can't
+ * block the remaining ones, and the outcome doesn't depend on how the tiles were scheduled.
+ * With `cancel_both`, a cell two intents fought over inside a tile stays off limits to
+ * boundary intents as well.
+ */
+pub fn resolve_tiled_intent_conflicts(
+    coords: &[Coord],
+    targets: &[Vec<Coord>],
+    priority: &[usize],
+    cancel_both: bool,
+    tile_size: u32,
+) -> Vec<usize> {
+    let tile_size = tile_size.max(1) as usize;
+    let tile_of = |coord: &Coord| (coord.1 / tile_size, coord.0 / tile_size);
+    let crosses_boundary = |i: usize| {
+        targets[i]
+            .iter()
+            .any(|target| tile_of(target) != tile_of(&coords[i]))
+    };
+
+    let mut priority_by_tile: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    let mut boundary_priority: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for &i in priority.iter() {
+        let tile_priority = if crosses_boundary(i) {
+            &mut boundary_priority
+        } else {
+            &mut priority_by_tile
+        };
+        tile_priority
+            .entry(tile_of(&coords[i]))
+            .or_default()
+            .push(i);
+    }
+
+    let mut tiles = priority_by_tile.into_iter().collect::<Vec<_>>();
+    tiles.sort_by_key(|(tile, _)| *tile);
+
+    let tile_winners = tiles
+        .par_iter()
+        .map(|(_, tile_priority)| resolve_intent_conflicts(targets, tile_priority, cancel_both))
+        .collect::<Vec<_>>();
+
+    let mut winners = tile_winners.into_iter().flatten().collect::<Vec<_>>();
+
+    // when cancelling, a cell contested inside a tile stays contested for everyone
+    let claimants = if cancel_both {
+        tiles
+            .iter()
+            .flat_map(|(_, tile_priority)| tile_priority.clone())
+            .collect::<Vec<_>>()
+    } else {
+        winners.clone()
+    };
+    let mut claimed: HashMap<Coord, usize> = HashMap::new();
+    for &i in claimants.iter() {
+        for target in targets[i].iter() {
+            claimed.insert(*target, i);
+        }
+    }
+
+    let mut boundary_tiles = boundary_priority.into_iter().collect::<Vec<_>>();
+    boundary_tiles.sort_by_key(|(tile, _)| *tile);
+    let boundary_order = boundary_tiles
+        .into_iter()
+        .flat_map(|(_, tile_priority)| tile_priority)
+        .filter(|&i| {
+            targets[i]
+                .iter()
+                .all(|target| !claimed.contains_key(target))
+        })
+        .collect::<Vec<_>>();
+
+    winners.extend(resolve_intent_conflicts(
+        targets,
+        &boundary_order,
+        cancel_both,
+    ));
+
+    winners
+}
+
+#[cfg(test)]
 mod tests {
     #[allow(unused_imports)]
     use super::*;
 
     #[test]
-    fn test() {}
+    fn resolve_intent_conflicts__priority_wins() {
+        let targets = vec![vec![(1, 1)], vec![(1, 1), (2, 2)], vec![(0, 0)]];
+
+        assert_eq!(
+            resolve_intent_conflicts(&targets, &[1, 0, 2], false),
+            vec![1, 2]
+        );
+        assert_eq!(
+            resolve_intent_conflicts(&targets, &[2, 0, 1], false),
+            vec![2, 0]
+        );
+    }
+
+    #[test]
+    fn resolve_intent_conflicts__cancel_both() {
+        let targets = vec![
+            vec![(1, 1)],
+            vec![(2, 2), (1, 1)],
+            vec![(0, 0), (0, 0)],
+            vec![],
+        ];
+
+        assert_eq!(
+            resolve_intent_conflicts(&targets, &[3, 1, 0, 2], true),
+            vec![3, 2]
+        );
+    }
+
+    #[test]
+    fn resolve_tiled_intent_conflicts__boundary_intents_resolved_last() {
+        // tiles of 2x2; intent 1 reaches from the first tile into the second
+        let coords = vec![(2, 0), (1, 0), (3, 1), (0, 0)];
+        let targets = vec![vec![(2, 1)], vec![(2, 1)], vec![(2, 1)], vec![(0, 1)]];
+
+        assert_eq!(
+            resolve_tiled_intent_conflicts(&coords, &targets, &[1, 2, 0, 3], false, 2),
+            vec![3, 2]
+        );
+        assert_eq!(
+            resolve_tiled_intent_conflicts(&coords, &targets, &[1, 2, 0, 3], true, 2),
+            vec![3]
+        );
+
+        // a single tile covering the world behaves like the untiled resolution
+        assert_eq!(
+            resolve_tiled_intent_conflicts(&coords, &targets, &[1, 2, 0, 3], false, 8),
+            resolve_intent_conflicts(&targets, &[1, 2, 0, 3], false)
+        );
+    }
+
+    #[test]
+    fn resolve_tiled_intent_conflicts__dropped_boundary_intents_dont_block() {
+        // intent 1 reaches into the cell intent 0 claimed inside the first tile, so it can't
+        // keep intent 2 from the cell they share
+        let coords = vec![(0, 0), (1, 1), (2, 2)];
+        let targets = vec![vec![(0, 1)], vec![(0, 1), (2, 1)], vec![(2, 1)]];
+
+        for cancel_both in [false, true] {
+            assert_eq!(
+                resolve_tiled_intent_conflicts(&coords, &targets, &[1, 2, 0], cancel_both, 2),
+                vec![0, 2]
+            );
+        }
+    }
+
+    #[test]
+    fn tile_coords__covers_the_world_once() {
+        let tiles = tile_coords((5, 3), 2);
+
+        assert_eq!(tiles.len(), 6);
+        assert_eq!(tiles[0], vec![(0, 0), (1, 0), (0, 1), (1, 1)]);
+        assert_eq!(tiles[2], vec![(4, 0), (4, 1)]);
+        assert_eq!(tiles[5], vec![(4, 2)]);
+
+        let mut coords = tiles.into_iter().flatten().collect::<Vec<_>>();
+        coords.sort_by_key(|coord| (coord.1, coord.0));
+        assert_eq!(coords, CoordIterator::new((5, 3)).collect::<Vec<_>>());
+    }
 }
@@ -4,6 +4,16 @@ use self::properties::*;
 use self::reactions::*;
 use crate::biology::genetic_manifest::predicates::OperatorParam;
 use crate::chemistry::actions::{default_actions, ActionParam};
+use crate::chemistry::config::{
+    neighborhood_from_config, update_mode_from_config, update_seed_from_config,
+};
+pub use crate::chemistry::helpers::action_points::ActionPointEconomy;
+pub use crate::chemistry::helpers::execution_points::ExecutionPointBudget;
+use crate::chemistry::helpers::signals::add_signals_to_manifest;
+pub use crate::chemistry::helpers::signals::SignalDefinition;
+pub use crate::chemistry::helpers::unit_behavior_execution::{
+    IntentConflictPolicy, UnitUpdateMode,
+};
 use crate::simulation::common::*;
 use crate::simulation::unit_entry::UnitEntryAttributeIndex;
 use crate::util::Coord;
@@ -31,6 +41,13 @@ pub struct ChemistryManifest {
     pub position_resources: Vec<PositionResourceDefinition>,
     pub simulation_attributes: Vec<SimulationAttributeDefinition>,
     pub unit_entry_attributes: Vec<UnitEntryAttributeDefinition>,
+
+    pub neighborhood: GridNeighborhood,
+    pub execution_point_budget: ExecutionPointBudget,
+    pub action_point_economy: ActionPointEconomy,
+    pub update_mode: UnitUpdateMode,
+    pub update_seed: u64,
+    pub signals: Vec<SignalDefinition>,
 }
 
 impl ChemistryManifest {
@@ -408,7 +425,12 @@ impl ChemistryManifest {
     }
 
     pub fn normalize_manifest(&mut self, config: &ChemistryConfiguration) {
+        add_signals_to_manifest(self, config);
         self.normalize_properties(config);
+        self.neighborhood = neighborhood_from_config(config);
+        self.execution_point_budget = ExecutionPointBudget::from_config(config, self);
+        self.update_mode = update_mode_from_config(config);
+        self.update_seed = update_seed_from_config(config);
 
         init_chemistry_action_params(self, config);
         let mut reactions: Vec<ReactionDefinition> = self.reactions.clone();
@@ -472,6 +494,7 @@ impl ChemistryManifest {
         // println!("END NORMALIZING REACTIONS\n");
 
         self.reactions = reactions;
+        self.action_point_economy = ActionPointEconomy::from_config(config, self);
     }
 
     pub fn normalize_properties(&mut self, config: &ChemistryConfiguration) {
@@ -5,11 +5,14 @@ use self::properties::*;
 use self::reactions::*;
 use crate::biology::genetic_manifest::predicates::OperatorParam;
 use crate::chemistry::actions::{default_actions, ActionParam};
+use crate::chemistry::helpers::action_points::ActionPointEconomy;
+use crate::chemistry::helpers::execution_points::ExecutionPointBudget;
+use crate::chemistry::helpers::signals::SignalDefinition;
 use crate::simulation::common::*;
 use crate::simulation::unit_entry::UnitEntryAttributeIndex;
 use crate::util::Coord;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ChemistryManifestData {
     pub chemistry_key: String,
     pub reactions: Vec<ReactionDefinition>,
@@ -22,6 +25,17 @@ pub struct ChemistryManifestData {
     pub position_resources: Vec<PositionResourceDefinition>,
     pub simulation_attributes: Vec<SimulationAttributeDefinition>,
     pub unit_entry_attributes: Vec<UnitEntryAttributeDefinition>,
+
+    pub neighborhood: GridNeighborhood,
+    pub execution_point_budget: ExecutionPointBudget,
+    pub action_point_economy: ActionPointEconomy,
+    pub update_mode: UnitUpdateMode,
+
+    #[serde(default)]
+    pub update_seed: u64,
+
+    #[serde(default)]
+    pub signals: Vec<SignalDefinition>,
 }
 
 impl ChemistryManifestData {
@@ -37,6 +51,12 @@ impl ChemistryManifestData {
             position_resources: self.position_resources.clone(),
             simulation_attributes: self.simulation_attributes.clone(),
             unit_entry_attributes: self.unit_entry_attributes.clone(),
+            neighborhood: self.neighborhood,
+            execution_point_budget: self.execution_point_budget.clone(),
+            action_point_economy: self.action_point_economy.clone(),
+            update_mode: self.update_mode.clone(),
+            update_seed: self.update_seed,
+            signals: self.signals.clone(),
         }
     }
 
@@ -53,6 +73,12 @@ impl ChemistryManifestData {
             position_resources: manifest.position_resources,
             simulation_attributes: manifest.simulation_attributes,
             unit_entry_attributes: manifest.unit_entry_attributes,
+            neighborhood: manifest.neighborhood,
+            execution_point_budget: manifest.execution_point_budget,
+            action_point_economy: manifest.action_point_economy,
+            update_mode: manifest.update_mode,
+            update_seed: manifest.update_seed,
+            signals: manifest.signals,
         }
     }
 }
@@ -9,12 +9,16 @@ pub mod properties;
 pub mod reactions;
 pub mod variants;
 
+use self::helpers::action_points::{
+    schedule_unit_reaction_indices, ReactionConflictPolicy, ReactionOrdering,
+};
 use self::helpers::place_units::place_units;
 use self::helpers::place_units::PlaceUnitsMethod;
 use self::properties::*;
 use self::reactions::*;
 use self::variants::foo::FooChemistry;
 use self::variants::LeverChemistry;
+use self::variants::PredatorPreyChemistry;
 use crate::biology::genetic_manifest::predicates::default_operators;
 use crate::biology::genetic_manifest::predicates::OperatorLibrary;
 use crate::biology::sensor_manifest::CustomSensorImplementation;
@@ -26,6 +30,7 @@ use crate::simulation::SimulationAttributes;
 
 use crate::chemistry::config::convert_configurable_to_action_param;
 use crate::chemistry::config::ChemistryConfigValue;
+use crate::chemistry::helpers::signals::signals_from_config;
 use crate::util::{grid_direction_from_num, Coord};
 use std::collections::HashMap;
 use std::rc::Rc;
@@ -49,6 +54,9 @@ impl ChemistryConfigBuilder {
             config: ChemistryConfiguration::new(),
         }
     }
+    pub fn from_config(config: ChemistryConfiguration) -> Self {
+        Self { config }
+    }
     pub fn set_bool(mut self, key: &str, val: bool) -> Self {
         self.config
             .insert(key.to_string(), ChemistryConfigValue::Boolean(val));
@@ -69,6 +77,48 @@ impl ChemistryConfigBuilder {
             .insert(key.to_string(), ChemistryConfigValue::Float64(val));
         self
     }
+    pub fn set_unit_resource_key(mut self, key: &str, val: &str) -> Self {
+        self.config.insert(
+            key.to_string(),
+            ChemistryConfigValue::UnitResourceKey(val.to_string()),
+        );
+        self
+    }
+    pub fn set_neighborhood(mut self, val: GridNeighborhood) -> Self {
+        self.config.insert(
+            "neighborhood".to_string(),
+            ChemistryConfigValue::Neighborhood(val),
+        );
+        self
+    }
+    pub fn set_update_mode(mut self, val: UnitUpdateMode) -> Self {
+        self.config.insert(
+            "update_mode".to_string(),
+            ChemistryConfigValue::UpdateMode(val),
+        );
+        self
+    }
+    pub fn set_update_seed(mut self, val: u64) -> Self {
+        self.config.insert(
+            "update_seed".to_string(),
+            ChemistryConfigValue::Integer(val),
+        );
+        self
+    }
+    pub fn set_world_seed(mut self, val: u64) -> Self {
+        self.config
+            .insert("world_seed".to_string(), ChemistryConfigValue::Integer(val));
+        self
+    }
+    pub fn add_signal(mut self, signal: SignalDefinition) -> Self {
+        let mut signals = signals_from_config(&self.config);
+        signals.push(signal);
+        self.config.insert(
+            "signals".to_string(),
+            ChemistryConfigValue::Signals(signals),
+        );
+        self
+    }
     pub fn build(self) -> ChemistryConfiguration {
         self.config
     }
@@ -89,6 +139,9 @@ pub fn construct_chemistry(
             NanobotsChemistry::construct(config.unwrap_or(NanobotsChemistry::default_config()))
         }
         "foo" => FooChemistry::construct(config.unwrap_or(FooChemistry::default_config())),
+        "predator_prey" => PredatorPreyChemistry::construct(
+            config.unwrap_or(PredatorPreyChemistry::default_config()),
+        ),
         _ => panic!("chemistry key not found: {}", key),
     }
 }
@@ -99,6 +152,7 @@ pub fn construct_chemistry_libraries(key: &str) -> ChemistryLibraries {
         "lever" => LeverChemistry::get_libraries(),
         "nanobots" => NanobotsChemistry::get_libraries(),
         "foo" => FooChemistry::get_libraries(),
+        "predator_prey" => PredatorPreyChemistry::get_libraries(),
         _ => panic!("chemistry key not found: {}", key),
     }
 }
@@ -113,7 +167,10 @@ pub struct ChemistryLibraries {
     pub operator_library: OperatorLibrary,
 }
 
-pub trait Chemistry {
+/**
+ * Shared by reference with the threads deciding unit behaviors, see `UnitUpdateMode::Tiled`.
+ */
+pub trait Chemistry: Send + Sync {
     fn construct(config: ChemistryConfiguration) -> Box<Self>
     where
         Self: Sized;
@@ -175,10 +232,30 @@ pub trait Chemistry {
         ChemistryLibraries {
             action_library: Self::construct_action_library(),
             custom_sensor_library: Self::custom_sensor_library(),
-            operator_library: default_operators().operators,
+            operator_library: Self::construct_operator_library(),
         }
     }
 
+    fn construct_operator_library() -> OperatorLibrary
+    where
+        Self: Sized,
+    {
+        let mut operators = default_operators().operators;
+        operators.append(&mut Self::custom_operator_library());
+        operators
+    }
+
+    /**
+     * Operators that only make sense for this chemistry.  They come after the default operators
+     * in the operator manifest.
+     */
+    fn custom_operator_library() -> OperatorLibrary
+    where
+        Self: Sized,
+    {
+        vec![]
+    }
+
     fn construct_action_library() -> ActionLibrary
     where
         Self: Sized,
@@ -355,17 +432,82 @@ pub trait Chemistry {
         }
     }
 
-    fn deduct_unit_execution_points(&self, sim: &mut SimCell, unit_entry_id: usize, points: u64) {}
+    /**
+     * The number of execution points that the unit at this coord can spend on its behavior
+     * during the current tick.
+     */
+    fn get_unit_execution_point_budget(&self, world: &World, coord: &Coord) -> u64 {
+        let budget = &self.get_manifest().execution_point_budget;
+        match world.get_unit_at(coord) {
+            Some(unit) => budget.points_available(unit),
+            None => 0,
+        }
+    }
+
+    fn deduct_unit_execution_points(&self, sim: &mut SimCell, coord: &Coord, points: u64) {
+        let budget = &self.get_manifest().execution_point_budget;
+        if let Some(resource_idx) = budget.resource {
+            let cost = budget.resource_cost(points);
+            if cost > 0 {
+                let current_amount = sim.world.get_unit_resource_at(coord, resource_idx);
+                sim.world
+                    .set_unit_resource_at(coord, resource_idx, current_amount - cost);
+            }
+        }
+    }
+
+    fn get_reaction_ordering(&self) -> ReactionOrdering {
+        ReactionOrdering::AsEmitted
+    }
 
-    fn execute_unit_reaction(&self, sim: &mut SimCell, coord: &Coord, result: &UnitBehaviorResult) {
-        self.deduct_unit_execution_points(sim, 0, result.consumed_execution_points);
+    fn get_reaction_conflict_policy(&self) -> ReactionConflictPolicy {
+        ReactionConflictPolicy::ExecuteAll
+    }
+
+    /**
+     * Executes the reactions of a unit's behavior result, returning the indices of the ones
+     * that succeeded.
+     */
+    fn execute_unit_reaction(
+        &self,
+        sim: &mut SimCell,
+        coord: &Coord,
+        result: &UnitBehaviorResult,
+    ) -> Vec<usize> {
+        self.deduct_unit_execution_points(sim, coord, result.consumed_execution_points);
+
+        let unit_id = match sim.world.get_unit_at(coord) {
+            Some(unit) => unit.id,
+            None => return vec![],
+        };
+
+        // shuffled orderings differ per unit and tick, but are reproducible from the update_seed
+        let seed = self
+            .get_manifest()
+            .update_seed
+            .wrapping_add(sim.world.tick)
+            .wrapping_mul(0x9e37_79b9_7f4a_7c15)
+            .wrapping_add(unit_id);
+
+        let conflict_policy = self.get_reaction_conflict_policy();
+        let scheduled = schedule_unit_reaction_indices(
+            &result.reactions,
+            &self.get_manifest().action_point_economy,
+            &self.get_reaction_ordering(),
+            &conflict_policy,
+            seed,
+        );
+
+        let neighborhood = self.get_manifest().neighborhood;
+        let mut coord = coord.clone();
+        let mut executed = vec![];
 
         // println!("behavior result: {:?}", result);
-        for i in 0..result.reactions.len().min(1) {
+        for i in scheduled {
             let reaction_call = result.reactions[i];
             let reaction_def = &sim.chemistry.get_manifest().reactions[reaction_call.0 as usize];
 
-            execute_reaction(
+            let succeeded = execute_reaction(
                 sim,
                 &coord,
                 &reaction_def,
@@ -373,7 +515,22 @@ pub trait Chemistry {
                 sim.unit_manifest,
                 reaction_call,
             );
+            if succeeded {
+                executed.push(i);
+            }
+
+            // follow the unit if the reaction moved it, and stop if it died
+            coord = match sim.world.find_unit_near(&coord, unit_id, &neighborhood) {
+                Some(unit_coord) => unit_coord,
+                None => break,
+            };
+
+            if !succeeded && conflict_policy == ReactionConflictPolicy::StopOnFailure {
+                break;
+            }
         }
+
+        executed
     }
 }
 
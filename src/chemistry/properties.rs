@@ -214,6 +214,7 @@ pub struct ResourceTabulation {
     pub offset_per_tick: i32,
     pub last_amount: ResourceAmount,
     pub max_amount: Option<ResourceAmount>,
+    pub min_amount: Option<ResourceAmount>,
 }
 
 impl ResourceTabulation {
@@ -223,12 +224,17 @@ impl ResourceTabulation {
             offset_per_tick: 0,
             last_amount: 0,
             max_amount: None,
+            min_amount: None,
         }
     }
 
     pub fn get_current_amount(&self, current_tick: u64) -> ResourceAmount {
         let tick_diff = (current_tick - self.last_update_tick) as i32;
-        let amount = (tick_diff * self.offset_per_tick + self.last_amount) as ResourceAmount;
+        let mut amount = (tick_diff * self.offset_per_tick + self.last_amount) as ResourceAmount;
+
+        if let Some(min) = self.min_amount {
+            amount = amount.max(min);
+        }
 
         if let Some(max) = self.max_amount {
             if amount > max {
@@ -4,11 +4,9 @@ use crate::biology::unit_behavior::NUM_REACTION_PARAMS;
 use crate::chemistry::actions::{ActionDefinitionIndex, ActionParam, ActionParamType};
 use crate::chemistry::ReactionId;
 use crate::simulation::common::*;
-use crate::util::grid_direction_from_num;
 use std::time::Instant;
 
-#[derive(Debug, Clone)]
-// #[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReactionDefinition {
     pub key: String,
     pub reagents: Vec<ReagentDefinition>,
@@ -41,8 +39,7 @@ pub type ActionIndex = u8;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ReagentDefinition {
-    // pub action_key: String,
-    pub action_key: &'static str,
+    pub action_key: String,
 
     // action_index needs to be set after initialization
     pub action_index: ActionDefinitionIndex,
@@ -51,9 +48,9 @@ pub struct ReagentDefinition {
 }
 
 impl ReagentDefinition {
-    pub fn new(action_key: &'static str, params: Vec<ActionParam>) -> ReagentDefinition {
+    pub fn new(action_key: &str, params: Vec<ActionParam>) -> ReagentDefinition {
         ReagentDefinition {
-            action_key,
+            action_key: action_key.to_string(),
             params,
             action_index: 0,
         }
@@ -67,9 +64,12 @@ pub fn execute_reaction(
     chemistry: &ChemistryInstance,
     unit_manifest: &UnitManifest,
     reaction_call: ReactionCall,
-) {
-    let action_params: Vec<[ActionParam; 3]> =
-        replace_unit_behavior_placeholders(reaction, reaction_call);
+) -> bool {
+    let action_params: Vec<[ActionParam; 3]> = replace_unit_behavior_placeholders(
+        reaction,
+        reaction_call,
+        &chemistry.get_manifest().neighborhood,
+    );
 
     for (i, reagent) in reaction.reagents.iter().enumerate() {
         //println!("reagent: {:?} with INDEX {}", reagent, reagent.action_index);
@@ -87,9 +87,34 @@ pub fn execute_reaction(
         );
 
         if !result {
-            break;
+            return false;
         }
     }
+
+    true
+}
+
+/**
+ * The cells a reaction reaches into from the given coord, ie. every direction param once the
+ * unit behavior arguments are filled in.  Used to detect units competing for the same cell.
+ */
+pub fn reaction_target_coords(
+    reaction: &ReactionDefinition,
+    reaction_call: ReactionCall,
+    coord: &Coord,
+    neighborhood: &GridNeighborhood,
+    size: GridSize2D,
+) -> Vec<Coord> {
+    replace_unit_behavior_placeholders(reaction, reaction_call, neighborhood)
+        .iter()
+        .flatten()
+        .filter_map(|param| match param {
+            ActionParam::Direction(direction) => {
+                neighborhood.coord_by_direction_offset(coord, direction, size)
+            }
+            _ => None,
+        })
+        .collect()
 }
 
 /**
@@ -99,6 +124,7 @@ pub fn execute_reaction(
 fn replace_unit_behavior_placeholders(
     reaction: &ReactionDefinition,
     reaction_call: ReactionCall,
+    neighborhood: &GridNeighborhood,
 ) -> Vec<[ActionParam; 3]> {
     let mut params_by_reagent: Vec<[ActionParam; 3]> = _empty_params_list(reaction.reagents.len());
     let mut param_idx: usize = 0;
@@ -116,7 +142,7 @@ fn replace_unit_behavior_placeholders(
                 ActionParam::UnitBehaviorArgument(param_type) => {
                     //println!("placeholder");
                     let param = get_param_by_index(reaction_call, param_idx);
-                    params[i] = convert_raw_arg_val_to_param_val(param, param_type);
+                    params[i] = convert_raw_arg_val_to_param_val(param, param_type, neighborhood);
                     //println!("params[i]: {:?}", &params[i]);
 
                     param_idx += 1;
@@ -167,6 +193,7 @@ fn _empty_params_list(size: usize) -> Vec<[ActionParam; 3]> {
 fn convert_raw_arg_val_to_param_val(
     raw_val: ReactionCallParam,
     param_type: &ActionParamType,
+    neighborhood: &GridNeighborhood,
 ) -> ActionParam {
     //println!("CONVERTING");
     //println!("raw_val: {:?}", raw_val);
@@ -202,7 +229,7 @@ fn convert_raw_arg_val_to_param_val(
         }
 
         ActionParamType::Direction => {
-            ActionParam::Direction(grid_direction_from_num(raw_val as u8 % 4))
+            ActionParam::Direction(neighborhood.direction_from_num(raw_val))
         }
         ActionParamType::ConstantNum => ActionParam::Constant(raw_val as ActionParamNumber),
         ActionParamType::Boolean => ActionParam::Boolean(raw_val as ActionParamNumber > 0),
@@ -242,6 +269,7 @@ pub mod tests {
                 ],
             ],
             (0, 10, 0, 0),
+            &GridNeighborhood::VonNeumann,
         );
 
         assert_eq!(
@@ -273,6 +301,7 @@ pub mod tests {
                 ],
             ],
             (42 /*not used*/, 1, 2, 3),
+            &GridNeighborhood::VonNeumann,
         );
 
         assert_eq!(
@@ -294,7 +323,88 @@ pub mod tests {
     #[ignore] // not implemented yet
     #[test]
     fn replace_placeholders__attr_val_replacement() {}
-    #[ignore] // not implemented yet
     #[test]
-    fn replace_placeholders__direction_replacement() {}
+    fn replace_placeholders__direction_replacement() {
+        let reaction = reaction![
+            "",
+            reagent![
+                "",
+                unit_behavior_arg!(Direction),
+                unit_behavior_arg!(Direction),
+                unit_behavior_arg!(Direction)
+            ],
+        ];
+
+        let action_params = replace_unit_behavior_placeholders(
+            &reaction,
+            (0, 1, 5, 9),
+            &GridNeighborhood::VonNeumann,
+        );
+        assert_eq!(
+            action_params,
+            vec![[
+                ActionParam::Direction(GridDirection::Right),
+                ActionParam::Direction(GridDirection::Right),
+                ActionParam::Direction(GridDirection::Right),
+            ]]
+        );
+
+        let action_params =
+            replace_unit_behavior_placeholders(&reaction, (0, 4, 7, 9), &GridNeighborhood::Moore);
+        assert_eq!(
+            action_params,
+            vec![[
+                ActionParam::Direction(GridDirection::UpRight),
+                ActionParam::Direction(GridDirection::UpLeft),
+                ActionParam::Direction(GridDirection::Right),
+            ]]
+        );
+
+        let action_params =
+            replace_unit_behavior_placeholders(&reaction, (0, 0, 6, 10), &GridNeighborhood::Hex);
+        assert_eq!(
+            action_params,
+            vec![[
+                ActionParam::Direction(GridDirection::UpRight),
+                ActionParam::Direction(GridDirection::UpRight),
+                ActionParam::Direction(GridDirection::Left),
+            ]]
+        );
+    }
+
+    #[test]
+    fn reaction_target_coords__direction_params() {
+        let reaction = reaction![
+            "",
+            reagent![
+                "",
+                unit_behavior_arg!(Direction),
+                constant_arg!(UnitResourceIndex, 0),
+                unit_behavior_arg!(Direction)
+            ],
+        ];
+
+        assert_eq!(
+            reaction_target_coords(
+                &reaction,
+                (0, 0, 3, 0),
+                &(1, 1),
+                &GridNeighborhood::VonNeumann,
+                (3, 3)
+            ),
+            vec![(1, 2), (0, 1)]
+        );
+
+        // off-grid targets aren't reachable
+        assert_eq!(
+            reaction_target_coords(
+                &reaction,
+                (0, 2, 2, 0),
+                &(0, 0),
+                &GridNeighborhood::VonNeumann,
+                (3, 3)
+            ),
+            vec![]
+        );
+    }
 }
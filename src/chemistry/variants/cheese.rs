@@ -1,5 +1,7 @@
 use crate::biology::genetic_manifest::predicates::default_operators;
+use crate::biology::sensor_manifest::{SensorAggregate, UnitSpeciesFilter};
 use crate::chemistry::actions::*;
+use crate::chemistry::config::{world_rng_from_config, WORLD_RNG_INIT, WORLD_RNG_PLACEMENT};
 use crate::chemistry::properties::*;
 use crate::chemistry::reactions::*;
 use crate::chemistry::*;
@@ -15,7 +17,7 @@ use crate::simulation::world::World;
 use crate::simulation::Simulation;
 use crate::util::Coord;
 
-use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::simulation::position::{
     PositionAttributeIndex, PositionAttributeValue, PositionResourceAmount, PositionResourceIndex,
@@ -173,6 +175,12 @@ impl Chemistry for CheeseChemistry {
             position_attributes: defs::PositionAttributesLookup::make_defs(),
             position_resources: defs::PositionResourcesLookup::make_defs(),
             reactions: defs::get_reactions(),
+            neighborhood: GridNeighborhood::default(),
+            execution_point_budget: ExecutionPointBudget::default(),
+            action_point_economy: ActionPointEconomy::default(),
+            update_mode: UnitUpdateMode::default(),
+            update_seed: 0,
+            signals: vec![],
         };
 
         let config = Self::fill_with_defaults(config.clone());
@@ -217,6 +225,8 @@ impl Chemistry for CheeseChemistry {
     }
 
     fn default_local_property_sensor_manifest(&self) -> LocalPropertySensorManifest {
+        let all_properties = self.get_manifest().all_properties.as_slice();
+
         LocalPropertySensorManifest::from_whitelist(
             vec![
                 ("unit_res::cheese".to_string(), 0),
@@ -224,8 +234,11 @@ impl Chemistry for CheeseChemistry {
                 ("pos_attr::is_cheese_dispenser".to_string(), 1),
             ]
             .as_slice(),
-            self.get_manifest().all_properties.as_slice(),
+            all_properties,
         )
+        .with_aggregate("pos_res::milk", SensorAggregate::Sum, 2, all_properties)
+        .with_nearest_direction("pos_res::milk", 3, all_properties)
+        .with_unit_count(UnitSpeciesFilter::Any, 1)
     }
 
     fn allocate_unit_resources(&self, coord: &Coord, sim: &mut SimCell) {
@@ -325,7 +338,7 @@ impl Chemistry for CheeseChemistry {
         let unit_drop_area = self.unit_drop_area(&world);
 
         use rand::Rng;
-        let mut rng = rand::thread_rng();
+        let mut rng = world_rng_from_config(&self.configuration, WORLD_RNG_INIT);
         for coord in CoordIterator::new(world.size.clone()) {
             if (coord.0 * world.size.1 + coord.1) % 2 == 0 {
                 world.set_pos_attribute_at(
@@ -406,7 +419,7 @@ fn custom_action_definitions() -> Vec<ActionDefinition> {
         &"make_cheese",
         vec![],
         // execute action
-        Rc::new(
+        Arc::new(
             |sim_cell: &mut SimCell, context: &ActionExecutionContext| -> bool {
                 let unit_resources = defs::UnitResourcesLookup::new();
                 let pos_resources = defs::PositionResourcesLookup::new();
@@ -466,7 +479,7 @@ pub fn place_units_static_region(
 ) {
     use rand::Rng;
     let manifest = unit_manifest.clone();
-    let mut rng = rand::thread_rng();
+    let mut rng = world_rng_from_config(&chemistry.configuration, WORLD_RNG_PLACEMENT);
     let mut attempts = 0;
 
     // println!("[PlaceUnits] placing units in region: {:?}", rect);
@@ -1,3 +1,6 @@
+use crate::biology::genetic_manifest::predicates::{
+    OperatorImplementation, OperatorLibrary, OperatorParam,
+};
 use crate::chemistry::actions::*;
 use crate::chemistry::properties::*;
 use crate::chemistry::reactions::*;
@@ -115,6 +118,12 @@ impl Chemistry for FooChemistry {
             position_attributes: defs::PositionAttributesLookup::make_defs(),
             position_resources: defs::PositionResourcesLookup::make_defs(),
             reactions: defs::get_reactions(),
+            neighborhood: GridNeighborhood::default(),
+            execution_point_budget: ExecutionPointBudget::default(),
+            action_point_economy: ActionPointEconomy::default(),
+            update_mode: UnitUpdateMode::default(),
+            update_seed: 0,
+            signals: vec![],
         };
 
         let config = Self::fill_with_defaults(config.clone());
@@ -148,6 +157,19 @@ impl Chemistry for FooChemistry {
         "foo".to_string()
     }
 
+    fn custom_operator_library() -> OperatorLibrary {
+        vec![OperatorImplementation {
+            index: 0,
+            name: "is_magic_foo_amount",
+            num_params: 1,
+            is_constant: false,
+            evaluate: std::sync::Arc::new(|params: &[OperatorParam]| -> bool { params[0] == 10 }),
+            render: std::sync::Arc::new(|param_strs: &[String]| -> String {
+                format!("is_magic_foo_amount({})", param_strs[0])
+            }),
+        }]
+    }
+
     fn get_manifest(&self) -> &ChemistryManifest {
         &self.manifest
     }
@@ -215,7 +237,7 @@ impl Chemistry for FooChemistry {
             &"set_foo_unit_resource_to_magic_amount",
             vec![],
             // execute action
-            Rc::new(
+            Arc::new(
                 |sim_cell: &mut SimCell, context: &ActionExecutionContext| -> bool {
                     let unit_resources = defs::UnitResourcesLookup::new();
                     let pos_resources = defs::PositionResourcesLookup::new();
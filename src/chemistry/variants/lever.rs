@@ -15,7 +15,7 @@ use crate::simulation::world::World;
 use crate::simulation::Simulation;
 use crate::util::Coord;
 
-use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::simulation::position::{
     PositionAttributeIndex, PositionAttributeValue, PositionResourceAmount, PositionResourceIndex,
@@ -98,6 +98,12 @@ impl Chemistry for LeverChemistry {
             position_attributes: defs::PositionAttributesLookup::make_defs(),
             position_resources: defs::PositionResourcesLookup::make_defs(),
             reactions: defs::get_reactions(),
+            neighborhood: GridNeighborhood::default(),
+            execution_point_budget: ExecutionPointBudget::default(),
+            action_point_economy: ActionPointEconomy::default(),
+            update_mode: UnitUpdateMode::default(),
+            update_seed: 0,
+            signals: vec![],
         };
         manifest.normalize_manifest(config);
         manifest
@@ -158,7 +164,7 @@ impl Chemistry for LeverChemistry {
         vec![ActionDefinition::new(
             &"pull_lever",
             vec![],
-            Rc::new(
+            Arc::new(
                 |sim_cell: &mut SimCell, context: &ActionExecutionContext| -> bool {
                     let unit = sim_cell.world.get_unit_at(context.coord).unwrap();
                     let entry_id = unit.entry_id;
@@ -180,8 +186,6 @@ impl Chemistry for LeverChemistry {
 }
 
 mod tests {
-    use std::cell::RefCell;
-
     #[allow(unused_imports)]
     use super::*;
     use crate::biology::unit_behavior::lever::SimpleLever;
@@ -196,7 +200,7 @@ mod tests {
             .chemistry(ChemistryBuilder::with_key("lever").build())
             .unit_entries(vec![UnitEntryBuilder::default()
                 .species_name("main".to_string())
-                .behavior(Rc::new(RefCell::new(SimpleLever::construct())))])
+                .behavior(Arc::new(SimpleLever::construct()))])
             .size((1, 1))
             .iterations(10)
             .to_simulation();
@@ -2,9 +2,11 @@ pub mod cheese;
 pub mod foo;
 pub mod lever;
 pub mod nanobots;
+pub mod predator_prey;
 pub mod simple;
 
 pub use self::cheese::CheeseChemistry;
 pub use self::foo::FooChemistry;
 pub use self::lever::LeverChemistry;
 pub use self::nanobots::NanobotsChemistry;
+pub use self::predator_prey::PredatorPreyChemistry;
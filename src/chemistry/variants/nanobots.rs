@@ -47,6 +47,12 @@ impl Chemistry for NanobotsChemistry {
             )],
             unit_entry_attributes: vec![],
             reactions: vec![],
+            neighborhood: GridNeighborhood::default(),
+            execution_point_budget: ExecutionPointBudget::default(),
+            action_point_economy: ActionPointEconomy::default(),
+            update_mode: UnitUpdateMode::default(),
+            update_seed: 0,
+            signals: vec![],
         };
 
         let config = Self::fill_with_defaults(config.clone());
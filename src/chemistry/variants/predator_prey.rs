@@ -0,0 +1,595 @@
+use crate::biology::sensor_manifest::{SensorAggregate, UnitSpeciesFilter};
+use crate::chemistry::actions::*;
+use crate::chemistry::config::{world_rng_from_config, WORLD_RNG_INIT};
+use crate::chemistry::properties::*;
+use crate::chemistry::reactions::*;
+use crate::chemistry::*;
+
+use crate::simulation::common::helpers::resource_allocation::allocate_stored_resources;
+use crate::simulation::common::helpers::resource_allocation::StoredResourceAllocationMethod;
+use crate::simulation::common::helpers::unit_behavior_execution::behavior_execution;
+use crate::simulation::common::*;
+use crate::simulation::unit::*;
+use crate::simulation::world::World;
+use crate::util::Coord;
+
+use std::sync::Arc;
+
+use crate::simulation::position::{
+    PositionAttributeIndex, PositionAttributeValue, PositionResourceAmount, PositionResourceIndex,
+};
+
+use crate::simulation::unit::{
+    UnitAttributeIndex, UnitAttributeValue, UnitResourceAmount, UnitResourceIndex,
+};
+use crate::util::*;
+use std::collections::HashMap;
+
+/**
+ * Two kinds of units share the world: prey graze the grass growing on positions and predators
+ * hunt neighboring prey, never other predators.  Which kind a unit is comes from its is_predator attribute, which is usually set
+ * through the default attributes of its unit entry so that a UnitManifest can hold both.
+ *
+ * Every unit burns metabolism_cost energy per tick and dies when it runs out.  Prey are scored
+ * by unit_ticks_alive and predators by prey_eaten.
+ */
+pub struct PredatorPreyChemistry {
+    manifest: ChemistryManifest,
+    configuration: ChemistryConfiguration,
+}
+
+pub mod defs {
+    const CHEMISTRY_KEY: &str = "predator_prey";
+    use super::*;
+
+    def_unit_entry_attributes! {[
+        [prey_eaten, Number],
+        [unit_ticks_alive, Number]
+    ]}
+
+    def_simulation_attributes! {[
+        [total_prey_eaten, Number]
+    ]}
+
+    def_unit_attributes! {[
+        [is_predator, Boolean]
+    ]}
+
+    def_position_attributes! {[ ]}
+
+    def_position_resources! {[
+        [grass, false]
+    ]}
+
+    def_unit_resources! {[
+       [energy, false]
+    ]}
+
+    def_reactions! {
+        reaction!("graze",
+            reagent!("require_predator",
+                constant_arg!(Boolean, false),
+            ),
+            reagent!("graze",
+                chemistry_arg!(UnitResourceAmount, max_graze_amount),
+            ),
+        ),
+
+        reaction!("hunt",
+            reagent!("require_predator",
+                constant_arg!(Boolean, true),
+            ),
+            reagent!("offset_unit_resource",
+                constant_arg!(UnitResourceKey, "energy"),
+                chemistry_arg!(UnitResourceAmount, hunt_cost),
+                constant_arg!(Boolean, false),
+            ),
+            reagent!("kill_prey",
+                unit_behavior_arg!(Direction),
+                constant_arg!(UnitResourceKey, "energy"),
+                chemistry_arg!(ConstantNum, hunt_efficiency_pct),
+            ),
+            reagent!("record_kill"),
+        ),
+
+        reaction!("share_energy",
+            reagent!("transfer_unit_resource",
+                constant_arg!(UnitResourceKey, "energy"),
+                unit_behavior_arg!(UnitResourceAmount),
+                unit_behavior_arg!(Direction),
+            ),
+        ),
+
+        reaction!("move_unit",
+            reagent!("offset_unit_resource",
+                constant_arg!(UnitResourceKey, "energy"),
+                chemistry_arg!(UnitResourceAmount, move_cost),
+                constant_arg!(Boolean, false),
+            ),
+            reagent!("move_unit",
+                unit_behavior_arg!(Direction)
+            ),
+        ),
+
+        reaction!("new_unit",
+            reagent!("offset_unit_resource",
+                constant_arg!(UnitResourceKey, "energy"),
+                chemistry_arg!(UnitResourceAmount, new_unit_cost),
+                constant_arg!(Boolean, false),
+            ),
+            reagent!("new_unit",
+                unit_behavior_arg!(Direction),
+            ),
+        ),
+
+        reaction!("mate",
+            reagent!("offset_unit_resource",
+                constant_arg!(UnitResourceKey, "energy"),
+                chemistry_arg!(UnitResourceAmount, new_unit_cost),
+                constant_arg!(Boolean, false),
+            ),
+            reagent!("mate",
+                unit_behavior_arg!(Direction),
+                unit_behavior_arg!(Direction),
+            ),
+        ),
+    }
+}
+
+impl Chemistry for PredatorPreyChemistry {
+    fn construct(config: ChemistryConfiguration) -> Box<PredatorPreyChemistry> {
+        let config = Self::fill_with_defaults(config);
+
+        let mut chemistry = PredatorPreyChemistry {
+            manifest: PredatorPreyChemistry::construct_manifest(&config),
+            configuration: config,
+        };
+
+        wrap_chemistry!(chemistry)
+    }
+
+    fn get_key() -> String {
+        "predator_prey".to_string()
+    }
+
+    fn construct_manifest(config: &ChemistryConfiguration) -> ChemistryManifest {
+        let mut manifest = ChemistryManifest {
+            chemistry_key: Self::get_key(),
+            all_properties: vec![],
+            simulation_attributes: defs::SimulationAttributesLookup::make_defs(),
+            unit_entry_attributes: defs::UnitEntryAttributesLookup::make_defs(),
+            action_manifest: ActionManifest::new(Self::construct_action_library()),
+            unit_resources: defs::UnitResourcesLookup::make_defs(),
+            unit_attributes: defs::UnitAttributesLookup::make_defs(),
+            position_attributes: defs::PositionAttributesLookup::make_defs(),
+            position_resources: defs::PositionResourcesLookup::make_defs(),
+            reactions: defs::get_reactions(),
+            neighborhood: GridNeighborhood::default(),
+            execution_point_budget: ExecutionPointBudget::default(),
+            action_point_economy: ActionPointEconomy::default(),
+            update_mode: UnitUpdateMode::default(),
+            update_seed: 0,
+            signals: vec![],
+        };
+
+        let config = Self::fill_with_defaults(config.clone());
+        manifest.normalize_manifest(&config);
+
+        manifest
+    }
+
+    fn custom_action_definitions() -> Vec<ActionDefinition>
+    where
+        Self: Sized,
+    {
+        custom_action_definitions()
+    }
+
+    fn default_config() -> ChemistryConfiguration {
+        ChemistryConfigBuilder::new()
+            .set_resource_amount("move_cost", -1)
+            .set_resource_amount("new_unit_cost", -60)
+            .set_resource_amount("hunt_cost", -5)
+            .set_integer("hunt_efficiency_pct", 80)
+            .set_resource_amount("max_graze_amount", 10)
+            .set_resource_amount("metabolism_cost", 1)
+            .set_resource_amount("starting_energy", 50)
+            .set_float_amount("grass_odds", 0.5)
+            .set_resource_amount("grass_regrowth", 1)
+            .set_resource_amount("max_grass_in_position", 30)
+            .build()
+    }
+
+    fn get_configuration(&self) -> ChemistryConfiguration {
+        self.configuration.clone()
+    }
+
+    fn default_local_property_sensor_manifest(&self) -> LocalPropertySensorManifest {
+        let all_properties = self.get_manifest().all_properties.as_slice();
+
+        LocalPropertySensorManifest::from_whitelist(
+            vec![
+                ("unit_res::energy".to_string(), 0),
+                ("unit_attr::is_predator".to_string(), 1),
+                ("pos_res::grass".to_string(), 1),
+            ]
+            .as_slice(),
+            all_properties,
+        )
+        .with_aggregate(
+            "unit_attr::is_predator",
+            SensorAggregate::Count,
+            2,
+            all_properties,
+        )
+        .with_nearest_direction("pos_res::grass", 3, all_properties)
+        .with_nearest_direction("unit_res::energy", 3, all_properties)
+        .with_unit_count(UnitSpeciesFilter::Any, 1)
+    }
+
+    fn get_manifest(&self) -> &ChemistryManifest {
+        &self.manifest
+    }
+    fn get_manifest_mut(&mut self) -> &mut ChemistryManifest {
+        &mut self.manifest
+    }
+
+    fn on_simulation_tick(&self, sim: &mut SimCell) -> bool {
+        allocate_stored_resources(
+            sim,
+            sim.unit_manifest,
+            &StoredResourceAllocationMethod::Every,
+        );
+
+        behavior_execution(sim);
+
+        let unit_resources = defs::UnitResourcesLookup::new();
+        let unit_entry_attributes = defs::UnitEntryAttributesLookup::new();
+        let metabolism_cost = self
+            .get_configuration()
+            .get("metabolism_cost")
+            .unwrap()
+            .unwrap_resource_amount();
+
+        let mut total_units = 0;
+        for coord in CoordIterator::new(sim.world.size) {
+            let entry_id = match sim.world.get_unit_at(&coord) {
+                Some(unit) => unit.entry_id,
+                None => continue,
+            };
+
+            sim.world
+                .add_unit_resource_at(&coord, unit_resources.energy, -metabolism_cost);
+            if sim
+                .world
+                .get_unit_resource_at(&coord, unit_resources.energy)
+                <= 0
+            {
+                sim.world.destroy_unit(&coord);
+                continue;
+            }
+
+            total_units += 1;
+            let ticks_alive = sim.unit_entry_attributes[entry_id]
+                [unit_entry_attributes.unit_ticks_alive]
+                .unwrap_integer();
+            sim.unit_entry_attributes[entry_id][unit_entry_attributes.unit_ticks_alive] =
+                UnitEntryAttributeValue::Integer(ticks_alive + 1);
+        }
+
+        total_units != 0
+    }
+
+    fn on_simulation_finish(&self, sim: &mut SimCell) {}
+
+    fn init_world_custom(&self, world: &mut World) {
+        use rand::Rng;
+        let config = self.get_configuration();
+        let mut rng = world_rng_from_config(&config, WORLD_RNG_INIT);
+        let position_resources = defs::PositionResourcesLookup::new();
+
+        let odds = config.get("grass_odds").unwrap().unwrap_float();
+        let regrowth = config
+            .get("grass_regrowth")
+            .unwrap()
+            .unwrap_resource_amount();
+        let max_grass = config
+            .get("max_grass_in_position")
+            .unwrap()
+            .unwrap_resource_amount();
+
+        for coord in CoordIterator::new(world.size.clone()) {
+            if rng.gen_ratio((odds * 100.0) as u32, 100) {
+                world.set_pos_resource_at(&coord, position_resources.grass, max_grass / 2);
+                world.set_pos_resource_tab_offset(
+                    &coord,
+                    position_resources.grass,
+                    regrowth,
+                    Some(max_grass),
+                );
+            }
+        }
+    }
+
+    fn get_default_unit_seed_attributes(
+        &self,
+        world: &mut World,
+        coord: &Coord,
+        entry: &UnitEntryData,
+    ) -> UnitAttributes {
+        self.get_manifest().unit_attributes_of(vec![(
+            "is_predator".to_owned(),
+            UnitAttributeValue::Bool(false),
+        )])
+    }
+
+    fn get_unit_seed_stored_resource_amounts(
+        &self,
+        world: &mut World,
+        coord: &Coord,
+        entry: &UnitEntryData,
+    ) -> UnitResources {
+        if entry.default_resources.is_some() {
+            return entry.default_resources.as_ref().unwrap().clone();
+        }
+
+        let unit_resources = defs::UnitResourcesLookup::new();
+        let mut resources = self.get_manifest().empty_unit_resources();
+        resources[unit_resources.energy] = self
+            .get_configuration()
+            .get("starting_energy")
+            .unwrap()
+            .unwrap_resource_amount();
+        resources
+    }
+}
+
+fn custom_action_definitions() -> Vec<ActionDefinition> {
+    vec![
+        ActionDefinition::new(
+            &"require_predator",
+            vec![ActionParamDefinition {
+                name: "is_predator".to_string(),
+                param_type: ActionParamType::Boolean,
+            }],
+            // fails for the other kind of unit, so that the rest of the reaction doesn't run
+            Arc::new(
+                |sim_cell: &mut SimCell, context: &ActionExecutionContext| -> bool {
+                    let unit_attributes = defs::UnitAttributesLookup::new();
+                    let unit = sim_cell.world.get_unit_at(context.coord).unwrap();
+
+                    unit.attributes[unit_attributes.is_predator].unwrap_bool()
+                        == context.params[0].to_bool()
+                },
+            ),
+        ),
+        ActionDefinition::new(
+            &"graze",
+            vec![ActionParamDefinition {
+                name: "max_amount".to_string(),
+                param_type: ActionParamType::UnitResourceAmount,
+            }],
+            Arc::new(
+                |sim_cell: &mut SimCell, context: &ActionExecutionContext| -> bool {
+                    let unit_resources = defs::UnitResourcesLookup::new();
+                    let pos_resources = defs::PositionResourcesLookup::new();
+
+                    let max_amount = context.params[0].to_unit_resource_amount();
+                    let grass = sim_cell
+                        .world
+                        .get_pos_resource_at(context.coord, pos_resources.grass);
+                    let amount = grass.min(max_amount).max(0);
+
+                    sim_cell.world.set_pos_resource_at(
+                        context.coord,
+                        pos_resources.grass,
+                        grass - amount,
+                    );
+                    sim_cell.world.add_unit_resource_at(
+                        context.coord,
+                        unit_resources.energy,
+                        amount,
+                    );
+                    true
+                },
+            ),
+        ),
+        ActionDefinition::new(
+            &"kill_prey",
+            vec![
+                ActionParamDefinition {
+                    name: "direction".to_string(),
+                    param_type: ActionParamType::Direction,
+                },
+                ActionParamDefinition {
+                    name: "resource".to_string(),
+                    param_type: ActionParamType::UnitResourceIndex,
+                },
+                ActionParamDefinition {
+                    name: "pct_absorbed".to_string(),
+                    param_type: ActionParamType::ConstantNum,
+                },
+            ],
+            // like kill_unit, but fails when the neighbor is a predator so that record_kill
+            // only counts prey
+            Arc::new(
+                |sim_cell: &mut SimCell, context: &ActionExecutionContext| -> bool {
+                    let unit_attributes = defs::UnitAttributesLookup::new();
+                    let resource_idx = context.params[1].to_unit_resource_index();
+                    let pct_absorbed = context.params[2].to_constant().clamp(0, 100);
+
+                    let target = match neighbor_unit_coord(sim_cell, context, &context.params[0]) {
+                        Some(target) => target,
+                        None => return false,
+                    };
+                    let target_unit = sim_cell.world.get_unit_at(&target).unwrap();
+                    if target_unit.attributes[unit_attributes.is_predator].unwrap_bool() {
+                        return false;
+                    }
+
+                    kill_unit_at(
+                        sim_cell.world,
+                        context.coord,
+                        &target,
+                        resource_idx,
+                        pct_absorbed,
+                    );
+                    true
+                },
+            ),
+        ),
+        ActionDefinition::new(
+            &"record_kill",
+            vec![],
+            Arc::new(
+                |sim_cell: &mut SimCell, context: &ActionExecutionContext| -> bool {
+                    let sim_attributes = defs::SimulationAttributesLookup::new();
+                    let unit_entry_attributes = defs::UnitEntryAttributesLookup::new();
+                    let entry_id = sim_cell.world.get_unit_at(context.coord).unwrap().entry_id;
+
+                    let next_val = sim_cell.unit_entry_attributes[entry_id]
+                        [unit_entry_attributes.prey_eaten]
+                        .unwrap_integer()
+                        + 1;
+                    sim_cell.unit_entry_attributes[entry_id][unit_entry_attributes.prey_eaten] =
+                        UnitEntryAttributeValue::Integer(next_val);
+
+                    let next_val =
+                        sim_cell.attributes[sim_attributes.total_prey_eaten].unwrap_integer() + 1;
+                    sim_cell.attributes[sim_attributes.total_prey_eaten] =
+                        SimulationAttributeValue::Integer(next_val);
+                    true
+                },
+            ),
+        ),
+    ]
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    use crate::biology::unit_behavior::NullBehavior;
+    use crate::chemistry::reactions::execute_reaction;
+    use crate::simulation::common::builder::ChemistryBuilder;
+    use crate::simulation::common::helpers::place_units::PlaceUnitsMethod;
+
+    fn react(sim: &mut Simulation, key: &str, coord: &Coord) -> bool {
+        let reaction = sim
+            .chemistry
+            .get_manifest()
+            .identify_reaction(&key.to_string())
+            .unwrap();
+        let mut sim_cell = sim.editable();
+        let (chemistry, unit_manifest) = (sim_cell.chemistry, sim_cell.unit_manifest);
+
+        // direction 0 is up
+        execute_reaction(
+            &mut sim_cell,
+            coord,
+            &reaction,
+            chemistry,
+            unit_manifest,
+            (reaction.id as ReactionId, 0, 0, 0),
+        )
+    }
+
+    #[test]
+    fn hunting_and_grazing() {
+        let mut sim = SimulationBuilder::default()
+            .chemistry(ChemistryBuilder::with_key("predator_prey").build())
+            .size((5, 5))
+            .place_units_method(PlaceUnitsMethod::ManualSingleEntry {
+                attributes: None,
+                coords: vec![(2, 2), (2, 3)],
+            })
+            .unit_manifest(UnitManifest {
+                units: vec![UnitEntry::new("main", NullBehavior::construct())],
+            })
+            .to_simulation();
+
+        let unit_attributes = defs::UnitAttributesLookup::new();
+        let unit_resources = defs::UnitResourcesLookup::new();
+        let pos_resources = defs::PositionResourcesLookup::new();
+        let entry_attributes = defs::UnitEntryAttributesLookup::new();
+        assert_eq!(
+            sim.world
+                .get_unit_resource_at(&(2, 2), unit_resources.energy),
+            50
+        );
+
+        // prey can't hunt
+        assert!(!react(&mut sim, "hunt", &(2, 2)));
+
+        // predators can't hunt each other
+        for coord in [(2, 2), (2, 3)] {
+            sim.world.set_unit_attribute_at(
+                &coord,
+                unit_attributes.is_predator,
+                UnitAttributeValue::Bool(true),
+            );
+        }
+        assert!(!react(&mut sim, "hunt", &(2, 2)));
+        assert!(sim.world.has_unit_at(&(2, 3)));
+        assert_eq!(
+            sim.unit_entry_attributes[0][entry_attributes.prey_eaten].unwrap_integer(),
+            0
+        );
+
+        sim.world.set_unit_attribute_at(
+            &(2, 3),
+            unit_attributes.is_predator,
+            UnitAttributeValue::Bool(false),
+        );
+        sim.world
+            .set_unit_resource_at(&(2, 2), unit_resources.energy, 50);
+        assert!(react(&mut sim, "hunt", &(2, 2)));
+        assert!(!sim.world.has_unit_at(&(2, 3)));
+        // the hunt cost plus 80% of the prey's energy
+        assert_eq!(
+            sim.world
+                .get_unit_resource_at(&(2, 2), unit_resources.energy),
+            85
+        );
+        assert_eq!(
+            sim.unit_entry_attributes[0][entry_attributes.prey_eaten].unwrap_integer(),
+            1
+        );
+
+        // predators can't graze
+        sim.world
+            .set_pos_resource_at(&(2, 2), pos_resources.grass, 4);
+        assert!(!react(&mut sim, "graze", &(2, 2)));
+        assert_eq!(
+            sim.world.get_pos_resource_at(&(2, 2), pos_resources.grass),
+            4
+        );
+    }
+
+    #[test]
+    fn metabolism() {
+        let chemistry_builder = ChemistryBuilder::with_key("predator_prey");
+        let mut sim = SimulationBuilder::default()
+            .chemistry(chemistry_builder.build())
+            .size((5, 5))
+            .place_units_method(PlaceUnitsMethod::ManualSingleEntry {
+                attributes: None,
+                coords: vec![(1, 1)],
+            })
+            .unit_entries(vec![UnitEntryBuilder::default()
+                .species_name("main".to_string())
+                .behavior(NullBehavior::construct())
+                .default_resources(vec![("energy".to_string(), 3)])])
+            .to_simulation();
+
+        let entry_attributes = defs::UnitEntryAttributesLookup::new();
+        sim.tick();
+        sim.tick();
+        assert!(sim.world.has_unit_at(&(1, 1)));
+        sim.tick();
+        assert!(!sim.world.has_unit_at(&(1, 1)));
+        assert_eq!(
+            sim.unit_entry_attributes[0][entry_attributes.unit_ticks_alive].unwrap_integer(),
+            2
+        );
+    }
+}
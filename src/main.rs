@@ -66,6 +66,24 @@ fn main() {
         RunMode::GuiSimulation(sim_args, gui_args) => {
             runners::start_sim_with_gui(sim_args, gui_args);
         }
+        RunMode::TerminalSimulation(sim_args, ui_args) => {
+            runners::start_sim_with_tui(sim_args, ui_args);
+        }
+        RunMode::ExperimentSimReplayTerminal(exp_args, ui_args) => {
+            runners::start_exp_replay_with_tui(exp_args, ui_args);
+        }
+        RunMode::ConfiguredExperiment(args) => {
+            runners::run_configured_experiment(args);
+        }
+        RunMode::Sweep(args) => {
+            runners::run_sweep(args);
+        }
+        RunMode::Tournament(args) => {
+            runners::run_tournament(args);
+        }
+        RunMode::ExportLineage(args) => {
+            runners::export_lineage(args);
+        }
         RunMode::OneOff(scenario_key) => {
             run_one_off(&scenario_key);
         }
@@ -6,6 +6,7 @@ use crate::{
     biology::{
         experiments::{
             logging::{get_exp_genomes_dir, get_experiments_dir},
+            seeding::load_genomes,
             types::ExperimentSimSettings,
         },
         unit_behavior::framed::FramedGenomeUnitBehavior,
@@ -24,6 +25,7 @@ use crate::{
 };
 use std::io::prelude::*;
 use std::rc::Rc;
+use std::sync::Arc;
 
 fn load_file(path: PathBuf) -> String {
     println!("path: {:?}", path);
@@ -57,9 +59,9 @@ pub fn construct_replay_sim(exp_key: &str, genome_filename: &str) -> SimulationB
 
     let chemistry_builder = settings.chemistry_options.clone();
     let chemistry = chemistry_builder.build();
-    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_rc();
+    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_arc();
 
-    let genomes = load_genome_csv(path, &settings.chemistry_options.chemistry_key);
+    let genomes = load_genome_file(path, &settings.chemistry_options);
     let unit_entries = genomes
         .iter()
         .enumerate()
@@ -82,31 +84,20 @@ pub fn construct_replay_sim(exp_key: &str, genome_filename: &str) -> SimulationB
         })
 }
 
-pub fn load_genome_csv(path: PathBuf, chemistry_key: &str) -> Vec<Rc<CompiledFramedGenome>> {
-    let mut file = fs::OpenOptions::new()
-        .read(true)
-        .open(path.as_path())
-        .unwrap();
-
-    let mut str = String::new();
-    file.read_to_string(&mut str);
+/**
+ * Compiles the logged genomes against the chemistry that chemistry_builder configures, which
+ * should be the one of the experiment the genomes are replayed in.
+ */
+pub fn load_genome_file(
+    path: PathBuf,
+    chemistry_builder: &ChemistryBuilder,
+) -> Vec<Arc<CompiledFramedGenome>> {
+    let chemistry = chemistry_builder.build();
+    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_arc();
 
-    // println!("raw: {}", &str);
-    let chemistry = ChemistryBuilder::with_key(chemistry_key).build();
-    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_rc();
-
-    let genomes = str
-        .split("\n")
-        .filter(|s| !s.trim().is_empty())
-        .map(|line| {
-            let genome_vals = line
-                .trim()
-                .split(",")
-                .map(|v| v.trim().parse::<FramedGenomeWord>().unwrap())
-                .collect::<Vec<_>>();
-
-            FramedGenomeCompiler::compile(genome_vals, &gm).wrap_rc()
-        })
+    let genomes = load_genomes(&path, &gm)
+        .into_iter()
+        .map(|genome_vals| FramedGenomeCompiler::compile(genome_vals, &gm).wrap_arc())
         .collect::<Vec<_>>();
 
     if genomes.len() == 0 {
@@ -142,7 +133,7 @@ pub fn inspect_genome() {
         .collect::<Vec<_>>();
 
     let chemistry = ChemistryBuilder::with_key("cheese").build();
-    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_rc();
+    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_arc();
     let genome = FramedGenomeCompiler::compile(vals, &gm);
     println!("genome:\n{}", genome.display(&gm));
 }
@@ -11,11 +11,27 @@ use crate::{
         SimulationControlEvent,
     },
     ui::event_loop::{UiConfig, WorldRenderConfig},
+    ui::terminal::TerminalViewConfig,
 };
 
+use crate::biology::experiments::lineage::{
+    lineage_to_graphml, lineage_to_newick, parse_lineage_csv,
+};
+use crate::biology::experiments::logging::{
+    ensure_experiment_data_dir_exists, get_exp_genomes_dir, get_experiments_dir,
+};
+use crate::biology::experiments::sweep::{sweep_summary_csv, sweep_summary_table, SweepSpec};
+use crate::biology::experiments::tournament::{
+    Tournament, TournamentContestant, TournamentFormat, TournamentSettings,
+};
+use crate::biology::experiments::variants::multi_pool::config::MultiPoolExperimentConfig;
+use crate::biology::experiments::variants::multi_pool::logger::{
+    MultiPoolExperimentLogger, MultiPoolExperimentLoggingSettings,
+};
+use crate::simulation::fitness::fitness_calculators_for;
 use crate::ui;
 
-use self::exp_replay::{construct_replay_sim, load_exp_settings};
+use self::exp_replay::{construct_replay_sim, load_exp_settings, load_genome_file};
 
 #[derive(Clone)]
 pub struct SimulationRunnerArgs {
@@ -42,14 +58,58 @@ pub struct ExperimentSimReplayGuiArgs {
     pub genome_filename: String,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub enum LineageExportFormat {
+    Newick,
+    GraphMl,
+}
+
+#[derive(Clone)]
+pub struct LineageExportArgs {
+    pub experiment_name_key: String,
+    pub gene_pool_id: usize,
+    pub format: LineageExportFormat,
+}
+
+#[derive(Clone)]
+pub struct TournamentArgs {
+    // each one is an experiment name and a genome file in its genomes dir
+    pub genome_files: Vec<(String, String)>,
+
+    // the experiment whose simulation settings the matches are played in
+    pub settings_experiment_name_key: Option<String>,
+    pub format: TournamentFormat,
+    pub num_trials: usize,
+    pub seed: u64,
+    pub fitness_calculation_key: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct ConfiguredExperimentArgs {
+    pub config_path: String,
+    pub experiment_name_key: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct SweepArgs {
+    pub spec_path: String,
+    pub max_parallel_runs: Option<usize>,
+}
+
 pub enum RunMode {
     HeadlessSimulation(SimulationRunnerArgs),
     GuiSimulation(SimulationRunnerArgs, SimulationUiRunnerArgs),
     ExperimentSimReplayGui(ExperimentSimReplayGuiArgs, SimulationUiRunnerArgs),
     HeadlessExperiment(ExperimentRunnerArgs),
     MultiPoolExperiment(ExperimentRunnerArgs),
+    ConfiguredExperiment(ConfiguredExperimentArgs),
+    Sweep(SweepArgs),
+    TerminalSimulation(SimulationRunnerArgs, SimulationUiRunnerArgs),
+    ExperimentSimReplayTerminal(ExperimentSimReplayGuiArgs, SimulationUiRunnerArgs),
     GuiExperiment(ExperimentRunnerArgs),
     OneOff(String),
+    ExportLineage(LineageExportArgs),
+    Tournament(TournamentArgs),
 }
 
 // use crate::scenarios::experiments::get_experiment_scenario;
@@ -153,8 +213,203 @@ pub fn start_exp_replay_with_ui(
     );
 }
 
+pub fn start_sim_with_tui(
+    sim_runner_args: SimulationRunnerArgs,
+    sim_ui_runner_args: SimulationUiRunnerArgs,
+) {
+    let (sender_from_ui, receiver_from_ui) = std::sync::mpsc::channel::<SimulationControlEvent>();
+    let sim_ref = new_threaded_simulation_reference();
+    let sim_ref2 = sim_ref.clone();
+
+    let view_updates_per_second = sim_ui_runner_args.max_view_updates_per_second.unwrap_or(4);
+
+    let handle = std::thread::spawn(move || {
+        let mut sim = get_simulation_scenario(&sim_runner_args);
+        let mut executor = ThreadedSimulationExecutor::new(
+            sim,
+            sim_ref2,
+            receiver_from_ui,
+            sim_ui_runner_args.max_ticks_per_second.unwrap_or(1),
+            view_updates_per_second,
+        );
+
+        executor.is_paused = true;
+        executor.run();
+    });
+
+    ui::terminal::start_sim_tui(
+        sim_ref,
+        sender_from_ui,
+        TerminalViewConfig {
+            renders_per_second: view_updates_per_second,
+        },
+    );
+    handle.join();
+}
+
+pub fn start_exp_replay_with_tui(
+    exp_replay_args: ExperimentSimReplayGuiArgs,
+    sim_ui_runner_args: SimulationUiRunnerArgs,
+) {
+    let (sender_from_ui, receiver_from_ui) = std::sync::mpsc::channel::<SimulationControlEvent>();
+    let sim_ref = new_threaded_simulation_reference();
+    let sim_ref2 = sim_ref.clone();
+
+    let view_updates_per_second = sim_ui_runner_args.max_view_updates_per_second.unwrap_or(4);
+
+    let handle = std::thread::spawn(move || {
+        let mut sim = construct_replay_sim(
+            &exp_replay_args.experiment_name_key,
+            &exp_replay_args.genome_filename,
+        )
+        .to_simulation();
+        let mut executor = ThreadedSimulationExecutor::new(
+            sim,
+            sim_ref2,
+            receiver_from_ui,
+            sim_ui_runner_args.max_ticks_per_second.unwrap_or(1),
+            view_updates_per_second,
+        );
+
+        executor.is_paused = true;
+        executor.run();
+    });
+
+    ui::terminal::start_sim_tui(
+        sim_ref,
+        sender_from_ui,
+        TerminalViewConfig {
+            renders_per_second: view_updates_per_second,
+        },
+    );
+    handle.join();
+}
+
+/**
+ * Converts a gene pool's lineage.csv into a file next to it that tree and graph viewers can
+ * read.
+ */
+pub fn export_lineage(args: LineageExportArgs) {
+    let logger = MultiPoolExperimentLogger {
+        settings: MultiPoolExperimentLoggingSettings {
+            experiment_key: args.experiment_name_key.clone(),
+            ..MultiPoolExperimentLoggingSettings::default()
+        },
+    };
+    let lineage_path = logger.get_gene_pool_lineage_path(args.gene_pool_id);
+    let contents = std::fs::read_to_string(&lineage_path)
+        .expect(&format!("could not read lineage file {:?}", lineage_path));
+    let records = parse_lineage_csv(&contents);
+
+    let (exported, extension) = match args.format {
+        LineageExportFormat::Newick => (lineage_to_newick(&records), "nwk"),
+        LineageExportFormat::GraphMl => (lineage_to_graphml(&records), "graphml"),
+    };
+
+    let out_path = lineage_path.with_extension(extension);
+    std::fs::write(&out_path, exported).expect("failed to write lineage export");
+    println!(
+        "exported {} genomes to {}",
+        records.len(),
+        out_path.to_str().unwrap()
+    );
+}
+
+pub fn run_tournament(args: TournamentArgs) {
+    if args.genome_files.is_empty() {
+        panic!("No genome files given");
+    }
+
+    let settings_exp_key = args
+        .settings_experiment_name_key
+        .clone()
+        .unwrap_or(args.genome_files[0].0.clone());
+    let sim_settings = load_exp_settings(&settings_exp_key);
+
+    let fitness_calculation_key = args.fitness_calculation_key.clone().unwrap_or_else(|| {
+        let manifest = sim_settings.chemistry_options.manifest();
+        fitness_calculators_for(&manifest)
+            .first()
+            .expect("The chemistry has no fitness calculator, pass one explicitly")
+            .key
+            .clone()
+    });
+
+    let mut contestants = vec![];
+    for (exp_key, genome_filename) in args.genome_files.iter() {
+        let mut path = get_exp_genomes_dir(exp_key);
+        path.push(genome_filename);
+
+        for (i, genome) in load_genome_file(path, &sim_settings.chemistry_options)
+            .iter()
+            .enumerate()
+        {
+            contestants.push(TournamentContestant {
+                name: format!("{}/{}#{}", exp_key, genome_filename, i),
+                genome: genome.as_ref().clone(),
+            });
+        }
+    }
+
+    println!(
+        "Starting tournament of {} genomes with fitness {}",
+        contestants.len(),
+        &fitness_calculation_key
+    );
+    let mut tournament = Tournament::new(
+        contestants,
+        TournamentSettings {
+            format: args.format,
+            num_trials: args.num_trials,
+            seed: args.seed,
+            sim_settings,
+            fitness_calculation_key,
+            use_threads: true,
+        },
+    );
+    tournament.run();
+
+    println!("{}", tournament.report());
+}
+
 pub fn run_multi_pool_experiment(args: ExperimentRunnerArgs) {
     let mut exp = get_multipool_experiment_scenario(args);
 
     exp.start();
 }
+
+pub fn run_configured_experiment(args: ConfiguredExperimentArgs) {
+    let mut config = MultiPoolExperimentConfig::load(std::path::Path::new(&args.config_path));
+    if let Some(experiment_name_key) = &args.experiment_name_key {
+        config = config.with_experiment_key(experiment_name_key);
+    }
+
+    println!(
+        "Starting experiment {} from {}",
+        &config.settings.experiment_key, &args.config_path
+    );
+    let mut exp = config.to_experiment();
+    exp.start();
+}
+
+/**
+ * Runs a sweep, then prints its summary and saves it as <sweep name>_sweep.csv in the
+ * experiments dir.
+ */
+pub fn run_sweep(args: SweepArgs) {
+    let mut spec = SweepSpec::load(std::path::Path::new(&args.spec_path));
+    if let Some(max_parallel_runs) = args.max_parallel_runs {
+        spec.max_parallel_runs = max_parallel_runs;
+    }
+
+    let results = crate::biology::experiments::sweep::run_sweep(&spec);
+
+    ensure_experiment_data_dir_exists();
+    let mut path = get_experiments_dir();
+    path.push(format!("{}_sweep.csv", &spec.name_key));
+    std::fs::write(&path, sweep_summary_csv(&spec, &results))
+        .expect("failed to write sweep summary");
+
+    println!("{}", sweep_summary_table(&spec, &results));
+    println!("summary saved to {}", path.to_str().unwrap());
+}
@@ -4,6 +4,7 @@ use crate::biology::experiments::alterations::default_alteration_set;
 use crate::biology::experiments::fitness::FitnessRankAdjustmentMethod;
 use crate::biology::experiments::types::SeedGenomeSettings;
 use crate::biology::experiments::variants::multi_pool::builder::MultiPoolExperimentSettingsBuilder;
+use crate::biology::experiments::variants::multi_pool::config::MultiPoolExperimentConfig;
 use crate::biology::experiments::variants::multi_pool::logger::MultiPoolExperimentLoggingSettings;
 use crate::biology::experiments::variants::multi_pool::types::{
     FitnessCycleStrategy, GenePoolSettings,
@@ -94,7 +95,7 @@ pub fn multi_pool_cheese_experiment_just_one(
 ) -> MultiPoolExperiment {
     let chemistry_builder = ChemistryBuilder::with_key("cheese");
     let chemistry = chemistry_builder.build();
-    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_rc();
+    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_arc();
 
     let settings = MultiPoolExperimentSettingsBuilder::default()
         .max_iterations(1000)
@@ -138,17 +139,17 @@ pub fn multi_pool_cheese_experiment_just_one(
 pub fn multi_pool_cheese_experiment_vary_chemistry_config(
     runner_args: ExperimentRunnerArgs,
 ) -> MultiPoolExperiment {
-    let chemistry_builder = ChemistryBuilder::with_key("cheese");
-    let chemistry = chemistry_builder.build();
-    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_rc();
+    vary_chemistry_config(&runner_args.experiment_name_key).to_experiment()
+}
 
+pub fn vary_chemistry_config(experiment_name_key: &str) -> MultiPoolExperimentConfig {
     let settings = MultiPoolExperimentSettingsBuilder::default()
         .max_iterations(10000000)
         // .max_iterations(100)
         .chemistry_key("cheese".to_owned())
-        .experiment_key(runner_args.experiment_name_key.clone())
+        .experiment_key(experiment_name_key.to_string())
         .logging_settings(MultiPoolExperimentLoggingSettings {
-            experiment_key: runner_args.experiment_name_key.clone(),
+            experiment_key: experiment_name_key.to_string(),
             allow_overwrite: true,
             checkpoint_interval: 1000,
         })
@@ -211,9 +212,10 @@ pub fn multi_pool_cheese_experiment_vary_chemistry_config(
             .build(),
     ];
 
-    let mut exp = MultiPoolExperiment::new(settings, gene_pool_settings);
-    exp.initialize();
-    exp
+    MultiPoolExperimentConfig {
+        settings,
+        gene_pools: gene_pool_settings,
+    }
 }
 // GenePoolSettings {
 //         sim_settings: ExperimentSimSettingsBuilder::default()
@@ -40,7 +40,7 @@ pub fn alterations() -> CompiledAlterationSet {
 pub fn simple_experiment(runner_args: ExperimentRunnerArgs) -> SimpleExperiment {
     let chemistry_builder = ChemistryBuilder::with_key("cheese");
     let chemistry = chemistry_builder.build();
-    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_rc();
+    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_arc();
 
     let settings = SimpleExperimentSettings {
         experiment_key: runner_args.experiment_name_key.to_string(),
@@ -66,6 +66,10 @@ pub fn simple_experiment(runner_args: ExperimentRunnerArgs) -> SimpleExperiment
         alteration_set: alterations(),
         fitness_calculation_key: "total_cheese_acquired".to_string(),
         cull_strategy: CullStrategy::WorstFirst { percent: 0.30 },
+        seed_genome_settings: SeedGenomeSettings::Random {
+            min_size: 30,
+            max_size: 50,
+        },
         fitness_cycle_strategy: FitnessCycleStrategy::Exaustive {
             group_scramble_pct: 0.40,
         },
@@ -1,7 +1,7 @@
 use crate::{
     biology::experiments::{
         alterations::{self, CompiledAlterationSet},
-        types::{CullStrategy, ExperimentSimSettings},
+        types::{CullStrategy, ExperimentSimSettings, SeedGenomeSettings},
         variants::{
             multi_pool::types::FitnessCycleStrategy,
             simple::{
@@ -28,10 +28,14 @@ pub fn alterations() -> CompiledAlterationSet {
 pub fn simple_experiment(runner_args: ExperimentRunnerArgs) -> SimpleExperiment {
     let chemistry_builder = ChemistryBuilder::with_key("lever");
     let chemistry = chemistry_builder.build();
-    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_rc();
+    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_arc();
 
     let settings = SimpleExperimentSettings {
         cull_strategy: CullStrategy::WorstFirst { percent: 0.30 },
+        seed_genome_settings: SeedGenomeSettings::Random {
+            min_size: 30,
+            max_size: 50,
+        },
         fitness_calculation_key: "lever_pulls".to_string(),
         num_genomes: 10,
         sim_settings: ExperimentSimSettings {
@@ -5,6 +5,7 @@ use crate::{
 
 pub mod cheese;
 pub mod lever;
+pub mod predator_prey;
 
 pub fn get_experiment_scenario(runner_args: ExperimentRunnerArgs) -> SimpleExperiment {
     match runner_args.experiment_scenario_key.as_str() {
@@ -22,6 +23,7 @@ pub fn get_multipool_experiment_scenario(runner_args: ExperimentRunnerArgs) -> M
         "cheese_multi_pool" => {
             cheese::multi::multi_pool_cheese_experiment_vary_chemistry_config(runner_args.clone())
         }
+        "predator_prey_coevolution" => predator_prey::coevolution_experiment(runner_args.clone()),
         _ => panic!("scenario not defined"),
     }
 }
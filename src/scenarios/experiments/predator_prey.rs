@@ -0,0 +1,95 @@
+use crate::biology::experiments::builders::{
+    ExperimentSimSettingsBuilder, GenePoolSettingsBuilder,
+};
+use crate::biology::experiments::fitness::FitnessRankAdjustmentMethod;
+use crate::biology::experiments::types::{CullStrategy, SeedGenomeSettings};
+use crate::biology::experiments::variants::multi_pool::builder::MultiPoolExperimentSettingsBuilder;
+use crate::biology::experiments::variants::multi_pool::coevolution::CoevolutionSettings;
+use crate::biology::experiments::variants::multi_pool::config::MultiPoolExperimentConfig;
+use crate::biology::experiments::variants::multi_pool::logger::MultiPoolExperimentLoggingSettings;
+use crate::biology::experiments::variants::multi_pool::types::FitnessCycleStrategy;
+use crate::biology::experiments::variants::multi_pool::MultiPoolExperiment;
+use crate::runners::ExperimentRunnerArgs;
+use crate::simulation::unit::UnitAttributeValue;
+
+use super::cheese::multi::alterations;
+
+fn side_sim_settings(is_predator: bool) -> ExperimentSimSettingsBuilder {
+    let mut sim_settings = ExperimentSimSettingsBuilder::default();
+    sim_settings
+        .num_simulation_ticks(100)
+        .grid_size((30, 30))
+        .num_genomes_per_sim(10)
+        .default_unit_attr(vec![(
+            "is_predator".to_string(),
+            UnitAttributeValue::Bool(is_predator),
+        )])
+        .chemistry_key("predator_prey".to_string());
+
+    sim_settings
+}
+
+fn side_gene_pool(is_predator: bool, num_genomes: usize) -> GenePoolSettingsBuilder {
+    let (name_key, fitness_calculation_key) = if is_predator {
+        ("predators", "prey_eaten")
+    } else {
+        ("prey", "unit_ticks_alive")
+    };
+
+    let mut gene_pool = GenePoolSettingsBuilder::default();
+    gene_pool
+        .sim_settings(side_sim_settings(is_predator).build())
+        .receive_external_genomes(false)
+        .num_genomes(num_genomes)
+        .name_key(name_key.to_string())
+        .alteration_specs(alterations())
+        .fitness_calculation_key(fitness_calculation_key.to_string())
+        .fitness_cycle_strategy(FitnessCycleStrategy::Exaustive {
+            group_scramble_pct: 0.30,
+        })
+        .fitness_rank_adjustment_method(FitnessRankAdjustmentMethod::Absolute)
+        .seed_genome_settings(SeedGenomeSettings::Random {
+            min_size: 20,
+            max_size: 100,
+        })
+        .cull_strategy(CullStrategy::WorstFirst { percent: 0.30 });
+
+    gene_pool
+}
+
+/**
+ * A pool of prey and a pool of predators evolving against each other.
+ */
+pub fn coevolution_config(
+    experiment_name_key: &str,
+    num_genomes: usize,
+) -> MultiPoolExperimentConfig {
+    let settings = MultiPoolExperimentSettingsBuilder::default()
+        .max_iterations(100000)
+        .chemistry_key("predator_prey".to_owned())
+        .experiment_key(experiment_name_key.to_string())
+        .logging_settings(MultiPoolExperimentLoggingSettings {
+            experiment_key: experiment_name_key.to_string(),
+            allow_overwrite: true,
+            checkpoint_interval: 1000,
+        })
+        .evaluation_points_per_tick(num_genomes as u64)
+        .reference_sim_settings(side_sim_settings(false).build())
+        .reference_fitness_calculation_key("unit_ticks_alive".to_owned())
+        .coevolution(CoevolutionSettings {
+            opposing_pools: vec![(0, 1)],
+        })
+        .build();
+
+    MultiPoolExperimentConfig {
+        settings,
+        gene_pools: vec![
+            side_gene_pool(false, num_genomes).build(),
+            side_gene_pool(true, num_genomes).build(),
+        ],
+    }
+}
+
+pub fn coevolution_experiment(runner_args: ExperimentRunnerArgs) -> MultiPoolExperiment {
+    coevolution_config(&runner_args.experiment_name_key, 20).to_experiment()
+}
@@ -1,7 +1,7 @@
 use crate::{
     biology::experiments::{
         alterations,
-        types::{CullStrategy, ExperimentSimSettings},
+        types::{CullStrategy, ExperimentSimSettings, SeedGenomeSettings},
         variants::{
             multi_pool::types::FitnessCycleStrategy,
             simple::{
@@ -21,10 +21,14 @@ use std::rc::Rc;
 pub fn test_fitness(key: &str) {
     let exp_key = key.to_string();
     let chemistry_builder = ChemistryBuilder::with_key("cheese");
-    let gm = GeneticManifest::from_chemistry(&chemistry_builder.build()).wrap_rc();
+    let gm = GeneticManifest::from_chemistry(&chemistry_builder.build()).wrap_arc();
 
     let settings = SimpleExperimentSettings {
         cull_strategy: CullStrategy::WorstFirst { percent: 0.30 },
+        seed_genome_settings: SeedGenomeSettings::Random {
+            min_size: 30,
+            max_size: 50,
+        },
         fitness_calculation_key: "total_cheese_acquired".to_string(),
         num_genomes: 4,
         sim_settings: ExperimentSimSettings {
@@ -1,7 +1,7 @@
 use crate::{
     biology::experiments::{
         alterations,
-        types::{CullStrategy, ExperimentSimSettings},
+        types::{CullStrategy, ExperimentSimSettings, SeedGenomeSettings},
         variants::{
             multi_pool::types::FitnessCycleStrategy,
             simple::{
@@ -16,12 +16,12 @@ use crate::{
 use crate::biology::genome::framed::builders::*;
 use crate::biology::unit_behavior::framed::common::*;
 use crate::simulation::common::*;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub fn test_fitness(key: &str) {
     let exp_key = key.to_string();
     let chemistry_builder = ChemistryBuilder::with_key("cheese");
-    let gm = GeneticManifest::from_chemistry(&chemistry_builder.build()).wrap_rc();
+    let gm = GeneticManifest::from_chemistry(&chemistry_builder.build()).wrap_arc();
 
     let genome_vals1 = frame_from_single_channel(vec![gene(
         if_any!(if_all!(conditional!(is_truthy, 1))),
@@ -49,6 +49,10 @@ pub fn test_fitness(key: &str) {
 
     let settings = SimpleExperimentSettings {
         cull_strategy: CullStrategy::WorstFirst { percent: 0.30 },
+        seed_genome_settings: SeedGenomeSettings::Random {
+            min_size: 30,
+            max_size: 50,
+        },
         fitness_calculation_key: "lever_pulls".to_string(),
         num_genomes: 4,
         sim_settings: ExperimentSimSettings {
@@ -79,13 +83,13 @@ pub fn test_fitness(key: &str) {
     exp.initialize();
 
     exp.genome_entries[0].compiled_genome =
-        Rc::new(FramedGenomeCompiler::compile(genome_vals1, &gm));
+        Arc::new(FramedGenomeCompiler::compile(genome_vals1, &gm));
     exp.genome_entries[1].compiled_genome =
-        Rc::new(FramedGenomeCompiler::compile(genome_vals2, &gm));
+        Arc::new(FramedGenomeCompiler::compile(genome_vals2, &gm));
     exp.genome_entries[2].compiled_genome =
-        Rc::new(FramedGenomeCompiler::compile(genome_vals3, &gm));
+        Arc::new(FramedGenomeCompiler::compile(genome_vals3, &gm));
     exp.genome_entries[3].compiled_genome =
-        Rc::new(FramedGenomeCompiler::compile(genome_vals4, &gm));
+        Arc::new(FramedGenomeCompiler::compile(genome_vals4, &gm));
 
     // exp.genome_entries[0].compiled_genome.raw_values = genome_vals1.clone();
     // exp.genome_entries[1].compiled_genome.raw_values = genome_vals2.clone();
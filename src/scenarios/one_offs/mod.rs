@@ -26,6 +26,9 @@ pub fn run_one_off(scenario_key: &str) {
         "test_sim_perf" => {
             perf::sim::test_sim_perf();
         }
+        "test_tiled_tick_perf" => {
+            perf::tiles::test_tiled_tick_perf();
+        }
         _ => {
             panic!("Scenario key not found: {}", scenario_key);
         }
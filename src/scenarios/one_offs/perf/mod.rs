@@ -1,2 +1,3 @@
 pub mod closures;
 pub mod sim;
+pub mod tiles;
@@ -1,5 +1,4 @@
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::sync::Arc;
 use std::time::Instant;
 
 use crate::biology::genome::framed::render::with_stats::render_frames_with_stats;
@@ -18,15 +17,15 @@ use crate::simulation::Simulation;
 pub fn test_sim_perf() {
     let chemistry_builder = ChemistryBuilder::with_key("cheese");
     let chemistry = chemistry_builder.build();
-    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_rc();
+    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_arc();
 
     let genome1 = get_genome2(&gm);
     let genome2 = get_genome2(&gm);
     let genome3 = get_genome2(&gm);
 
-    let genome1_stats = Rc::new(RefCell::new(genome1.new_stats()));
-    let genome2_stats = Rc::new(RefCell::new(genome2.new_stats()));
-    let genome3_stats = Rc::new(RefCell::new(genome3.new_stats()));
+    let genome1_stats = Arc::new(genome1.new_stats());
+    let genome2_stats = Arc::new(genome2.new_stats());
+    let genome3_stats = Arc::new(genome3.new_stats());
 
     let start_time = Instant::now();
 
@@ -93,7 +92,7 @@ pub fn test_sim_perf() {
         if i == 999 {
             println!(
                 "{}",
-                render_frames_with_stats(&genome1.frames, &gm, Some(&genome1_stats.borrow()))
+                render_frames_with_stats(&genome1.frames, &gm, Some(&genome1_stats))
             );
         }
     }
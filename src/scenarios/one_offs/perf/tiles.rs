@@ -0,0 +1,69 @@
+use std::time::Instant;
+
+use crate::biology::genome::framed::samples::cheese::get_genome2;
+use crate::biology::unit_behavior::framed::FramedGenomeUnitBehavior;
+use crate::chemistry::builder::*;
+use crate::chemistry::{ChemistryConfigBuilder, IntentConflictPolicy, UnitUpdateMode};
+use crate::simulation::common::helpers::place_units::PlaceUnitsMethod;
+use crate::simulation::common::{GeneticManifest, UnitManifest};
+use crate::simulation::config::SimulationBuilder;
+use crate::simulation::unit_entry::builder::UnitEntryBuilder;
+
+/**
+ * Prints the time per tick of each update mode on a large world, relative to the sequential
+ * mode.  The tiled modes decide the units' behaviors on the available threads.
+ */
+pub fn test_tiled_tick_perf() {
+    let size = (500, 500);
+    let ticks = 10;
+
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    println!("{} threads available", threads);
+
+    let modes = vec![
+        UnitUpdateMode::Sequential,
+        UnitUpdateMode::Simultaneous(IntentConflictPolicy::RandomPriority),
+        UnitUpdateMode::Tiled(IntentConflictPolicy::RandomPriority, 64),
+        UnitUpdateMode::Tiled(IntentConflictPolicy::RandomPriority, 16),
+    ];
+
+    let mut baseline_ms: Option<f64> = None;
+    for mode in modes {
+        let config = ChemistryConfigBuilder::new()
+            .set_update_mode(mode.clone())
+            .build();
+        let chemistry_builder = ChemistryBuilder::with_key("cheese").config(config);
+        let chemistry = chemistry_builder.build();
+        let gm = GeneticManifest::from_chemistry(&chemistry).wrap_arc();
+
+        let entry = UnitEntryBuilder::default()
+            .species_name("species1".to_string())
+            .behavior(FramedGenomeUnitBehavior::new(get_genome2(&gm), gm.clone()).construct())
+            .default_resources(vec![("cheese".to_string(), 100)])
+            .build(&chemistry_builder.manifest());
+
+        let mut sim = SimulationBuilder::default()
+            .chemistry(chemistry)
+            .size(size)
+            .iterations(ticks)
+            .place_units_method(PlaceUnitsMethod::SimpleDropMultiple {
+                attributes: None,
+                units_per_entry: 50000,
+            })
+            .unit_manifest(UnitManifest { units: vec![entry] })
+            .to_simulation();
+
+        let start_time = Instant::now();
+        for _ in 0..ticks {
+            sim.tick();
+        }
+        let ms_per_tick = start_time.elapsed().as_secs_f64() * 1000.0 / ticks as f64;
+
+        let speedup = baseline_ms.map_or(1.0, |baseline| baseline / ms_per_tick);
+        baseline_ms.get_or_insert(ms_per_tick);
+        println!(
+            "{:?}: {:.1}ms per tick, {:.2}x vs sequential",
+            mode, ms_per_tick, speedup
+        );
+    }
+}
@@ -13,8 +13,7 @@ use crate::simulation::common::helpers::place_units::PlaceUnitsMethod;
 use crate::simulation::common::*;
 use crate::simulation::config::*;
 use crate::simulation::executors::threaded::ThreadedSimulationExecutor;
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub fn basic(sim_args: &SimulationRunnerArgs) -> SimulationBuilder {
     let chemistry_builder = ChemistryBuilder::with_key("cheese");
@@ -39,17 +38,17 @@ pub fn basic(sim_args: &SimulationRunnerArgs) -> SimulationBuilder {
 pub fn with_genomes(sim_args: &SimulationRunnerArgs) -> SimulationBuilder {
     let chemistry_builder = ChemistryBuilder::with_key("cheese");
     let chemistry = chemistry_builder.build();
-    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_rc();
+    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_arc();
 
     use crate::biology::genome::framed::samples::cheese::get_genome1;
     let frames1 = get_genome1(&gm);
 
     let genome_values2 = legacy::get_genome2().build(&gm);
     let frames2 =
-        FramedGenomeCompiler::compile(simple_convert_into_frames(genome_values2), &gm).wrap_rc();
+        FramedGenomeCompiler::compile(simple_convert_into_frames(genome_values2), &gm).wrap_arc();
     let genome_values3 = legacy::get_genome3().build(&gm);
     let frames3 =
-        FramedGenomeCompiler::compile(simple_convert_into_frames(genome_values3), &gm).wrap_rc();
+        FramedGenomeCompiler::compile(simple_convert_into_frames(genome_values3), &gm).wrap_arc();
 
     let entry1 = UnitEntryBuilder::default()
         .species_name("species1".to_string())
@@ -81,7 +80,7 @@ pub fn with_genome2(sim_args: &SimulationRunnerArgs) -> SimulationBuilder {
 
     let chemistry_builder = ChemistryBuilder::with_key("cheese");
     let chemistry = chemistry_builder.build();
-    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_rc();
+    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_arc();
 
     use crate::biology::genome::framed::samples::cheese::get_genome1;
     let frames1 = get_genome2(&gm);
@@ -104,6 +103,6 @@ pub fn with_genome2(sim_args: &SimulationRunnerArgs) -> SimulationBuilder {
 pub fn get_unit_entries_for_cheese() -> Vec<UnitEntryBuilder> {
     vec![UnitEntryBuilder::default()
         .species_name("main".to_string())
-        .behavior(Rc::new(RefCell::new(SimpleMouse::construct())))
+        .behavior(Arc::new(SimpleMouse::construct()))
         .default_resources(vec![("cheese".to_owned(), 200)])]
 }
@@ -14,8 +14,7 @@ use crate::simulation::common::variants::LeverChemistry;
 use crate::simulation::common::*;
 use crate::simulation::config::*;
 use crate::simulation::executors::threaded::ThreadedSimulationExecutor;
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub fn basic(sim_args: &SimulationRunnerArgs) -> SimulationBuilder {
     let chemistry_builder = ChemistryBuilder::with_key("lever");
@@ -28,7 +27,7 @@ pub fn basic(sim_args: &SimulationRunnerArgs) -> SimulationBuilder {
 pub fn with_genome(sim_args: &SimulationRunnerArgs) -> SimulationBuilder {
     let chemistry_builder = ChemistryBuilder::with_key("lever");
     let chemistry = chemistry_builder.build();
-    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_rc();
+    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_arc();
 
     use crate::biology::genome::framed::samples::lever::genome1;
     let _genome1 = genome1(&gm);
@@ -49,5 +48,5 @@ pub fn with_genome(sim_args: &SimulationRunnerArgs) -> SimulationBuilder {
 pub fn get_unit_entries_for_lever() -> Vec<UnitEntryBuilder> {
     vec![UnitEntryBuilder::default()
         .species_name("main".to_string())
-        .behavior(Rc::new(RefCell::new(SimpleLever::construct())))]
+        .behavior(Arc::new(SimpleLever::construct()))]
 }
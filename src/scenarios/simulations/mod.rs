@@ -1,5 +1,6 @@
 pub mod cheese;
 pub mod lever;
+pub mod predator_prey;
 
 use crate::biology::unit_behavior::mouse::*;
 use crate::runners::SimulationRunnerArgs;
@@ -38,6 +39,8 @@ pub fn get_simulation_scenario(sim_args: &SimulationRunnerArgs) -> Simulation {
         ("cheese", "with_genome2") => cheese::with_genome2(sim_args),
         ("lever", "basic") => lever::basic(sim_args),
         ("lever", "with_genome") => lever::with_genome(sim_args),
+        ("predator_prey", "basic") => predator_prey::basic(sim_args),
+        ("predator_prey", "evolving") => predator_prey::evolving(sim_args),
         _ => panic!("Unsupported simulation scenario"),
     };
 
@@ -0,0 +1,54 @@
+use crate::biology::experiments::util::random_genome_of_length;
+use crate::biology::genome::framed::builders::FramedGenomeCompiler;
+use crate::biology::unit_behavior::framed::FramedGenomeUnitBehavior;
+use crate::runners::SimulationRunnerArgs;
+use crate::simulation::common::builder::ChemistryBuilder;
+use crate::simulation::common::helpers::place_units::PlaceUnitsMethod;
+use crate::simulation::common::*;
+use crate::simulation::in_sim_evolution::InSimEvolution;
+
+/**
+ * Like basic, but the genomes keep evolving inside the simulation: offspring are mutated copies
+ * of their parent, or crossovers when bred through mate.
+ */
+pub fn evolving(sim_args: &SimulationRunnerArgs) -> SimulationBuilder {
+    basic(sim_args)
+        .in_sim_evolution(InSimEvolution::with_mutation_odds(0.1))
+        .auto_retire_unit_entries(true)
+        .iterations(5000)
+}
+
+/**
+ * One prey and one predator species, each running a random genome.
+ */
+pub fn basic(sim_args: &SimulationRunnerArgs) -> SimulationBuilder {
+    let chemistry_builder = ChemistryBuilder::with_key("predator_prey");
+    let chemistry = chemistry_builder.build();
+    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_arc();
+
+    let entries = [("prey", false), ("predator", true)]
+        .iter()
+        .map(|(name, is_predator)| {
+            let genome = FramedGenomeCompiler::compile(random_genome_of_length(40), &gm).wrap_arc();
+
+            UnitEntryBuilder::default()
+                .species_name(name.to_string())
+                .behavior(FramedGenomeUnitBehavior::new(genome, gm.clone()).construct())
+                .default_attributes(vec![(
+                    "is_predator".to_string(),
+                    UnitAttributeValue::Bool(*is_predator),
+                )])
+                .build(&chemistry_builder.manifest())
+        })
+        .collect::<Vec<_>>();
+
+    SimulationBuilder::default()
+        .chemistry(chemistry)
+        .unit_manifest(UnitManifest { units: entries })
+        .place_units_method(PlaceUnitsMethod::SimpleDropMultiple {
+            attributes: None,
+            units_per_entry: 10,
+        })
+        .size((30, 30))
+        .iterations(1000)
+}
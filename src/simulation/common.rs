@@ -12,7 +12,7 @@ pub use super::{
     SimulationResourceAmount, SimulationResourceIndex,
 };
 
-pub use crate::biology::sensor_manifest::{SensorContext, SensorManifest};
+pub use crate::biology::sensor_manifest::{SensorContext, SensorManifest, SensorValue};
 pub use crate::biology::unit_behavior::{NullBehavior, UnitBehavior, UnitBehaviorResult};
 
 pub use crate::biology::genetic_manifest::{GeneticManifest, GeneticManifestData};
@@ -32,7 +32,7 @@ pub use crate::simulation::unit_entry::{
     UnitManifest,
 };
 pub use crate::util::text_grid::{CellTextAlignment, TextGridOptions};
-pub use crate::util::{Coord, CoordOffset, GridDirection, GridSize2D};
+pub use crate::util::{Coord, CoordOffset, GridDirection, GridNeighborhood, GridSize2D};
 pub use std::sync::Arc;
 
 pub use crate::simulation::executors::simple::SimpleSimulationExecutor;
@@ -8,7 +8,11 @@ use crate::util::GridSize2D;
 // use std::rc::Rc;
 
 pub mod builder {
+    use crate::simulation::in_sim_evolution::InSimEvolution;
+    use crate::simulation::unit_entry::UnitManifestObserver;
     use crate::{simulation::common::helpers::place_units::PlaceUnitsMethod, util::GridSize2D};
+    use std::cell::RefCell;
+    use std::rc::Rc;
 
     use super::*;
     #[derive(Builder)]
@@ -22,6 +26,9 @@ pub mod builder {
         // pub chemistry_configuration: ChemistryConfiguration,
         pub place_units_method: PlaceUnitsMethod,
         pub chemistry: ChemistryInstance,
+        pub in_sim_evolution: InSimEvolution,
+        pub auto_retire_unit_entries: bool,
+        pub unit_manifest_observers: Vec<Rc<RefCell<dyn UnitManifestObserver>>>,
     }
 
     impl SimulationBuilder {
@@ -74,6 +81,9 @@ pub mod builder {
                 unit_manifest.unwrap(),
                 self.place_units_method.unwrap_or_default(),
             );
+            sim.in_sim_evolution = self.in_sim_evolution;
+            sim.auto_retire_unit_entries = self.auto_retire_unit_entries.unwrap_or(false);
+            sim.unit_manifest_observers = self.unit_manifest_observers.unwrap_or_default();
 
             sim
         }
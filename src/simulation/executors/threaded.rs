@@ -6,10 +6,13 @@ use std::time::{Duration, Instant};
 
 use crate::chemistry::variants::CheeseChemistry;
 use crate::simulation::common::SimulationControlEventReceiver;
-use crate::simulation::simulation_data::{SimulationData, ThreadedSimulationReference};
+use crate::simulation::fitness::FitnessScore;
+use crate::simulation::simulation_data::{
+    ExecutorStatus, SimulationData, ThreadedSimulationReference,
+};
 use crate::simulation::Simulation;
 use crate::simulation::*;
-use crate::util::RateCounter;
+use crate::util::{Coord, RateCounter};
 
 pub struct ThreadedSimulationExecutor {
     pub is_paused: bool,
@@ -22,8 +25,17 @@ pub struct ThreadedSimulationExecutor {
     pub last_view_update: Instant,
     pub last_tick: Instant,
     pub double: ThreadedSimulationReference,
+    pub inspected_coord: Option<Coord>,
+    pub tick_counter: RateCounter,
+
+    // the entry fitness only changes when the simulation ticks, so it's kept along with the
+    // tick it was calculated at
+    pub fitness: Option<(u64, Vec<(String, FitnessScore)>)>,
 }
 
+pub const MIN_TICKS_PER_SECOND: u32 = 1;
+pub const MAX_TICKS_PER_SECOND: u32 = 10_000;
+
 impl ThreadedSimulationExecutor {
     pub fn new(
         mut simulation: Simulation,
@@ -43,6 +55,9 @@ impl ThreadedSimulationExecutor {
             last_tick: Instant::now(),
             last_view_update: Instant::now(),
             double,
+            inspected_coord: None,
+            tick_counter: RateCounter::new(),
+            fitness: None,
         }
     }
     pub fn start(&mut self) {
@@ -50,17 +65,92 @@ impl ThreadedSimulationExecutor {
         self.run();
     }
     pub fn handle_control_events(&mut self) {
-        for event in self.control_events_receiver.try_iter() {
+        let events = self.control_events_receiver.try_iter().collect::<Vec<_>>();
+        for event in events {
             println!("Received {:?}", event);
             match &event {
-                SimulationControlEvent::Resume => {
+                SimulationControlEvent::Resume | SimulationControlEvent::Start => {
                     self.is_paused = false;
                 }
-                _ => {}
+                SimulationControlEvent::Pause => {
+                    self.is_paused = true;
+                    self.update_view();
+                }
+                SimulationControlEvent::Step => {
+                    self.is_paused = true;
+                    if !self.is_finished {
+                        self.tick();
+                    }
+                    self.update_view();
+                }
+                SimulationControlEvent::SpeedUp => {
+                    self.max_ticks_per_second =
+                        (self.max_ticks_per_second * 2).min(MAX_TICKS_PER_SECOND);
+                    self.update_view();
+                }
+                SimulationControlEvent::SlowDown => {
+                    self.max_ticks_per_second =
+                        (self.max_ticks_per_second / 2).max(MIN_TICKS_PER_SECOND);
+                    self.update_view();
+                }
+                SimulationControlEvent::Halt => {
+                    self.is_finished = true;
+                    self.is_paused = false;
+                    self.update_view();
+                }
+                SimulationControlEvent::Inspect(coord) => {
+                    self.inspected_coord = Some(*coord);
+                    self.update_view();
+                }
             }
         }
     }
 
+    pub fn tick(&mut self) {
+        self.simulation.tick();
+        self.tick_counter.increment();
+        self.last_tick = Instant::now();
+
+        if self.simulation.world.tick >= self.simulation.iterations {
+            self.is_finished = true;
+        }
+    }
+
+    pub fn update_view(&mut self) {
+        let mut data = self.simulation.to_data();
+        data.inspection = self
+            .inspected_coord
+            .map(|coord| self.simulation.inspect_cell(&coord));
+
+        let ticks_per_second = if self.is_paused || self.is_finished {
+            0.0
+        } else {
+            self.tick_counter.per_second()
+        };
+        let population = self.simulation.world.count_units();
+        let tick = self.simulation.world.tick;
+        if self
+            .fitness
+            .as_ref()
+            .map_or(true, |(at_tick, _)| *at_tick != tick)
+        {
+            self.fitness = Some((tick, self.simulation.entry_fitness()));
+        }
+
+        data.executor_status = ExecutorStatus {
+            is_paused: self.is_paused,
+            is_finished: self.is_finished,
+            max_ticks_per_second: self.max_ticks_per_second,
+            ticks_per_second,
+            unit_updates_per_second: ticks_per_second * population as f64,
+            population,
+            fitness: self.fitness.as_ref().unwrap().1.clone(),
+        };
+
+        let locked = self.double.lock().unwrap();
+        locked.replace(Some(data));
+    }
+
     pub fn run_loop(&mut self) {
         let mut should_break = false;
 
@@ -71,13 +161,14 @@ impl ThreadedSimulationExecutor {
 
             let mut has_initialized = false;
 
-            let mut target_tick_delay =
-                Duration::new(0, (10u32).pow(9) / self.max_ticks_per_second);
-            let mut target_view_delay =
-                Duration::new(0, (10u32).pow(9) / self.max_view_updates_per_second);
-
             // TODO: this should be rewritten
             loop {
+                // the tick rate can change between iterations
+                let target_tick_delay =
+                    Duration::new(0, (10u32).pow(9) / self.max_ticks_per_second);
+                let target_view_delay =
+                    Duration::new(0, (10u32).pow(9) / self.max_view_updates_per_second);
+
                 let observed_delay = Instant::now().duration_since(self.last_tick);
                 let should_tick = observed_delay > target_tick_delay;
                 let mut divisor = if self.max_ticks_per_second < 5 { 2 } else { 3 };
@@ -94,8 +185,7 @@ impl ThreadedSimulationExecutor {
 
                 if should_tick {
                     counter.increment();
-                    self.simulation.tick();
-                    self.last_tick = Instant::now();
+                    self.tick();
                     has_initialized = true;
 
                     // // aoeu - might need to rig to update less often than the ticks at some point.  otherwise
@@ -106,8 +196,7 @@ impl ThreadedSimulationExecutor {
                 }
 
                 if should_update_view || should_tick {
-                    let locked = self.double.lock().unwrap();
-                    locked.replace(Some(self.simulation.to_data()));
+                    self.update_view();
                     self.last_view_update = Instant::now();
                 }
 
@@ -115,15 +204,12 @@ impl ThreadedSimulationExecutor {
                 //     "iterations: {}, {}",
                 //     self.simulation.iterations, self.simulation.world.tick
                 // );
-                should_break = self.simulation.world.tick >= self.simulation.iterations
-                    || self.is_finished
-                    || self.is_paused;
+                self.handle_control_events();
+
+                should_break = self.is_finished || self.is_paused;
                 if should_break {
-                    self.is_finished = true;
                     break 'outer_loop;
                 }
-
-                self.handle_control_events();
             }
         }
     }
@@ -136,10 +222,7 @@ impl ThreadedSimulationExecutor {
     }
 
     pub fn run(&mut self) {
-        {
-            let locked = self.double.lock().unwrap();
-            locked.replace(Some(self.simulation.to_data()));
-        }
+        self.update_view();
 
         while !self.is_finished {
             self.wait_loop();
@@ -148,3 +231,78 @@ impl ThreadedSimulationExecutor {
         }
     }
 }
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::simulation::common::builder::ChemistryBuilder;
+    use crate::simulation::common::*;
+    use crate::simulation::simulation_data::new_threaded_simulation_reference;
+
+    fn executor() -> (ThreadedSimulationExecutor, SimulationControlEventSender) {
+        let chemistry_builder = ChemistryBuilder::with_key("cheese");
+        let sim = SimulationBuilder::default()
+            .chemistry(chemistry_builder.build())
+            .size((3, 3))
+            .iterations(3)
+            .unit_manifest(UnitManifest {
+                units: vec![UnitEntryBuilder::default()
+                    .species_name("main".to_string())
+                    .behavior(NullBehavior::construct())
+                    .build(&chemistry_builder.manifest())],
+            })
+            .to_simulation();
+
+        let (sender, receiver) = channel::<SimulationControlEvent>();
+        let executor = ThreadedSimulationExecutor::new(
+            sim,
+            new_threaded_simulation_reference(),
+            receiver,
+            1,
+            1,
+        );
+        (executor, sender)
+    }
+
+    #[test]
+    fn playback_controls() {
+        let (mut executor, sender) = executor();
+        let start_tick = executor.simulation.world.tick;
+
+        sender.send(SimulationControlEvent::Step).unwrap();
+        sender.send(SimulationControlEvent::SpeedUp).unwrap();
+        sender.send(SimulationControlEvent::SpeedUp).unwrap();
+        executor.handle_control_events();
+
+        assert_eq!(executor.simulation.world.tick, start_tick + 1);
+        assert!(executor.is_paused);
+        assert_eq!(executor.max_ticks_per_second, 4);
+
+        let status = executor
+            .double
+            .lock()
+            .unwrap()
+            .borrow()
+            .as_ref()
+            .unwrap()
+            .executor_status
+            .clone();
+        assert!(status.is_paused);
+        assert_eq!(status.max_ticks_per_second, 4);
+
+        sender.send(SimulationControlEvent::SlowDown).unwrap();
+        sender.send(SimulationControlEvent::Resume).unwrap();
+        executor.handle_control_events();
+        assert_eq!(executor.max_ticks_per_second, 2);
+        assert!(!executor.is_paused);
+
+        sender.send(SimulationControlEvent::Halt).unwrap();
+        executor.handle_control_events();
+        assert!(executor.is_finished);
+
+        // stepping a halted simulation does nothing
+        sender.send(SimulationControlEvent::Step).unwrap();
+        executor.handle_control_events();
+        assert_eq!(executor.simulation.world.tick, start_tick + 1);
+    }
+}
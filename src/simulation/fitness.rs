@@ -1,4 +1,6 @@
+use crate::chemistry::ChemistryManifest;
 use crate::simulation::common::*;
+use crate::simulation::Simulation;
 use std::rc::Rc;
 
 pub type FitnessScore = u64;
@@ -33,6 +35,24 @@ pub fn default_fitness_calculators() -> Vec<FitnessCalculationDefinition> {
                 attr_val.coerce_unwrap_to_integer() as FitnessScore
             }),
         },
+        FitnessCalculationDefinition {
+            key: "prey_eaten".to_string(),
+            execute: Rc::new(|unit_entry_id: usize, sim: &SimCell| -> FitnessScore {
+                let manifest = sim.chemistry.get_manifest();
+                let attr_id = manifest.unit_entry_attribute_by_key("prey_eaten").id;
+                let attr_val = &sim.unit_entry_attributes[unit_entry_id][attr_id];
+                attr_val.coerce_unwrap_to_integer() as FitnessScore
+            }),
+        },
+        FitnessCalculationDefinition {
+            key: "unit_ticks_alive".to_string(),
+            execute: Rc::new(|unit_entry_id: usize, sim: &SimCell| -> FitnessScore {
+                let manifest = sim.chemistry.get_manifest();
+                let attr_id = manifest.unit_entry_attribute_by_key("unit_ticks_alive").id;
+                let attr_val = &sim.unit_entry_attributes[unit_entry_id][attr_id];
+                attr_val.coerce_unwrap_to_integer() as FitnessScore
+            }),
+        },
     ]
 }
 
@@ -50,6 +70,49 @@ pub fn calculate_fitness(
     (fitnessDef.execute)(unit_entry_id, sim)
 }
 
+/**
+ * The fitness calculators that make sense for a chemistry, ie. those that read one of its unit
+ * entry attributes.
+ */
+pub fn fitness_calculators_for(manifest: &ChemistryManifest) -> Vec<FitnessCalculationDefinition> {
+    default_fitness_calculators()
+        .into_iter()
+        .filter(|calculator| {
+            manifest
+                .unit_entry_attributes
+                .iter()
+                .any(|def| def.key == calculator.key)
+        })
+        .collect()
+}
+
+impl Simulation {
+    /**
+     * The current fitness of each unit entry, keyed by species name, using the first fitness
+     * calculator that applies to the chemistry.  Empty if none does.
+     */
+    pub fn entry_fitness(&mut self) -> Vec<(String, FitnessScore)> {
+        let calculator = match fitness_calculators_for(self.chemistry.get_manifest()).first() {
+            Some(calculator) => calculator.clone(),
+            None => return vec![],
+        };
+
+        let species_names = self
+            .unit_manifest
+            .units
+            .iter()
+            .map(|entry| entry.info.species_name.clone())
+            .collect::<Vec<_>>();
+
+        let sim = self.editable();
+        species_names
+            .into_iter()
+            .enumerate()
+            .map(|(entry_id, species_name)| (species_name, (calculator.execute)(entry_id, &sim)))
+            .collect()
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use crate::{runners::SimulationRunnerArgs, scenarios::simulations::get_simulation_scenario};
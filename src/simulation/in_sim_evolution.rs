@@ -0,0 +1,259 @@
+use crate::biology::experiments::alterations::CompiledAlterationSet;
+use crate::simulation::common::*;
+use crate::simulation::world::UnitBirth;
+use crate::simulation::Simulation;
+
+use rand::Rng;
+
+/**
+ * Lets genomes change while a simulation runs, rather than only between simulations.  A unit
+ * copied from a single parent gets a mutated copy of the parent's genome, a unit bred through
+ * the `mate` action gets a crossover of both parents.  Each new genome becomes a new entry of
+ * the unit manifest.
+ *
+ * Only entries whose behavior exposes a genome are altered, other units keep their parent's
+ * entry.
+ */
+#[derive(Clone)]
+pub struct InSimEvolution {
+    // alterations taking one genome, for units with a single parent
+    pub mutations: CompiledAlterationSet,
+
+    // alterations taking two genomes, for units bred from a mate
+    pub crossovers: CompiledAlterationSet,
+
+    // chance that a unit with a single parent is mutated at all, otherwise it keeps the
+    // parent's entry
+    pub mutation_odds: f64,
+}
+
+impl InSimEvolution {
+    pub fn new(
+        mutations: CompiledAlterationSet,
+        crossovers: CompiledAlterationSet,
+        mutation_odds: f64,
+    ) -> Self {
+        for (set, genomes_required) in [(&mutations, 1), (&crossovers, 2)] {
+            if let Some(alteration) = set
+                .alterations
+                .iter()
+                .find(|a| a.genomes_required != genomes_required)
+            {
+                panic!(
+                    "Alteration {} takes {} genomes, expected {}",
+                    alteration.key, alteration.genomes_required, genomes_required
+                );
+            }
+        }
+
+        Self {
+            mutations,
+            crossovers,
+            mutation_odds,
+        }
+    }
+
+    pub fn with_mutation_odds(mutation_odds: f64) -> Self {
+        Self::new(
+            CompiledAlterationSet::from_keys(
+                &vec![
+                    "point_mutation",
+                    "insertion",
+                    "deletion",
+                    "random_region_insert",
+                ]
+                .iter()
+                .map(|key| key.to_string())
+                .collect(),
+            ),
+            CompiledAlterationSet::from_keys(&vec!["crossover".to_string()]),
+            mutation_odds,
+        )
+    }
+
+    /**
+     * The entry for a newborn unit, or None if it should keep its first parent's entry.
+     */
+    pub fn offspring_entry(
+        &self,
+        birth: &UnitBirth,
+        unit_manifest: &UnitManifest,
+    ) -> Option<UnitEntry> {
+        let parents = birth
+            .parent_entry_ids
+            .iter()
+            .map(|entry_id| &unit_manifest.units[*entry_id])
+            .collect::<Vec<_>>();
+
+        let alterations = match parents.len() {
+            1 if rand::thread_rng().gen_bool(self.mutation_odds) => &self.mutations,
+            2 => &self.crossovers,
+            _ => return None,
+        };
+
+        let mut genomes = vec![];
+        for parent in parents.iter() {
+            genomes.push(parent.behavior.genome()?);
+        }
+        let genomes = genomes
+            .iter()
+            .map(|genome| genome.as_ref())
+            .collect::<Vec<_>>();
+
+        let alteration = alterations.choose_random();
+        let params = (alteration.prepare)(genomes.as_slice());
+        let raw_genome = (alteration.execute)(genomes.as_slice(), params.as_slice());
+        if raw_genome.len() == 0 {
+            return None;
+        }
+
+        let behavior = parents[0].behavior.with_genome(raw_genome)?;
+        let mut info = parents[0].info.clone();
+        info.parent_entry_ids = birth.parent_entry_ids.clone();
+
+        Some(UnitEntry { info, behavior })
+    }
+}
+
+impl Simulation {
+    /**
+     * Gives the units born during the tick their own entries when evolving in-sim.  A unit that
+     * died before the end of the tick is skipped.
+     */
+    pub fn resolve_births(&mut self) {
+        let births = std::mem::take(&mut self.world.births);
+        let evolution = match &self.in_sim_evolution {
+            Some(evolution) => evolution.clone(),
+            None => return,
+        };
+
+        for birth in births.iter() {
+            let coord = match self.world.get_unit_at(&birth.coord) {
+                Some(unit) if unit.id == birth.unit_id => birth.coord,
+                _ => match self.world.find_unit_coord(birth.unit_id) {
+                    Some(coord) => coord,
+                    None => continue,
+                },
+            };
+
+            if let Some(entry) = evolution.offspring_entry(birth, &self.unit_manifest) {
+                let entry_id = self.register_unit_entry(entry);
+                self.world.set_unit_entry_id(&coord, entry_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::biology::experiments::util::random_genome_of_length;
+    use crate::biology::genome::framed::builders::FramedGenomeCompiler;
+    use crate::biology::unit_behavior::framed::FramedGenomeUnitBehavior;
+    use crate::chemistry::actions::default_actions;
+    use crate::chemistry::actions::tests::execute_action;
+    use crate::simulation::common::builder::ChemistryBuilder;
+    use crate::simulation::common::helpers::place_units::PlaceUnitsMethod;
+
+    fn evolving_sim(mutation_odds: f64) -> Simulation {
+        let chemistry_builder = ChemistryBuilder::with_key("cheese");
+        let chemistry = chemistry_builder.build();
+        let gm = GeneticManifest::from_chemistry(&chemistry).wrap_arc();
+
+        let entries = ["a", "b"]
+            .iter()
+            .map(|name| {
+                let genome =
+                    FramedGenomeCompiler::compile(random_genome_of_length(40), &gm).wrap_arc();
+                UnitEntryBuilder::default()
+                    .species_name(name.to_string())
+                    .behavior(FramedGenomeUnitBehavior::new(genome, gm.clone()).construct())
+                    .build(&chemistry_builder.manifest())
+            })
+            .collect::<Vec<_>>();
+
+        SimulationBuilder::default()
+            .chemistry(chemistry)
+            .size((5, 5))
+            .iterations(10)
+            .unit_manifest(UnitManifest { units: entries })
+            .place_units_method(PlaceUnitsMethod::Skip)
+            .in_sim_evolution(InSimEvolution::with_mutation_odds(mutation_odds))
+            .to_simulation()
+    }
+
+    fn execute(sim: &mut Simulation, key: &str, coord: &Coord, params: &[ActionParam]) -> bool {
+        let actions = default_actions();
+        let action = actions.iter().find(|a| a.key == key).unwrap();
+        execute_action(action, coord, sim, params)
+    }
+
+    #[test]
+    fn mutated_offspring() {
+        let mut sim = evolving_sim(1.0);
+        let entry = sim.unit_manifest.units[0].info.clone();
+        sim.world
+            .seed_unit_at(&(2, 2), &entry, None, sim.chemistry.as_ref());
+
+        let params = [
+            ActionParam::Direction(GridDirection::Up),
+            ActionParam::Nil,
+            ActionParam::Nil,
+        ];
+        assert!(execute(&mut sim, "new_unit", &(2, 2), &params));
+        assert_eq!(sim.world.births.len(), 1);
+        sim.resolve_births();
+
+        assert!(sim.world.births.is_empty());
+        assert_eq!(sim.unit_manifest.units.len(), 3);
+        assert_eq!(sim.unit_entry_attributes.len(), 3);
+        assert_eq!(sim.world.get_unit_at(&(2, 3)).unwrap().entry_id, 2);
+        assert_eq!(sim.unit_manifest.units[2].info.parent_entry_ids, vec![0]);
+        assert_eq!(sim.world.get_unit_at(&(2, 2)).unwrap().entry_id, 0);
+    }
+
+    #[test]
+    fn mating() {
+        let mut sim = evolving_sim(0.0);
+        let (a, b) = (
+            sim.unit_manifest.units[0].info.clone(),
+            sim.unit_manifest.units[1].info.clone(),
+        );
+        sim.world
+            .seed_unit_at(&(2, 2), &a, None, sim.chemistry.as_ref());
+        sim.world
+            .seed_unit_at(&(2, 3), &b, None, sim.chemistry.as_ref());
+
+        // a single parent is never mutated at zero odds
+        let params = [
+            ActionParam::Direction(GridDirection::Left),
+            ActionParam::Nil,
+            ActionParam::Nil,
+        ];
+        assert!(execute(&mut sim, "new_unit", &(2, 2), &params));
+
+        // no mate in the given direction
+        let params = [
+            ActionParam::Direction(GridDirection::Down),
+            ActionParam::Direction(GridDirection::Right),
+            ActionParam::Nil,
+        ];
+        assert!(!execute(&mut sim, "mate", &(2, 2), &params));
+
+        let params = [
+            ActionParam::Direction(GridDirection::Up),
+            ActionParam::Direction(GridDirection::Right),
+            ActionParam::Nil,
+        ];
+        assert!(execute(&mut sim, "mate", &(2, 2), &params));
+        sim.resolve_births();
+
+        assert_eq!(sim.world.get_unit_at(&(1, 2)).unwrap().entry_id, 0);
+        let child = sim.world.get_unit_at(&(3, 2)).unwrap().entry_id;
+        assert_eq!(child, 2);
+        assert_eq!(
+            sim.unit_manifest.units[child].info.parent_entry_ids,
+            vec![0, 1]
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use crate::biology::unit_behavior::UnitBehaviorInspection;
+use crate::simulation::Simulation;
+use crate::util::Coord;
+
+/**
+ * Everything the ui inspector shows about a single cell.  Built on the simulation thread,
+ * since unit behaviors can't leave it, and handed to the ui with the rest of SimulationData.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellInspection {
+    pub coord: Coord,
+    pub tick: u64,
+    pub position_attributes: Vec<(String, String)>,
+    pub position_resources: Vec<(String, String)>,
+    pub unit: Option<UnitInspection>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnitInspection {
+    pub entry_id: usize,
+    pub species_name: String,
+    pub attributes: Vec<(String, String)>,
+    pub resources: Vec<(String, String)>,
+    pub behavior: Option<UnitBehaviorInspection>,
+}
+
+impl CellInspection {
+    pub fn to_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("cell {:?} @ tick {}", self.coord, self.tick)];
+
+        let mut push_section =
+            |lines: &mut Vec<String>, title: &str, values: &[(String, String)]| {
+                lines.push(format!("{}:", title));
+                for (key, value) in values.iter() {
+                    lines.push(format!("  {}: {}", key, value));
+                }
+            };
+
+        push_section(&mut lines, "position attributes", &self.position_attributes);
+        push_section(&mut lines, "position resources", &self.position_resources);
+
+        match &self.unit {
+            Some(unit) => {
+                lines.push(format!(
+                    "unit: {} (entry {})",
+                    unit.species_name, unit.entry_id
+                ));
+                push_section(&mut lines, "unit attributes", &unit.attributes);
+                push_section(&mut lines, "unit resources", &unit.resources);
+
+                if let Some(behavior) = &unit.behavior {
+                    lines.push("sensors:".to_string());
+                    for (key, value) in behavior.sensor_values.iter() {
+                        lines.push(format!("  {}: {}", key, value));
+                    }
+
+                    lines.push("genome:".to_string());
+                    lines.extend(behavior.genome.lines().map(|line| line.to_string()));
+                }
+            }
+            None => {
+                lines.push("no unit".to_string());
+            }
+        }
+
+        lines
+    }
+}
+
+impl Simulation {
+    pub fn inspect_cell(&self, coord: &Coord) -> CellInspection {
+        let manifest = self.chemistry.get_manifest();
+        let tick = self.world.tick;
+        let position = self
+            .world
+            .get_position_at(coord)
+            .expect(&format!("no position at {:?}", coord));
+
+        let position_attributes = manifest
+            .position_attributes
+            .iter()
+            .map(|def| {
+                (
+                    def.key.clone(),
+                    format!("{:?}", position.get_attribute(def.id)),
+                )
+            })
+            .collect();
+
+        let position_resources = manifest
+            .position_resources
+            .iter()
+            .map(|def| {
+                (
+                    def.key.clone(),
+                    position.get_resource(def.id, tick).to_string(),
+                )
+            })
+            .collect();
+
+        let unit = position.unit.as_ref().map(|unit| {
+            let entry = &self.unit_manifest.units[unit.entry_id];
+
+            UnitInspection {
+                entry_id: unit.entry_id,
+                species_name: entry.info.species_name.clone(),
+                attributes: manifest
+                    .unit_attributes
+                    .iter()
+                    .map(|def| (def.key.clone(), format!("{:?}", unit.get_attribute(def.id))))
+                    .collect(),
+                resources: manifest
+                    .unit_resources
+                    .iter()
+                    .map(|def| (def.key.clone(), unit.get_resource(def.id).to_string()))
+                    .collect(),
+                behavior: entry.behavior.inspect(coord, &self.attributes, &self.world),
+            }
+        });
+
+        CellInspection {
+            coord: *coord,
+            tick,
+            position_attributes,
+            position_resources,
+            unit,
+        }
+    }
+}
@@ -1,10 +1,11 @@
 use crate::simulation::common::*;
-use crate::util::{coord_by_coord_offset, Coord, CoordOffset, GridDirection, GridSize2D};
+use crate::util::{coord_by_coord_offset, Coord, GridDirection, GridNeighborhood, GridSize2D};
 use ndarray::*;
 use ndarray::{Array, Array2, Dim, Ix, Shape};
 
 pub struct CoordOffsetIterator {
-    iter: OffsetIterator,
+    neighborhood: GridNeighborhood,
+    i: usize,
     coord: Coord,
     grid_size: GridSize2D,
 }
@@ -14,14 +15,12 @@ impl Iterator for CoordOffsetIterator {
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            let next = self.iter.next();
-            if next.is_none() {
-                return None;
-            }
-
-            let (coord_offset, dir) = next.unwrap();
+            let dir = self.neighborhood.directions().get(self.i)?.clone();
+            self.i += 1;
 
-            let result = coord_by_coord_offset(&self.coord, coord_offset, self.grid_size);
+            let result =
+                self.neighborhood
+                    .coord_by_direction_offset(&self.coord, &dir, self.grid_size);
             if result.is_some() {
                 return Some((result.unwrap(), dir));
             }
@@ -31,57 +30,23 @@ impl Iterator for CoordOffsetIterator {
 
 impl CoordOffsetIterator {
     pub fn new(coord: &Coord, grid_size: &GridSize2D) -> Self {
+        Self::with_neighborhood(coord, grid_size, &GridNeighborhood::VonNeumann)
+    }
+
+    pub fn with_neighborhood(
+        coord: &Coord,
+        grid_size: &GridSize2D,
+        neighborhood: &GridNeighborhood,
+    ) -> Self {
         Self {
             coord: coord.clone(),
-            iter: OffsetIterator::new(),
+            neighborhood: neighborhood.clone(),
+            i: 0,
             grid_size: grid_size.clone(),
         }
     }
 }
 
-pub struct OffsetIterator {
-    direction: Option<GridDirection>,
-}
-
-impl OffsetIterator {
-    pub fn new() -> Self {
-        OffsetIterator {
-            direction: Some(GridDirection::Up),
-        }
-    }
-}
-
-impl Iterator for OffsetIterator {
-    type Item = (CoordOffset, GridDirection);
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let current_dir = self.direction.clone();
-        match self.direction {
-            Some(GridDirection::Up) => {
-                self.direction = Some(GridDirection::Right);
-                Some(((0, 1), GridDirection::Up))
-            }
-
-            Some(GridDirection::Right) => {
-                self.direction = Some(GridDirection::Down);
-                Some(((1, 0), GridDirection::Right))
-            }
-
-            Some(GridDirection::Down) => {
-                self.direction = Some(GridDirection::Left);
-                Some(((0, -1), GridDirection::Down))
-            }
-
-            Some(GridDirection::Left) => {
-                self.direction = None;
-                Some(((-1, 0), GridDirection::Left))
-            }
-
-            None => None,
-        }
-    }
-}
-
 pub struct CoordIterator {
     x: usize,
     y: usize,
@@ -225,5 +190,21 @@ mod test {
             let mut _iter = CoordOffsetIterator::new(&(2, 2), &(5, 5));
             assert_eq!(_iter.next().unwrap(), ((2, 3), GridDirection::Up));
         }
+
+        #[test]
+        fn hex_neighborhood() {
+            use crate::util::GridNeighborhood;
+            let coords =
+                CoordOffsetIterator::with_neighborhood(&(0, 0), &(5, 5), &GridNeighborhood::Hex)
+                    .collect::<Vec<_>>();
+            assert_eq!(
+                coords,
+                vec![
+                    ((1, 1), GridDirection::UpRight),
+                    ((1, 0), GridDirection::Right),
+                    ((0, 1), GridDirection::UpLeft),
+                ]
+            );
+        }
     }
 }
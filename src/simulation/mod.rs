@@ -3,6 +3,8 @@ pub mod config;
 pub mod coord_set;
 pub mod executors;
 pub mod fitness;
+pub mod in_sim_evolution;
+pub mod inspection;
 pub mod iterators;
 pub mod position;
 pub mod simulation_data;
@@ -18,12 +20,14 @@ use ndarray::{Array, Array2, Dim, Ix, Shape};
 use self::common::helpers::place_units::{self, PlaceUnitsMethod};
 use self::config::SimulationConfigData;
 use self::config::*;
+use self::in_sim_evolution::InSimEvolution;
 use self::iterators::CoordIterator;
 use self::position::*;
-use self::simulation_data::{SimulationData, ThreadedSimulationReference};
+use self::simulation_data::{ExecutorStatus, SimulationData, ThreadedSimulationReference};
 use self::unit::*;
 use self::unit_entry::{UnitEntry, UnitEntryData, UnitManifest};
 use self::unit_entry::{UnitEntryAttributes, UnitEntryId};
+use self::unit_entry::{UnitManifestEvent, UnitManifestObserver};
 use self::world::*;
 use crate::chemistry::properties::UnitEntryAttributeDefinition;
 use crate::chemistry::properties::{AttributeIndex, AttributeValue, ResourceAmount, ResourceIndex};
@@ -58,6 +62,13 @@ pub struct Simulation {
 
     pub place_units_method: PlaceUnitsMethod,
 
+    // when set, units born during a tick get altered genomes of their own
+    pub in_sim_evolution: Option<InSimEvolution>,
+
+    // retire the unit entries without living units at the end of every tick
+    pub auto_retire_unit_entries: bool,
+    pub unit_manifest_observers: Vec<Rc<RefCell<dyn UnitManifestObserver>>>,
+
     _early_terminate: bool,
     // pub control_events: Option<SimulationControlEventReceiver>,
 }
@@ -79,12 +90,22 @@ pub struct Simulation {
 //     PositionUpdated(Coord),
 //     Nil,
 // }
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum SimulationControlEvent {
     Pause,
     Resume,
     Start,
     Halt,
+
+    // run a single tick, pausing if needed
+    Step,
+
+    // double or halve the maximum tick rate
+    SpeedUp,
+    SlowDown,
+
+    // show the given cell in the inspector
+    Inspect(Coord),
 }
 
 // pub type SimulationEventSender = Sender<SimulationEvent>;
@@ -117,13 +138,7 @@ impl Simulation {
         let unit_entry_attributes = unit_manifest
             .units
             .iter()
-            .map(|entry| {
-                if let Some(attr) = &entry.info.default_entry_attributes {
-                    attr.clone()
-                } else {
-                    chemistry.get_default_unit_entry_attributes()
-                }
-            })
+            .map(|entry| initial_unit_entry_attributes(&entry.info, &chemistry))
             .collect::<Vec<_>>();
 
         let mut simulation = Simulation {
@@ -134,6 +149,9 @@ impl Simulation {
             attributes,
             unit_entry_attributes,
             place_units_method,
+            in_sim_evolution: None,
+            auto_retire_unit_entries: false,
+            unit_manifest_observers: vec![],
             _early_terminate: false,
         };
 
@@ -263,6 +281,11 @@ impl Simulation {
         });
         perf_timer_stop!("sim_tick");
 
+        self.resolve_births();
+        if self.auto_retire_unit_entries {
+            self.retire_empty_unit_entries();
+        }
+
         // if is_finished {
         //     self._early_terminate = true;
         // }
@@ -325,6 +348,8 @@ impl Simulation {
             grid: self.world.grid.clone(),
             config: self.to_config_data(),
             tick: self.world.tick,
+            inspection: None,
+            executor_status: ExecutorStatus::default(),
         }
     }
 
@@ -343,6 +368,95 @@ impl Simulation {
         }
     }
 
+    /**
+     * Adds an entry to the unit manifest mid-run, along with its entry attributes.
+     */
+    pub fn register_unit_entry(&mut self, entry: UnitEntry) -> UnitEntryId {
+        self.unit_entry_attributes
+            .push(initial_unit_entry_attributes(&entry.info, &self.chemistry));
+        let entry_id = self.unit_manifest.register_entry(entry);
+
+        let info = self.unit_manifest.units[entry_id].info.clone();
+        self.notify_unit_manifest_observers(&UnitManifestEvent::Registered(info));
+        entry_id
+    }
+
+    /**
+     * Removes the unit entries that have no living units, along with their entry attributes.
+     * The remaining entries are renumbered and the units in the world follow.  Returns the old
+     * ids of the retired entries.
+     */
+    pub fn retire_empty_unit_entries(&mut self) -> Vec<UnitEntryId> {
+        let mut unit_counts = vec![0; self.unit_manifest.units.len()];
+        for coord in CoordIterator::new(self.world.size) {
+            if let Some(unit) = self.world.get_unit_at(&coord) {
+                unit_counts[unit.entry_id] += 1;
+            }
+        }
+
+        let retired = unit_counts
+            .iter()
+            .map(|count| *count == 0)
+            .collect::<Vec<_>>();
+        let retired_ids = (0..retired.len())
+            .filter(|i| retired[*i])
+            .collect::<Vec<_>>();
+        if retired_ids.is_empty() {
+            return retired_ids;
+        }
+
+        for entry_id in retired_ids.iter() {
+            let event = UnitManifestEvent::Retired(
+                self.unit_manifest.units[*entry_id].info.clone(),
+                self.unit_entry_attributes[*entry_id].clone(),
+            );
+            self.notify_unit_manifest_observers(&event);
+        }
+
+        let new_ids = self.unit_manifest.retire_entries(&retired);
+        let entry_attributes = std::mem::take(&mut self.unit_entry_attributes);
+        self.unit_entry_attributes = entry_attributes
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !retired[*i])
+            .map(|(_, attributes)| attributes)
+            .collect();
+
+        for coord in CoordIterator::new(self.world.size) {
+            if let Some(unit) = self.world.get_unit_at(&coord) {
+                let entry_id = new_ids[unit.entry_id].unwrap();
+                self.world.set_unit_entry_id(&coord, entry_id);
+            }
+        }
+        for birth in self.world.births.iter_mut() {
+            birth.parent_entry_ids = birth
+                .parent_entry_ids
+                .iter()
+                .filter_map(|parent_id| new_ids[*parent_id])
+                .collect();
+        }
+
+        let renumbered = new_ids
+            .iter()
+            .enumerate()
+            .filter_map(|(old_id, new_id)| match new_id {
+                Some(new_id) if *new_id != old_id => Some((old_id, *new_id)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if !renumbered.is_empty() {
+            self.notify_unit_manifest_observers(&UnitManifestEvent::Renumbered(renumbered));
+        }
+
+        retired_ids
+    }
+
+    fn notify_unit_manifest_observers(&self, event: &UnitManifestEvent) {
+        for observer in self.unit_manifest_observers.iter() {
+            observer.borrow_mut().on_unit_manifest_event(event);
+        }
+    }
+
     pub fn unit_attr_id_by_key(&self, key: &'static str) -> UnitAttributeIndex {
         self.chemistry.get_manifest().unit_attribute_by_key(key).id
     }
@@ -364,6 +478,17 @@ impl Simulation {
     }
 }
 
+fn initial_unit_entry_attributes(
+    entry: &UnitEntryData,
+    chemistry: &ChemistryInstance,
+) -> UnitEntryAttributes {
+    if let Some(attr) = &entry.default_entry_attributes {
+        attr.clone()
+    } else {
+        chemistry.get_default_unit_entry_attributes()
+    }
+}
+
 fn add_times(times: &Vec<u128>) -> u128 {
     let mut total: u128 = 0;
     for time in times.iter() {
@@ -449,6 +574,93 @@ mod tests {
             }
         }
     }
+
+    struct EventLog {
+        events: Vec<UnitManifestEvent>,
+    }
+
+    impl UnitManifestObserver for EventLog {
+        fn on_unit_manifest_event(&mut self, event: &UnitManifestEvent) {
+            self.events.push(event.clone());
+        }
+    }
+
+    #[test]
+    fn registering_and_retiring_unit_entries() {
+        let log = Rc::new(RefCell::new(EventLog { events: vec![] }));
+        let mut sim = SimulationBuilder::default()
+            .chemistry(ChemistryBuilder::with_key("cheese").build())
+            .size((5, 5))
+            .place_units_method(PlaceUnitsMethod::ManualSingleEntry {
+                attributes: None,
+                coords: vec![(1, 1)],
+            })
+            .unit_manifest(UnitManifest {
+                units: vec![UnitEntry::new("main", NullBehavior::construct())],
+            })
+            .unit_manifest_observers(vec![log.clone()])
+            .to_simulation();
+
+        let a = sim.register_unit_entry(UnitEntry::new("a", NullBehavior::construct()));
+        let b = sim.register_unit_entry(UnitEntry::new("b", NullBehavior::construct()));
+        assert_eq!((a, b), (1, 2));
+        assert_eq!(sim.unit_manifest.units[b].info.species_id, 2);
+        assert_eq!(sim.unit_entry_attributes.len(), 3);
+
+        let info = sim.unit_manifest.units[b].info.clone();
+        sim.world
+            .seed_unit_at(&(3, 3), &info, None, sim.chemistry.as_ref());
+        sim.unit_manifest.units[b].info.parent_entry_ids = vec![a, 0];
+
+        assert_eq!(sim.retire_empty_unit_entries(), vec![a]);
+        assert_eq!(sim.unit_entry_attributes.len(), 2);
+        assert_eq!(sim.unit_manifest.units[1].info.species_name, "b");
+        assert_eq!(sim.unit_manifest.units[1].info.unit_entry_id, 1);
+        assert_eq!(sim.unit_manifest.units[1].info.parent_entry_ids, vec![0]);
+        assert_eq!(sim.world.get_unit_at(&(3, 3)).unwrap().entry_id, 1);
+        assert_eq!(sim.world.get_unit_at(&(1, 1)).unwrap().entry_id, 0);
+
+        let events = &log.borrow().events;
+        assert_eq!(events.len(), 4);
+        assert!(
+            matches!(&events[2], UnitManifestEvent::Retired(info, _) if info.species_name == "a")
+        );
+        assert!(matches!(&events[3], UnitManifestEvent::Renumbered(ids) if ids == &vec![(2, 1)]));
+        assert!(sim.retire_empty_unit_entries().is_empty());
+    }
+
+    #[test]
+    fn unit_species_ids() {
+        let mut sim = SimulationBuilder::default()
+            .chemistry(ChemistryBuilder::with_key("cheese").build())
+            .size((5, 5))
+            .place_units_method(PlaceUnitsMethod::Skip)
+            .unit_manifest(UnitManifest {
+                units: ["a", "b", "a"]
+                    .iter()
+                    .map(|name| UnitEntry::new(name, NullBehavior::construct()))
+                    .collect(),
+            })
+            .to_simulation();
+        let species_ids = |sim: &Simulation| {
+            sim.unit_manifest
+                .units
+                .iter()
+                .map(|entry| entry.info.species_id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(species_ids(&sim), vec![0, 1, 0]);
+
+        // ids are kept when entries are retired
+        let info = sim.unit_manifest.units[1].info.clone();
+        sim.world
+            .seed_unit_at(&(3, 3), &info, None, sim.chemistry.as_ref());
+        sim.retire_empty_unit_entries();
+        sim.register_unit_entry(UnitEntry::new("c", NullBehavior::construct()));
+        sim.register_unit_entry(UnitEntry::new("b", NullBehavior::construct()));
+        assert_eq!(species_ids(&sim), vec![1, 2, 1]);
+        assert_eq!(sim.world.get_unit_at(&(3, 3)).unwrap().species_id, 1);
+    }
 }
 
 // pub fn send_event(channel: &mut SimulationEventSender, event: SimulationEvent) {
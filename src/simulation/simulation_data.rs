@@ -1,4 +1,6 @@
 //use crate::simulation::config::*;
+use crate::simulation::fitness::FitnessScore;
+use crate::simulation::inspection::CellInspection;
 // use crate::simulation::position::*;
 // use crate::simulation::unit::*;
 use crate::simulation::config::*;
@@ -22,4 +24,22 @@ pub struct SimulationData {
     pub grid: Grid,
     pub config: SimulationConfigData,
     pub tick: u64,
+    pub inspection: Option<CellInspection>,
+    pub executor_status: ExecutorStatus,
+}
+
+/**
+ * Playback state of the executor running the simulation, shown in the ui's hud.
+ */
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExecutorStatus {
+    pub is_paused: bool,
+    pub is_finished: bool,
+    pub max_ticks_per_second: u32,
+    pub ticks_per_second: f64,
+    pub unit_updates_per_second: f64,
+    pub population: usize,
+
+    // the current fitness of each unit entry, if the chemistry has a fitness calculator
+    pub fitness: Vec<(String, FitnessScore)>,
 }
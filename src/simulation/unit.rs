@@ -1,4 +1,4 @@
-use super::unit_entry::UnitEntryId;
+use super::unit_entry::{UnitEntryId, UnitSpeciesId};
 use crate::chemistry::properties::{AttributeIndex, AttributeValue, ResourceAmount, ResourceIndex};
 use crate::chemistry::ChemistryInstance;
 use crate::chemistry::{Chemistry, ChemistryManifest};
@@ -20,6 +20,7 @@ pub struct Unit {
     pub resources: UnitResources,
     pub attributes: Vec<UnitAttributeValue>,
     pub entry_id: UnitEntryId,
+    pub species_id: UnitSpeciesId,
     pub id: UnitId,
     pub coord: Coord,
     pub last_update_tick: u64,
@@ -121,6 +122,7 @@ pub fn empty_unit() -> Unit {
         resources: vec![],
         attributes: vec![],
         entry_id: 0,
+        species_id: 0,
         id: 0,
         coord: (0, 0),
         last_update_tick: 0,
@@ -4,14 +4,15 @@ use crate::simulation::common::{
 };
 use crate::simulation::unit::util::convert_maybe_resources_to_resources;
 use std::boxed::Box;
-use std::cell::{Cell, RefCell};
-use std::rc::Rc;
 use std::sync::Arc;
 
 use crate::chemistry::properties::AttributeValue;
 
 pub type UnitEntryId = usize;
 
+// entries with the same species_name share one, including those bred during the simulation
+pub type UnitSpeciesId = usize;
+
 pub type UnitEntryAttributeValue = AttributeValue;
 pub type UnitEntryAttributeIndex = AttributeIndex;
 pub type UnitEntryAttributes = Vec<UnitEntryAttributeValue>;
@@ -27,26 +28,114 @@ pub struct UnitManifest {
 
 impl UnitManifest {
     pub fn from(entries: &Vec<UnitEntry>) -> Self {
-        let mut units: Vec<UnitEntry> = entries.clone();
+        let mut manifest = UnitManifest {
+            units: entries.clone(),
+        };
+        manifest.init_manifest();
+        manifest
+    }
 
-        for i in (0..entries.len()) {
-            units[i].info.unit_entry_id = i as usize;
+    pub fn init_manifest(&mut self) {
+        let units = std::mem::take(&mut self.units);
+        for entry in units {
+            self.register_entry(entry);
         }
+    }
 
-        UnitManifest { units }
+    /**
+     * Adds an entry while the simulation is running, returning its id.
+     */
+    pub fn register_entry(&mut self, mut entry: UnitEntry) -> UnitEntryId {
+        let id = self.units.len();
+        entry.info.unit_entry_id = id;
+        entry.info.species_id = self.species_id_for(&entry.info.species_name);
+        self.units.push(entry);
+        id
     }
 
-    pub fn init_manifest(&mut self) {
-        for i in (0..self.units.len()) {
-            self.units[i].info.unit_entry_id = i;
+    /**
+     * The id of the entries named species_name, or a new one if there are none.  Ids are kept
+     * when entries are retired, so a new id is one past the highest.
+     */
+    fn species_id_for(&self, species_name: &str) -> UnitSpeciesId {
+        match self
+            .units
+            .iter()
+            .find(|entry| entry.info.species_name == species_name)
+        {
+            Some(entry) => entry.info.species_id,
+            None => self
+                .units
+                .iter()
+                .map(|entry| entry.info.species_id + 1)
+                .max()
+                .unwrap_or(0),
         }
     }
+
+    /**
+     * Removes the flagged entries and renumbers the rest so that ids stay positions in the
+     * manifest.  Returns the new id of every old id, None for the removed ones.  Parents that
+     * were removed are dropped from the remaining entries' lineage.
+     */
+    pub fn retire_entries(&mut self, retired: &[bool]) -> Vec<Option<UnitEntryId>> {
+        let mut next_id = 0;
+        let new_ids = retired
+            .iter()
+            .map(|is_retired| {
+                if *is_retired {
+                    None
+                } else {
+                    next_id += 1;
+                    Some(next_id - 1)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let units = std::mem::take(&mut self.units);
+        for (i, mut entry) in units.into_iter().enumerate() {
+            if let Some(id) = new_ids[i] {
+                entry.info.unit_entry_id = id;
+                entry.info.parent_entry_ids = entry
+                    .info
+                    .parent_entry_ids
+                    .iter()
+                    .filter_map(|parent_id| new_ids[*parent_id])
+                    .collect();
+                self.units.push(entry);
+            }
+        }
+
+        new_ids
+    }
+}
+
+/**
+ * A change to the unit manifest of a running simulation.
+ */
+#[derive(Clone, Debug)]
+pub enum UnitManifestEvent {
+    Registered(UnitEntryData),
+
+    // the entry as it was before removal, along with its final entry attributes
+    Retired(UnitEntryData, UnitEntryAttributes),
+
+    // (old id, new id) of the entries that moved after others were retired
+    Renumbered(Vec<(UnitEntryId, UnitEntryId)>),
+}
+
+/**
+ * Gets told about entries being added to or retired from a simulation's unit manifest, eg. to
+ * keep a record of species that no longer exist.
+ */
+pub trait UnitManifestObserver {
+    fn on_unit_manifest_event(&mut self, event: &UnitManifestEvent);
 }
 
 #[derive(Clone)]
 pub struct UnitEntry {
     pub info: UnitEntryData,
-    pub behavior: Rc<RefCell<dyn UnitBehavior>>,
+    pub behavior: Arc<dyn UnitBehavior>,
     /*
         technically, the unit_behavior accesses the world via sensors, which might differ by unit_entry.
         so the sensor manifest should be included in each unit_entry.
@@ -55,7 +144,7 @@ pub struct UnitEntry {
 }
 
 impl UnitEntry {
-    pub fn new(species_name: &'static str, unit_behavior: Rc<RefCell<dyn UnitBehavior>>) -> Self {
+    pub fn new(species_name: &'static str, unit_behavior: Arc<dyn UnitBehavior>) -> Self {
         Self {
             info: UnitEntryData {
                 species_name: species_name.to_string(),
@@ -63,7 +152,9 @@ impl UnitEntry {
                 default_resources: None,
                 default_entry_attributes: None,
                 unit_entry_id: 0,
+                species_id: 0,
                 external_id: 0,
+                parent_entry_ids: vec![],
             },
 
             behavior: unit_behavior,
@@ -118,7 +209,11 @@ pub struct UnitEntryData {
     pub default_unit_attributes: Option<UnitAttributes>,
     pub default_resources: Option<UnitResources>,
     pub unit_entry_id: UnitEntryId,
+    pub species_id: UnitSpeciesId,
     pub external_id: usize,
+
+    // the entries this one was bred from during the simulation, empty for the initial entries
+    pub parent_entry_ids: Vec<UnitEntryId>,
 }
 
 impl UnitEntryData {
@@ -128,8 +223,10 @@ impl UnitEntryData {
             default_unit_attributes: None,
             default_resources: None,
             unit_entry_id: 0,
+            species_id: 0,
             default_entry_attributes: None,
             external_id: 0,
+            parent_entry_ids: vec![],
         }
     }
 }
@@ -141,7 +238,7 @@ pub mod builder {
     #[builder(pattern = "owned", setter(strip_option))]
     #[builder(build_fn(skip))]
     pub struct UnitEntry {
-        pub behavior: Rc<RefCell<dyn UnitBehavior>>,
+        pub behavior: Arc<dyn UnitBehavior>,
         pub species_name: String,
         pub default_attributes: Vec<(String, UnitAttributeValue)>,
         pub default_resources: Vec<(String, UnitResourceAmount)>,
@@ -178,11 +275,13 @@ pub mod builder {
             unit_entry::UnitEntry {
                 info: UnitEntryData {
                     unit_entry_id: 0,
+                    species_id: 0,
                     species_name: self.species_name.unwrap().clone(),
                     default_unit_attributes: compiled_attr,
                     default_resources: compiled_res,
                     default_entry_attributes: compiled_entry_attr,
                     external_id: self.external_id.unwrap_or(0),
+                    parent_entry_ids: vec![],
                 },
 
                 behavior: self.behavior.unwrap(),
@@ -2,13 +2,25 @@ use std::sync::mpsc::{channel, Receiver, Sender};
 
 use crate::chemistry::{Chemistry, ChemistryInstance};
 use crate::simulation::common::*;
-use crate::util::{coord_by_coord_offset, coord_by_direction_offset, Coord, GridDirection};
+use crate::simulation::iterators::CoordOffsetIterator;
+use crate::util::{coord_by_coord_offset, Coord, GridDirection, GridNeighborhood};
 use ndarray::*;
 use ndarray::{Array, Array2, Dim, Ix, Shape};
 
 // TODO: do we need an option here?  Array2 by default returns an option.
 pub type Grid = Array2<Option<Position>>;
 
+/**
+ * A unit copied from one or two parents during the current tick.  Kept until the end of the tick,
+ * so that the simulation can give the unit a genome of its own when evolving in-sim.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnitBirth {
+    pub unit_id: UnitId,
+    pub coord: Coord,
+    pub parent_entry_ids: Vec<UnitEntryId>,
+}
+
 #[derive(Clone)]
 pub struct World {
     pub grid: Grid,
@@ -16,6 +28,7 @@ pub struct World {
     pub last_unit_id: UnitId,
     pub tick: u64,
     pub _unit_count: u64,
+    pub births: Vec<UnitBirth>,
 }
 
 impl World {
@@ -33,6 +46,7 @@ impl World {
             last_unit_id: 0,
             tick: 0,
             _unit_count: 0,
+            births: vec![],
         }
     }
 
@@ -90,6 +104,75 @@ impl World {
         // maybe eventually the chemistry can define a list of attributes that are copied by
         // default from the src unit
         self.seed_unit_at(dest_coord, &unit_entry.info, None, chemistry);
+        self.births.push(UnitBirth {
+            unit_id: self.last_unit_id,
+            coord: dest_coord.clone(),
+            parent_entry_ids: vec![unit_entry.info.unit_entry_id],
+        });
+    }
+
+    /**
+     * Like copy_unit_with_attributes, but the birth is recorded with the unit at mate_coord as
+     * the second parent.
+     */
+    pub fn mate_units(
+        &mut self,
+        src_coord: &Coord,
+        mate_coord: &Coord,
+        dest_coord: &Coord,
+        unit_manifest: &UnitManifest,
+        chemistry: &dyn Chemistry,
+    ) {
+        let mate_entry_id = self.get_unit_at(mate_coord).unwrap().entry_id;
+        self.copy_unit_with_attributes(src_coord, dest_coord, unit_manifest, chemistry);
+        self.births
+            .last_mut()
+            .unwrap()
+            .parent_entry_ids
+            .push(mate_entry_id);
+    }
+
+    /**
+     * Where the unit with the given id is, looking at the coord and its neighbors before
+     * searching the whole world.  None if the unit is gone.
+     */
+    pub fn find_unit_near(
+        &self,
+        coord: &Coord,
+        unit_id: UnitId,
+        neighborhood: &GridNeighborhood,
+    ) -> Option<Coord> {
+        let is_unit = |c: &Coord| self.get_unit_at(c).map_or(false, |unit| unit.id == unit_id);
+        if is_unit(coord) {
+            return Some(coord.clone());
+        }
+
+        CoordOffsetIterator::with_neighborhood(coord, &self.size, neighborhood)
+            .map(|(c, _)| c)
+            .find(|c| is_unit(c))
+            .or_else(|| self.find_unit_coord(unit_id))
+    }
+
+    /**
+     * Where the unit with the given id is, if it is still alive.
+     */
+    pub fn find_unit_coord(&self, unit_id: UnitId) -> Option<Coord> {
+        CoordIterator::new(self.size).find(|coord| {
+            self.get_unit_at(coord)
+                .map_or(false, |unit| unit.id == unit_id)
+        })
+    }
+
+    pub fn set_unit_entry_id(&mut self, coord: &Coord, entry_id: UnitEntryId) {
+        let pos = self
+            .grid
+            .get_mut([coord.0, coord.1])
+            .unwrap()
+            .as_mut()
+            .unwrap();
+        if let Some(unit) = &mut pos.unit {
+            unit.entry_id = entry_id;
+        }
     }
 
     pub fn seed_unit_at(
@@ -133,6 +216,7 @@ impl World {
             resources,
             attributes,
             entry_id: unit_entry.unit_entry_id,
+            species_id: unit_entry.species_id,
             id: self.last_unit_id,
             coord: coord.clone(),
             last_update_tick: 0,
@@ -159,6 +243,13 @@ impl World {
         return None;
     }
 
+    pub fn count_units(&self) -> usize {
+        self.grid
+            .iter()
+            .filter(|maybe_pos| maybe_pos.as_ref().map_or(false, |pos| pos.unit.is_some()))
+            .count()
+    }
+
     pub fn has_unit_at(&self, coord: &Coord) -> bool {
         let maybe_pos = self.grid.get([coord.0, coord.1]).unwrap();
 
@@ -292,6 +383,18 @@ impl World {
         }
     }
 
+    pub fn set_pos_resource_tab_min(
+        &mut self,
+        coord: &Coord,
+        resource_idx: PositionResourceIndex,
+        min: Option<i32>,
+    ) {
+        let mut item = self.grid.get_mut([coord.0, coord.1]).unwrap();
+        if let Some(pos) = item {
+            pos.resources[resource_idx].min_amount = min;
+        }
+    }
+
     pub fn add_unit_resources_at(&mut self, coord: &Coord, unit_resources: &SomeUnitResources) {
         let mut item = self.grid.get_mut([coord.0, coord.1]).unwrap();
         if let Some(pos) = item {
@@ -374,8 +477,13 @@ impl World {
         }
     }
 
-    pub fn get_pos_at_dir(&self, coord: &Coord, dir: GridDirection) -> Option<&Position> {
-        let c = coord_by_direction_offset(coord, &dir, self.size);
+    pub fn get_pos_at_dir(
+        &self,
+        coord: &Coord,
+        dir: GridDirection,
+        neighborhood: &GridNeighborhood,
+    ) -> Option<&Position> {
+        let c = neighborhood.coord_by_direction_offset(coord, &dir, self.size);
 
         match c {
             Some(coord) => self.get_position_at(&coord),
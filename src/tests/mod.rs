@@ -1,4 +1,5 @@
 use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::{
     biology::{
@@ -17,9 +18,9 @@ use crate::{
 
 pub fn make_sim(
     chemistry: ChemistryInstance,
-    genome: Rc<CompiledFramedGenome>,
+    genome: Arc<CompiledFramedGenome>,
 ) -> SimulationBuilder {
-    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_rc();
+    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_arc();
     SimulationBuilder::default()
         .chemistry(chemistry)
         .size((3, 3))
@@ -47,7 +48,7 @@ pub fn default_chemistry_configuration() {
 
     assert_eq!(val, 10);
 
-    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_rc();
+    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_arc();
     let cm = &gm.chemistry_manifest;
 
     let genome_values = genome!(gene(
@@ -57,7 +58,7 @@ pub fn default_chemistry_configuration() {
     .build(&gm);
 
     let framed_vals = simple_convert_into_frames(genome_values);
-    let frames = FramedGenomeCompiler::compile(framed_vals, &gm).wrap_rc();
+    let frames = FramedGenomeCompiler::compile(framed_vals, &gm).wrap_arc();
 
     let mut sim = make_sim(chemistry, frames).to_simulation();
 
@@ -94,7 +95,7 @@ pub fn default_override_chemistry_configuration() {
 
     assert_eq!(val, target);
 
-    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_rc();
+    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_arc();
     let cm = &gm.chemistry_manifest;
     assert_eq!(cm.all_properties[1].id, 1); // make sure properties got created and normalized
 
@@ -105,7 +106,7 @@ pub fn default_override_chemistry_configuration() {
     .build(&gm);
 
     let framed_vals = simple_convert_into_frames(genome_values);
-    let frames = FramedGenomeCompiler::compile(framed_vals, &gm).wrap_rc();
+    let frames = FramedGenomeCompiler::compile(framed_vals, &gm).wrap_arc();
 
     let mut sim = make_sim(chemistry, frames).to_simulation();
 
@@ -131,3 +132,38 @@ pub fn default_override_chemistry_configuration() {
 
     assert_eq!(amount, target as i32);
 }
+
+#[test]
+pub fn inspect_cell() {
+    let chemistry = ChemistryBuilder::with_key("cheese").build();
+    let gm = GeneticManifest::from_chemistry(&chemistry).wrap_arc();
+
+    let genome_values = genome!(gene(
+        if_any(all((is_truthy, 1, 0, 0))),
+        then_do(new_unit(0, 0, 0))
+    ))
+    .build(&gm);
+
+    let framed_vals = simple_convert_into_frames(genome_values);
+    let frames = FramedGenomeCompiler::compile(framed_vals, &gm).wrap_arc();
+
+    let sim = make_sim(chemistry, frames).to_simulation();
+
+    let inspection = sim.inspect_cell(&(0, 0));
+    let unit = inspection.unit.as_ref().unwrap();
+    assert_eq!(unit.species_name, "main");
+    assert_eq!(
+        unit.resources.len(),
+        gm.chemistry_manifest.unit_resources.len()
+    );
+
+    let behavior = unit.behavior.as_ref().unwrap();
+    assert_eq!(
+        behavior.sensor_values.len(),
+        gm.sensor_manifest.sensors.len()
+    );
+    assert!(behavior.genome.contains("new_unit"));
+    assert!(inspection.to_lines().contains(&"genome:".to_string()));
+
+    assert_eq!(sim.inspect_cell(&(1, 1)).unit, None);
+}
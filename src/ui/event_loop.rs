@@ -9,11 +9,15 @@ use std::time::{Duration, Instant};
 use crate::piston_window::EventLoop;
 use crate::piston_window::RenderEvent;
 use crate::piston_window::UpdateEvent;
+use piston_window::{Button, Key, MouseButton, MouseCursorEvent, PressEvent};
 use piston_window::{OpenGL, PistonWindow, WindowSettings};
 
 use opengl_graphics::GlGraphics;
 
-use super::world::{draw_world, get_cell_renderer};
+use super::hud::draw_hud;
+use super::inspector::draw_inspector;
+use super::load_ui_glyphs;
+use super::world::{draw_world, get_cell_renderer, pick_cell, world_width, CellRenderer};
 
 // pub fn get_cell_renderer() -> <dyn CellRenderer> {
 //     match sim.config.chemistry_key.as_str() {
@@ -58,7 +62,9 @@ pub fn start_sim_ui(
     let mut started_sim = false;
     let mut last_rendered_tick = 0;
     let cell_size = 20.0;
-    let cell_renderer = get_cell_renderer(world_render_config.chemistry_key.as_str());
+    let mut cell_renderer: Option<Box<dyn CellRenderer>> = None;
+    let mut glyphs = load_ui_glyphs();
+    let mut cursor_pos: [f64; 2] = [0.0, 0.0];
 
     while let Some(e) = window.next() {
         if let Some(r) = e.render_args() {
@@ -72,17 +78,81 @@ pub fn start_sim_ui(
             }
 
             let sim = sim_option_ref.unwrap();
+            let cell_renderer = cell_renderer.get_or_insert_with(|| get_cell_renderer(sim));
 
             draw_world(
                 sim,
                 &mut gl,
                 r.viewport(),
-                &cell_renderer,
+                cell_renderer,
                 world_render_config.cell_size,
             );
+            if let Some(inspection) = &sim.inspection {
+                draw_inspector(
+                    sim,
+                    inspection,
+                    &mut gl,
+                    &mut glyphs,
+                    r.viewport(),
+                    world_render_config.cell_size,
+                    world_width(sim, world_render_config.cell_size),
+                );
+            }
+            draw_hud(
+                sim,
+                &mut gl,
+                &mut glyphs,
+                r.viewport(),
+                sim.config.size.1 as f64 * world_render_config.cell_size,
+                world_width(sim, world_render_config.cell_size),
+                cell_renderer
+                    .layer_names()
+                    .get(cell_renderer.selected_layer()),
+            );
             last_rendered_tick = sim.tick;
         }
 
+        if let Some(pos) = e.mouse_cursor_args() {
+            cursor_pos = pos;
+        }
+
+        if let Some(Button::Keyboard(key)) = e.press_args() {
+            let guard = _sim.lock();
+            let unwrapped = guard.unwrap();
+            let option = unwrapped.borrow();
+            let is_paused = option
+                .as_ref()
+                .map_or(true, |sim| sim.executor_status.is_paused);
+
+            if let Some(event) = control_event_for_key(key, is_paused) {
+                // the simulation thread is gone, there is nothing left to control
+                if sender_from_ui.send(event).is_err() {
+                    return;
+                }
+            }
+
+            if let (Some(layer), Some(renderer)) = (layer_for_key(key), cell_renderer.as_mut()) {
+                renderer.select_layer(layer);
+            }
+        }
+
+        if let Some(Button::Mouse(MouseButton::Left)) = e.press_args() {
+            let guard = _sim.lock();
+            let unwrapped = guard.unwrap();
+            let option = unwrapped.borrow();
+
+            if let Some(sim) = option.as_ref() {
+                if let Some(coord) = pick_cell(sim, world_render_config.cell_size, cursor_pos) {
+                    if sender_from_ui
+                        .send(SimulationControlEvent::Inspect(coord))
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+            }
+        }
+
         if let Some(u) = e.update_args() {
             if u.dt < frame_time_ms {
                 thread::sleep(Duration::from_millis(
@@ -92,12 +162,49 @@ pub fn start_sim_ui(
         }
 
         if !started_sim {
-            sender_from_ui.send(SimulationControlEvent::Resume);
+            if sender_from_ui.send(SimulationControlEvent::Resume).is_err() {
+                return;
+            }
             started_sim = true;
         }
     }
 }
 
+/**
+ * Playback keys: space pauses and resumes, `.` runs a single tick, `+`/`-` change the tick
+ * rate and `q` halts the simulation.
+ */
+pub fn control_event_for_key(key: Key, is_paused: bool) -> Option<SimulationControlEvent> {
+    match key {
+        Key::Space if is_paused => Some(SimulationControlEvent::Resume),
+        Key::Space => Some(SimulationControlEvent::Pause),
+        Key::Period => Some(SimulationControlEvent::Step),
+        Key::Equals | Key::Plus | Key::NumPadPlus => Some(SimulationControlEvent::SpeedUp),
+        Key::Minus | Key::NumPadMinus => Some(SimulationControlEvent::SlowDown),
+        Key::Q => Some(SimulationControlEvent::Halt),
+        _ => None,
+    }
+}
+
+/**
+ * The number keys select the renderer's layers, starting with 1 for the first layer.
+ */
+pub fn layer_for_key(key: Key) -> Option<usize> {
+    let layer_keys = [
+        Key::D1,
+        Key::D2,
+        Key::D3,
+        Key::D4,
+        Key::D5,
+        Key::D6,
+        Key::D7,
+        Key::D8,
+        Key::D9,
+        Key::D0,
+    ];
+    layer_keys.iter().position(|layer_key| *layer_key == key)
+}
+
 // pub fn start_ui_loop(_sim: ThreadedSimulationReference, mut sim_events: Receiver<SimulationEvent>, sender_from_ui: SimulationControlEventSender) {
 
 //     //let mut app_view = init_ui();
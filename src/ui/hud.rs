@@ -0,0 +1,78 @@
+use opengl_graphics::{GlGraphics, GlyphCache};
+use piston_window::{rectangle, text, Transformed, Viewport};
+
+use crate::simulation::simulation_data::SimulationData;
+use crate::ui::colors::{to_color, BLACK, WHITE};
+
+const FONT_SIZE: u32 = 14;
+const HUD_HEIGHT: f64 = 24.0;
+const MARGIN: f64 = 6.0;
+
+pub fn hud_text(sim: &SimulationData, layer_name: Option<&String>) -> String {
+    let status = &sim.executor_status;
+    let state = if status.is_finished {
+        "halted"
+    } else if status.is_paused {
+        "paused"
+    } else {
+        "running"
+    };
+
+    let mut text = format!(
+        "tick {}/{} | {:.1} ticks/s (max {}) | {:.0} units/s | population {} | {}",
+        sim.tick,
+        sim.config.iterations,
+        status.ticks_per_second,
+        status.max_ticks_per_second,
+        status.unit_updates_per_second,
+        status.population,
+        state
+    );
+    if !status.fitness.is_empty() {
+        let fitness = status
+            .fitness
+            .iter()
+            .map(|(species_name, score)| format!("{}={}", species_name, score))
+            .collect::<Vec<_>>();
+        text.push_str(&format!(" | fitness: {}", fitness.join(", ")));
+    }
+    if let Some(layer_name) = layer_name {
+        text.push_str(&format!(" | layer: {}", layer_name));
+    }
+
+    text
+}
+
+/**
+ * A single status line drawn at the given height, usually just below the world.
+ */
+pub fn draw_hud(
+    sim: &SimulationData,
+    gl: &mut GlGraphics,
+    glyphs: &mut GlyphCache,
+    viewport: Viewport,
+    top: f64,
+    width: f64,
+    layer_name: Option<&String>,
+) {
+    let line = hud_text(sim, layer_name);
+
+    gl.draw(viewport, |c, g| {
+        rectangle(
+            to_color(BLACK),
+            [0.0, top, width, HUD_HEIGHT],
+            c.transform,
+            g,
+        );
+
+        text::Text::new_color(to_color(WHITE), FONT_SIZE)
+            .draw(
+                &line,
+                glyphs,
+                &c.draw_state,
+                c.transform.trans(MARGIN, top + HUD_HEIGHT - MARGIN),
+                g,
+            )
+            .expect("failed to draw hud text");
+    });
+}
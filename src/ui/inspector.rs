@@ -0,0 +1,64 @@
+use opengl_graphics::{GlGraphics, GlyphCache};
+use piston_window::{rectangle, text, Rectangle, Transformed, Viewport};
+
+use crate::chemistry::config::neighborhood_from_config;
+use crate::simulation::inspection::CellInspection;
+use crate::simulation::simulation_data::SimulationData;
+use crate::ui::colors::{to_color, LIGHT_BLACK, RED, WHITE};
+use crate::ui::world::cell_rect;
+
+const FONT_SIZE: u32 = 12;
+const LINE_HEIGHT: f64 = 16.0;
+const MARGIN: f64 = 10.0;
+
+/**
+ * Outlines the inspected cell and lists everything known about it in a panel to the right of
+ * the world.
+ */
+pub fn draw_inspector(
+    sim: &SimulationData,
+    inspection: &CellInspection,
+    gl: &mut GlGraphics,
+    glyphs: &mut GlyphCache,
+    viewport: Viewport,
+    cell_size: f64,
+    panel_left: f64,
+) {
+    let neighborhood = neighborhood_from_config(&sim.config.chemistry_config);
+    let highlight_rect = cell_rect(sim, &neighborhood, cell_size, &inspection.coord);
+    let window_size = viewport.window_size;
+
+    gl.draw(viewport, |c, g| {
+        Rectangle::new_border(to_color(RED), 1.5).draw(
+            highlight_rect,
+            &c.draw_state,
+            c.transform,
+            g,
+        );
+
+        let panel_rect = [
+            panel_left,
+            0.0,
+            (window_size[0] - panel_left).max(0.0),
+            window_size[1],
+        ];
+        rectangle(to_color(LIGHT_BLACK), panel_rect, c.transform, g);
+
+        for (i, line) in inspection.to_lines().iter().enumerate() {
+            let y = MARGIN + (i + 1) as f64 * LINE_HEIGHT;
+            if y > window_size[1] {
+                break;
+            }
+
+            text::Text::new_color(to_color(WHITE), FONT_SIZE)
+                .draw(
+                    line,
+                    glyphs,
+                    &c.draw_state,
+                    c.transform.trans(panel_left + MARGIN, y),
+                    g,
+                )
+                .expect("failed to draw inspector text");
+        }
+    });
+}
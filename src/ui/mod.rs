@@ -1,6 +1,9 @@
 pub mod colors;
 pub mod event_loop;
 pub mod fake_board;
+pub mod hud;
+pub mod inspector;
+pub mod terminal;
 pub mod world;
 
 use fps_counter::FPSCounter;
@@ -16,9 +19,22 @@ use piston_window::clear;
 use piston_window::rectangle;
 use piston_window::{OpenGL, PistonWindow, WindowSettings};
 
-use opengl_graphics::GlGraphics;
+use opengl_graphics::{GlGraphics, GlyphCache, TextureSettings};
 
 const BOARD_WIDTH: usize = 100;
 const BOARD_HEIGHT: usize = 100;
 const BOARD_SIZE: usize = BOARD_WIDTH * BOARD_HEIGHT;
 // pub type Board = [u8; BOARD_SIZE];
+
+pub fn load_ui_glyphs() -> GlyphCache<'static> {
+    let assets = find_folder::Search::ParentsThenKids(3, 3)
+        .for_folder("assets")
+        .expect("could not find the assets folder");
+
+    GlyphCache::new(
+        assets.join("DMMono-Regular.ttf"),
+        (),
+        TextureSettings::new(),
+    )
+    .expect("could not load the ui font")
+}
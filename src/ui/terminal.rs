@@ -0,0 +1,329 @@
+use std::io::{Read, Stdout, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Duration;
+
+use crate::chemistry::builder::ChemistryBuilder;
+use crate::chemistry::config::neighborhood_from_config;
+use crate::simulation::common::SimulationControlEventSender;
+use crate::simulation::simulation_data::{SimulationData, ThreadedSimulationReference};
+use crate::simulation::SimulationControlEvent;
+use crate::ui::hud::hud_text;
+use crate::ui::world::manifest::{
+    heatmap_color, layer_heatmap_max, layer_heatmap_value, layers_from_manifest, species_color,
+    RenderLayer,
+};
+use crate::ui::world::row_shift;
+use crate::util::text_grid::{render_cells_into_grid, CellTextAlignment, TextGridOptions};
+
+const CLEAR_SCREEN: &str = "\x1b[2J";
+const CURSOR_HOME: &str = "\x1b[H";
+const CLEAR_TO_END: &str = "\x1b[J";
+const HIDE_CURSOR: &str = "\x1b[?25l";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+const RESET: &str = "\x1b[0m";
+
+pub struct TerminalViewConfig {
+    pub renders_per_second: u32,
+}
+
+/**
+ * Draws a simulation as a grid of ANSI colored characters, two per cell so that cells come out
+ * roughly square.  Uses the same layers as the ManifestCellRenderer.
+ */
+pub struct TerminalRenderer {
+    layers: Vec<RenderLayer>,
+    selected_layer: usize,
+}
+
+impl TerminalRenderer {
+    pub fn new(sim: &SimulationData) -> Self {
+        let manifest = ChemistryBuilder::with_key(&sim.config.chemistry_key)
+            .config(sim.config.chemistry_config.clone())
+            .manifest();
+
+        Self {
+            layers: layers_from_manifest(&manifest),
+            selected_layer: 0,
+        }
+    }
+
+    pub fn select_layer(&mut self, layer: usize) -> bool {
+        if layer < self.layers.len() {
+            self.selected_layer = layer;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn layer(&self) -> &RenderLayer {
+        &self.layers[self.selected_layer]
+    }
+
+    pub fn render_frame(&self, sim: &SimulationData) -> String {
+        let layer = self.layer();
+        let neighborhood = neighborhood_from_config(&sim.config.chemistry_config);
+        let heatmap_max = layer_heatmap_max(layer, sim);
+        let (width, height) = sim.config.size;
+
+        let options = TextGridOptions {
+            cell_width: 2,
+            cell_height: 1,
+            has_border: false,
+            alignment: CellTextAlignment::Left,
+        };
+
+        // the text grid puts the highest row on top, the gui draws the first row on top
+        let grid = render_cells_into_grid(&options, (width, height), |&(x, y)| {
+            self.render_cell(sim, x, height - 1 - y, heatmap_max)
+        });
+
+        let mut buffer = String::new();
+        for (y, line) in grid.lines().enumerate() {
+            // hex rows are shifted by half a cell, ie. a single character
+            let shift = (row_shift(&neighborhood, y, height) * 2.0) as usize;
+            buffer += &" ".repeat(shift);
+            buffer += line;
+            buffer += RESET;
+            buffer += "\n";
+        }
+
+        let layer_name = layer.name();
+        buffer += &hud_text(sim, Some(&layer_name));
+        buffer += "\n";
+        buffer += "space: pause/resume | .: step | +/-: speed | 1-9,0: layer | q: quit\n";
+
+        buffer
+    }
+
+    fn render_cell(&self, sim: &SimulationData, x: usize, y: usize, heatmap_max: f64) -> String {
+        let layer = self.layer();
+        let pos = sim.grid[[x, y]].as_ref().unwrap();
+
+        let bg = match layer {
+            RenderLayer::PositionResourceHeatmap(..) | RenderLayer::UnitResourceHeatmap(..) => {
+                match layer_heatmap_value(layer, sim, x, y) {
+                    Some(value) if heatmap_max > 0.0 => heatmap_color(value / heatmap_max),
+                    _ => heatmap_color(0.0),
+                }
+            }
+            RenderLayer::PositionAttributeOverlay(idx, _)
+                if pos.get_attribute(*idx).unwrap_bool() =>
+            {
+                [0.35, 0.35, 0.35, 1.0]
+            }
+            _ => [0.0, 0.0, 0.0, 1.0],
+        };
+
+        let unit = match layer {
+            RenderLayer::UnitResourceHeatmap(..) => None,
+            _ => pos.unit.as_ref(),
+        };
+
+        match unit {
+            Some(unit) => format!("{}{}()", ansi_bg(bg), ansi_fg(species_color(unit.entry_id))),
+            None => format!("{}  ", ansi_bg(bg)),
+        }
+    }
+}
+
+fn ansi_rgb(color: [f32; 4]) -> String {
+    let channel = |c: f32| (c.max(0.0).min(1.0) * 255.0).round() as u8;
+    format!(
+        "{};{};{}",
+        channel(color[0]),
+        channel(color[1]),
+        channel(color[2])
+    )
+}
+
+fn ansi_fg(color: [f32; 4]) -> String {
+    format!("\x1b[38;2;{}m", ansi_rgb(color))
+}
+
+fn ansi_bg(color: [f32; 4]) -> String {
+    format!("\x1b[48;2;{}m", ansi_rgb(color))
+}
+
+/**
+ * The terminal counterpart of event_loop::control_event_for_key.
+ */
+pub fn control_event_for_byte(byte: u8, is_paused: bool) -> Option<SimulationControlEvent> {
+    match byte {
+        b' ' if is_paused => Some(SimulationControlEvent::Resume),
+        b' ' => Some(SimulationControlEvent::Pause),
+        b'.' => Some(SimulationControlEvent::Step),
+        b'=' | b'+' => Some(SimulationControlEvent::SpeedUp),
+        b'-' => Some(SimulationControlEvent::SlowDown),
+        b'q' | b'Q' => Some(SimulationControlEvent::Halt),
+        _ => None,
+    }
+}
+
+/**
+ * The number keys select layers, starting with 1 for the first layer.
+ */
+pub fn layer_for_byte(byte: u8) -> Option<usize> {
+    match byte {
+        b'1'..=b'9' => Some((byte - b'1') as usize),
+        b'0' => Some(9),
+        _ => None,
+    }
+}
+
+fn set_stty(args: &[&str]) {
+    Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .status()
+        .expect("failed to run stty");
+}
+
+/**
+ * Reads single key presses from stdin on a separate thread.  The terminal is put into
+ * non-canonical mode so that keys arrive without waiting for enter.
+ */
+fn spawn_key_reader() -> Receiver<u8> {
+    set_stty(&["-icanon", "-echo", "min", "1"]);
+
+    let (sender, receiver) = channel::<u8>();
+    std::thread::spawn(move || {
+        let mut stdin = std::io::stdin();
+        let mut byte = [0u8; 1];
+        while let Ok(1) = stdin.read(&mut byte) {
+            if sender.send(byte[0]).is_err() {
+                break;
+            }
+        }
+    });
+
+    receiver
+}
+
+/**
+ * Redraws the simulation in place in the terminal until the simulation finishes or the user
+ * quits.  The simulation itself runs on a ThreadedSimulationExecutor, same as with the gui.
+ */
+pub fn start_sim_tui(
+    sim_ref: ThreadedSimulationReference,
+    sender: SimulationControlEventSender,
+    config: TerminalViewConfig,
+) {
+    let keys = spawn_key_reader();
+    let mut stdout = std::io::stdout();
+
+    let result = write!(stdout, "{}{}", CLEAR_SCREEN, HIDE_CURSOR)
+        .and_then(|_| render_loop(&sim_ref, &sender, &keys, &config, &mut stdout));
+
+    // the terminal is restored before reporting, otherwise the error wouldn't be readable
+    let restored = write!(stdout, "{}{}", RESET, SHOW_CURSOR).and_then(|_| stdout.flush());
+    set_stty(&["icanon", "echo"]);
+
+    result
+        .and(restored)
+        .expect("failed to draw the simulation in the terminal");
+}
+
+fn render_loop(
+    sim_ref: &ThreadedSimulationReference,
+    sender: &SimulationControlEventSender,
+    keys: &Receiver<u8>,
+    config: &TerminalViewConfig,
+    stdout: &mut Stdout,
+) -> std::io::Result<()> {
+    let frame_delay = Duration::from_secs_f64(1.0 / config.renders_per_second.max(1) as f64);
+    let mut renderer: Option<TerminalRenderer> = None;
+
+    loop {
+        let data = sim_ref.lock().unwrap().borrow().clone();
+
+        if let Some(data) = data {
+            let renderer = renderer.get_or_insert_with(|| TerminalRenderer::new(&data));
+
+            for byte in keys.try_iter() {
+                if let Some(layer) = layer_for_byte(byte) {
+                    renderer.select_layer(layer);
+                } else if let Some(event) =
+                    control_event_for_byte(byte, data.executor_status.is_paused)
+                {
+                    let is_halt = event == SimulationControlEvent::Halt;
+
+                    // the executor stops listening once the simulation is over
+                    if sender.send(event).is_err() || is_halt {
+                        return Ok(());
+                    }
+                }
+            }
+
+            let frame = renderer.render_frame(&data);
+            write!(stdout, "{}{}{}", CURSOR_HOME, frame, CLEAR_TO_END)?;
+            stdout.flush()?;
+
+            // the last frame stays on screen
+            if data.executor_status.is_finished {
+                return Ok(());
+            }
+        }
+
+        std::thread::sleep(frame_delay);
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::simulation::common::*;
+
+    #[test]
+    fn render_frame() {
+        let chemistry_builder = ChemistryBuilder::with_key("cheese");
+        let sim = SimulationBuilder::default()
+            .chemistry(chemistry_builder.build())
+            .size((3, 2))
+            .iterations(10)
+            .unit_manifest(UnitManifest {
+                units: vec![UnitEntryBuilder::default()
+                    .species_name("main".to_string())
+                    .behavior(NullBehavior::construct())
+                    .build(&chemistry_builder.manifest())],
+            })
+            .to_simulation();
+        let data = sim.to_data();
+
+        let mut renderer = TerminalRenderer::new(&data);
+        let frame = renderer.render_frame(&data);
+        let lines = frame.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 2 + 2);
+        assert_eq!(lines[0].matches("\x1b[48;2;").count(), 3);
+        assert!(lines[2].starts_with(&format!("tick {}/{}", data.tick, data.config.iterations)));
+        assert!(lines[2].ends_with("layer: species"));
+
+        assert!(renderer.select_layer(1));
+        assert!(!renderer.select_layer(100));
+        let frame = renderer.render_frame(&data);
+        assert!(frame.contains(&format!("layer: {}", renderer.layer().name())));
+    }
+
+    #[test]
+    fn keys() {
+        assert_eq!(
+            control_event_for_byte(b' ', true),
+            Some(SimulationControlEvent::Resume)
+        );
+        assert_eq!(
+            control_event_for_byte(b' ', false),
+            Some(SimulationControlEvent::Pause)
+        );
+        assert_eq!(
+            control_event_for_byte(b'q', false),
+            Some(SimulationControlEvent::Halt)
+        );
+        assert_eq!(control_event_for_byte(b'x', false), None);
+
+        assert_eq!(layer_for_byte(b'1'), Some(0));
+        assert_eq!(layer_for_byte(b'0'), Some(9));
+        assert_eq!(layer_for_byte(b'a'), None);
+    }
+}
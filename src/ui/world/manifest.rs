@@ -0,0 +1,266 @@
+use super::CellRenderer;
+use crate::chemistry::properties::AttributeDefinitionType;
+use crate::chemistry::ChemistryManifest;
+use crate::simulation::simulation_data::SimulationData;
+use crate::ui::colors::to_color;
+use opengl_graphics::GlGraphics;
+use piston_window::types::Color;
+use piston_window::{ellipse, rectangle, Context};
+
+/**
+ * What a ManifestCellRenderer shows for each cell.  Units are drawn in their species color on
+ * top of every layer except the unit resource heatmaps.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub enum RenderLayer {
+    Species,
+    PositionResourceHeatmap(usize, String),
+    UnitResourceHeatmap(usize, String),
+    PositionAttributeOverlay(usize, String),
+}
+
+impl RenderLayer {
+    pub fn name(&self) -> String {
+        match self {
+            RenderLayer::Species => "species".to_string(),
+            RenderLayer::PositionResourceHeatmap(_, key) => format!("position resource: {}", key),
+            RenderLayer::UnitResourceHeatmap(_, key) => format!("unit resource: {}", key),
+            RenderLayer::PositionAttributeOverlay(_, key) => format!("position attribute: {}", key),
+        }
+    }
+}
+
+pub fn layers_from_manifest(manifest: &ChemistryManifest) -> Vec<RenderLayer> {
+    let mut layers = vec![RenderLayer::Species];
+
+    layers.extend(
+        manifest
+            .position_resources
+            .iter()
+            .map(|def| RenderLayer::PositionResourceHeatmap(def.id, def.key.clone())),
+    );
+    layers.extend(
+        manifest
+            .unit_resources
+            .iter()
+            .map(|def| RenderLayer::UnitResourceHeatmap(def.id, def.key.clone())),
+    );
+    layers.extend(
+        manifest
+            .position_attributes
+            .iter()
+            .filter(|def| matches!(def.value_type, AttributeDefinitionType::Boolean))
+            .map(|def| RenderLayer::PositionAttributeOverlay(def.id, def.key.clone())),
+    );
+
+    layers
+}
+
+/**
+ * Renders any chemistry using nothing but its manifest.
+ */
+pub struct ManifestCellRenderer {
+    layers: Vec<RenderLayer>,
+    selected_layer: usize,
+
+    // the largest value of the selected heatmap layer, recalculated every frame
+    heatmap_max: f64,
+}
+
+impl ManifestCellRenderer {
+    pub fn new(manifest: &ChemistryManifest) -> Self {
+        Self {
+            layers: layers_from_manifest(manifest),
+            selected_layer: 0,
+            heatmap_max: 0.0,
+        }
+    }
+
+    fn heatmap_value(&self, sim: &SimulationData, x: usize, y: usize) -> Option<f64> {
+        layer_heatmap_value(&self.layers[self.selected_layer], sim, x, y)
+    }
+}
+
+/**
+ * The value a heatmap layer shows for a cell, or None for other layers and for unit heatmaps
+ * over empty cells.
+ */
+pub fn layer_heatmap_value(
+    layer: &RenderLayer,
+    sim: &SimulationData,
+    x: usize,
+    y: usize,
+) -> Option<f64> {
+    let pos = sim.grid[[x, y]].as_ref()?;
+    match layer {
+        RenderLayer::PositionResourceHeatmap(idx, _) => {
+            Some(pos.get_resource(*idx, sim.tick) as f64)
+        }
+        RenderLayer::UnitResourceHeatmap(idx, _) => {
+            pos.unit.as_ref().map(|unit| unit.get_resource(*idx) as f64)
+        }
+        _ => None,
+    }
+}
+
+/**
+ * The largest value of a heatmap layer over the whole grid, which heatmap colors are scaled to.
+ */
+pub fn layer_heatmap_max(layer: &RenderLayer, sim: &SimulationData) -> f64 {
+    let mut max: f64 = 0.0;
+    for x in 0..sim.config.size.0 {
+        for y in 0..sim.config.size.1 {
+            if let Some(value) = layer_heatmap_value(layer, sim, x, y) {
+                max = max.max(value);
+            }
+        }
+    }
+    max
+}
+
+/**
+ * Spreads the species around the color wheel by the golden angle, so that neighboring entry
+ * ids get clearly different colors.
+ */
+pub fn species_color(entry_id: usize) -> [f32; 4] {
+    let hue = (entry_id as f64 * 137.508) % 360.0;
+    let [r, g, b] = hsv_to_rgb(hue, 0.75, 0.95);
+    [r, g, b, 1.0]
+}
+
+/**
+ * Black through red to yellow as pct goes from 0 to 1.
+ */
+pub fn heatmap_color(pct: f64) -> [f32; 4] {
+    let pct = pct.max(0.0).min(1.0) as f32;
+    [(pct * 2.0).min(1.0), (pct * 2.0 - 1.0).max(0.0), 0.0, 1.0]
+}
+
+fn hsv_to_rgb(hue: f64, saturation: f64, value: f64) -> [f32; 3] {
+    let chroma = value * saturation;
+    let h = hue / 60.0;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = value - chroma;
+    [(r + m) as f32, (g + m) as f32, (b + m) as f32]
+}
+
+impl CellRenderer for ManifestCellRenderer {
+    fn prepare(&mut self, sim: &SimulationData) {
+        self.heatmap_max = layer_heatmap_max(&self.layers[self.selected_layer], sim);
+    }
+
+    fn draw_cell(
+        &self,
+        sim: &SimulationData,
+        g: &mut GlGraphics,
+        c: &mut Context,
+        x: usize,
+        y: usize,
+        cell_rect: [f64; 4],
+    ) {
+        let pos = sim.grid[[x, y]].as_ref().unwrap();
+        let layer = &self.layers[self.selected_layer];
+
+        rectangle(
+            to_color([0x00, 0x00, 0x00, 0xff]),
+            cell_rect,
+            c.transform,
+            g,
+        );
+
+        match layer {
+            RenderLayer::PositionResourceHeatmap(..) | RenderLayer::UnitResourceHeatmap(..) => {
+                if let Some(value) = self.heatmap_value(sim, x, y) {
+                    let pct = if self.heatmap_max > 0.0 {
+                        value / self.heatmap_max
+                    } else {
+                        0.0
+                    };
+                    rectangle(heatmap_color(pct), cell_rect, c.transform, g);
+                }
+            }
+            RenderLayer::PositionAttributeOverlay(idx, _) => {
+                if pos.get_attribute(*idx).unwrap_bool() {
+                    rectangle([1.0, 1.0, 1.0, 0.35], cell_rect, c.transform, g);
+                }
+            }
+            RenderLayer::Species => {}
+        }
+
+        if let RenderLayer::UnitResourceHeatmap(..) = layer {
+            return;
+        }
+
+        if let Some(unit) = &pos.unit {
+            let width = cell_rect[2] * 0.6;
+            let offset = (cell_rect[2] - width) / 2.0;
+            let rect = [cell_rect[0] + offset, cell_rect[1] + offset, width, width];
+            ellipse(species_color(unit.entry_id), rect, c.transform, g);
+        }
+    }
+
+    fn bg_color(&self) -> Color {
+        [0.5; 4]
+    }
+
+    fn layer_names(&self) -> Vec<String> {
+        self.layers.iter().map(|layer| layer.name()).collect()
+    }
+
+    fn selected_layer(&self) -> usize {
+        self.selected_layer
+    }
+
+    fn select_layer(&mut self, layer: usize) -> bool {
+        if layer < self.layers.len() {
+            self.selected_layer = layer;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::chemistry::builder::ChemistryBuilder;
+
+    #[test]
+    fn layers_cover_the_manifest() {
+        let manifest = ChemistryBuilder::with_key("lever").manifest();
+        let mut renderer = ManifestCellRenderer::new(&manifest);
+        let layers = layers_from_manifest(&manifest);
+
+        assert_eq!(layers[0], RenderLayer::Species);
+        assert_eq!(
+            layers.len(),
+            1 + manifest.position_resources.len()
+                + manifest.unit_resources.len()
+                + manifest
+                    .position_attributes
+                    .iter()
+                    .filter(|def| matches!(def.value_type, AttributeDefinitionType::Boolean))
+                    .count()
+        );
+
+        assert!(renderer.select_layer(layers.len() - 1));
+        assert!(!renderer.select_layer(layers.len()));
+        assert_eq!(renderer.selected_layer(), layers.len() - 1);
+    }
+
+    #[test]
+    fn colors() {
+        assert_eq!(heatmap_color(0.0), [0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(heatmap_color(1.0), [1.0, 1.0, 0.0, 1.0]);
+        assert_ne!(species_color(0), species_color(1));
+    }
+}
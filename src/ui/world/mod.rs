@@ -1,11 +1,16 @@
 pub mod cheese;
 pub mod draw_world;
+pub mod manifest;
+use crate::chemistry::config::neighborhood_from_config;
 use crate::simulation::simulation_data::SimulationData;
+use crate::util::{Coord, GridNeighborhood};
 use opengl_graphics::GlGraphics;
 use piston_window::types::Color;
 use piston_window::{clear, Context, Viewport};
 
 use self::cheese::CheeseCellRenderer;
+use self::manifest::ManifestCellRenderer;
+use crate::chemistry::builder::ChemistryBuilder;
 
 pub trait CellRenderer {
     fn draw_cell(
@@ -18,33 +23,107 @@ pub trait CellRenderer {
         cell_rect: [f64; 4],
     );
     fn bg_color(&self) -> Color;
+
+    // called once per frame, before any cell is drawn
+    fn prepare(&mut self, sim: &SimulationData) {}
+
+    fn layer_names(&self) -> Vec<String> {
+        vec![]
+    }
+    fn selected_layer(&self) -> usize {
+        0
+    }
+    fn select_layer(&mut self, layer: usize) -> bool {
+        false
+    }
 }
 
 pub fn draw_world(
     sim: &SimulationData,
     gl: &mut GlGraphics,
     viewport: Viewport,
-    cell_renderer: &Box<dyn CellRenderer>,
+    cell_renderer: &mut Box<dyn CellRenderer>,
     cell_size: f64,
 ) {
+    cell_renderer.prepare(sim);
+    let neighborhood = neighborhood_from_config(&sim.config.chemistry_config);
+
     gl.draw(viewport, |mut c, g| {
         let tick = sim.tick;
         clear(cell_renderer.bg_color(), g);
 
         for x in 0..sim.config.size.0 {
             for y in 0..sim.config.size.1 {
-                let x_pos = x as f64 * cell_size;
-                let y_pos = y as f64 * cell_size;
-                let rect = [x_pos, y_pos, cell_size, cell_size];
+                let rect = cell_rect(sim, &neighborhood, cell_size, &(x, y));
                 cell_renderer.draw_cell(sim, g, &mut c, x, y, rect);
             }
         }
     });
 }
 
-pub fn get_cell_renderer(chemistry_key: &str) -> Box<dyn CellRenderer> {
-    match chemistry_key {
+pub fn cell_rect(
+    sim: &SimulationData,
+    neighborhood: &GridNeighborhood,
+    cell_size: f64,
+    coord: &Coord,
+) -> [f64; 4] {
+    let (x, y) = *coord;
+    let x_pos = (x as f64 + row_shift(neighborhood, y, sim.config.size.1)) * cell_size;
+    let y_pos = y as f64 * cell_size;
+    [x_pos, y_pos, cell_size, cell_size]
+}
+
+/**
+ * The width the world takes up on screen, including the shifted rows of hex grids.
+ */
+pub fn world_width(sim: &SimulationData, cell_size: f64) -> f64 {
+    let neighborhood = neighborhood_from_config(&sim.config.chemistry_config);
+    let max_shift = row_shift(&neighborhood, 0, sim.config.size.1);
+    (sim.config.size.0 as f64 + max_shift) * cell_size
+}
+
+/**
+ * The cell under a point in window coordinates, ie. the inverse of the layout in draw_world.
+ */
+pub fn pick_cell(sim: &SimulationData, cell_size: f64, point: [f64; 2]) -> Option<Coord> {
+    let neighborhood = neighborhood_from_config(&sim.config.chemistry_config);
+    let (width, height) = sim.config.size;
+
+    if point[1] < 0.0 {
+        return None;
+    }
+    let y = (point[1] / cell_size).floor() as usize;
+    if y >= height {
+        return None;
+    }
+
+    let x_pos = point[0] / cell_size - row_shift(&neighborhood, y, height);
+    if x_pos < 0.0 || x_pos.floor() as usize >= width {
+        return None;
+    }
+
+    Some((x_pos.floor() as usize, y))
+}
+
+/**
+ * Hex grids are drawn with each row shifted half a cell relative to its neighbors so that the
+ * six neighbors of a cell surround it on screen.
+ */
+pub fn row_shift(neighborhood: &GridNeighborhood, y: usize, grid_height: usize) -> f64 {
+    match neighborhood {
+        GridNeighborhood::Hex => (grid_height - 1 - y) as f64 * 0.5,
+        _ => 0.0,
+    }
+}
+
+pub fn get_cell_renderer(sim: &SimulationData) -> Box<dyn CellRenderer> {
+    match sim.config.chemistry_key.as_str() {
         "cheese" => Box::new(CheeseCellRenderer::new()),
-        _ => unreachable!(),
+        chemistry_key => {
+            let manifest = ChemistryBuilder::with_key(chemistry_key)
+                .config(sim.config.chemistry_config.clone())
+                .manifest();
+            Box::new(ManifestCellRenderer::new(&manifest))
+        }
     }
 }
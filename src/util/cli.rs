@@ -1,8 +1,10 @@
+use crate::biology::experiments::tournament::TournamentFormat;
 use crate::runners::{
-    ExperimentRunnerArgs, ExperimentSimReplayGuiArgs, RunMode, SimulationRunnerArgs,
-    SimulationUiRunnerArgs,
+    ConfiguredExperimentArgs, ExperimentRunnerArgs, ExperimentSimReplayGuiArgs, LineageExportArgs,
+    LineageExportFormat, RunMode, SimulationRunnerArgs, SimulationUiRunnerArgs, SweepArgs,
+    TournamentArgs,
 };
-use clap::{Arg, ArgAction, Command};
+use clap::{Arg, ArgAction, ArgMatches, Command};
 
 pub fn parse_cli_args() -> RunMode {
     let iterations_arg = Arg::new("num_iterations")
@@ -117,6 +119,148 @@ pub fn parse_cli_args() -> RunMode {
                         .number_of_values(1),
                 ),
         )
+        .subcommand(
+            Command::new("sim_tui")
+                .about("Run a single simulation, drawn in the terminal")
+                .arg(chemistry_key_arg.clone())
+                .arg(scenario_key_arg.clone())
+                .arg(sim_tps_arg.clone())
+                .arg(ui_frame_rate_arg.clone())
+                .arg(iterations_arg.clone()),
+        )
+        .subcommand(
+            Command::new("exp_replay_tui")
+                .about(
+                    "Replay part of an experiment by running a single simulation in the terminal",
+                )
+                .arg(exp_name_key_arg.clone())
+                .arg(sim_tps_arg.clone())
+                .arg(ui_frame_rate_arg.clone())
+                .arg(
+                    Arg::new("genome_filename")
+                        .long("genome_file")
+                        .help("the file name of the genomes to use")
+                        .action(ArgAction::Set)
+                        .number_of_values(1),
+                ),
+        )
+        .subcommand(
+            Command::new("export_lineage")
+                .about("Export the lineage of a multi pool experiment's gene pool as Newick or GraphML")
+                .arg(exp_name_key_arg.clone())
+                .arg(
+                    Arg::new("gene_pool_id")
+                        .short('p')
+                        .long("pool")
+                        .help("The id of the gene pool to export")
+                        .action(ArgAction::Set)
+                        .number_of_values(1),
+                )
+                .arg(
+                    Arg::new("format")
+                        .short('f')
+                        .long("format")
+                        .help("Either newick or graphml")
+                        .action(ArgAction::Set)
+                        .number_of_values(1),
+                ),
+        )
+        .subcommand(
+            Command::new("run_experiment")
+                .visible_alias("run-experiment")
+                .about("Run a multi pool experiment described by a ron config file")
+                .arg(
+                    Arg::new("config_path")
+                        .long("config")
+                        .help("The path of the config file.  Experiments save theirs as config.ron")
+                        .action(ArgAction::Set)
+                        .number_of_values(1),
+                )
+                .arg(
+                    Arg::new("name_key")
+                        .short('n')
+                        .long("name")
+                        .help("Overrides the experiment name given in the config")
+                        .action(ArgAction::Set)
+                        .number_of_values(1),
+                ),
+        )
+        .subcommand(
+            Command::new("sweep")
+                .about("Run a multi pool experiment for every combination of a sweep's parameters and summarize their fitness")
+                .arg(
+                    Arg::new("spec_path")
+                        .long("spec")
+                        .help("The path of the ron sweep spec")
+                        .action(ArgAction::Set)
+                        .number_of_values(1),
+                )
+                .arg(
+                    Arg::new("max_parallel_runs")
+                        .short('j')
+                        .long("jobs")
+                        .help("Overrides how many experiments of the sweep run at once")
+                        .action(ArgAction::Set)
+                        .number_of_values(1),
+                ),
+        )
+        .subcommand(
+            Command::new("tournament")
+                .about("Play saved genomes against each other and rate them")
+                .arg(
+                    Arg::new("genomes")
+                        .short('g')
+                        .long("genomes")
+                        .help("An experiment name and genome file, as <experiment>:<file>.  Can be repeated")
+                        .action(ArgAction::Append)
+                        .number_of_values(1),
+                )
+                .arg(
+                    Arg::new("settings_from")
+                        .long("settings_from")
+                        .help("The experiment whose simulation settings are used, defaults to the first genome file's")
+                        .action(ArgAction::Set)
+                        .number_of_values(1),
+                )
+                .arg(
+                    Arg::new("format")
+                        .short('f')
+                        .long("format")
+                        .help("Either round_robin or swiss")
+                        .action(ArgAction::Set)
+                        .number_of_values(1),
+                )
+                .arg(
+                    Arg::new("rounds")
+                        .short('r')
+                        .long("rounds")
+                        .help("The number of rounds of a swiss tournament")
+                        .action(ArgAction::Set)
+                        .number_of_values(1),
+                )
+                .arg(
+                    Arg::new("trials")
+                        .short('t')
+                        .long("trials")
+                        .help("How many simulations each pairing plays")
+                        .action(ArgAction::Set)
+                        .number_of_values(1),
+                )
+                .arg(
+                    Arg::new("seed")
+                        .long("seed")
+                        .help("Seeds the matches, so that the tournament can be replayed")
+                        .action(ArgAction::Set)
+                        .number_of_values(1),
+                )
+                .arg(
+                    Arg::new("fitness_key")
+                        .long("fitness")
+                        .help("The fitness calculation used to decide matches")
+                        .action(ArgAction::Set)
+                        .number_of_values(1),
+                ),
+        )
         .get_matches();
 
     match matches.subcommand() {
@@ -248,6 +392,142 @@ pub fn parse_cli_args() -> RunMode {
             );
         }
 
+        Some(("sim_tui", sim_matches)) => {
+            let sim_scenario_key = sim_matches
+                .get_one::<String>("scenario_key")
+                .expect("Scenario key required");
+            let chemistry_key = sim_matches
+                .get_one::<String>("chemistry_key")
+                .expect("chemistry key required");
+            let iterations = sim_matches.get_one::<u64>("num_iterations");
+
+            let args = SimulationRunnerArgs {
+                chemistry_key: chemistry_key.clone(),
+                simulation_scenario_key: sim_scenario_key.clone(),
+                unit_entry_scenario_key: None,
+                iterations: iterations.map(|i| *i),
+            };
+
+            return RunMode::TerminalSimulation(args, parse_sim_ui_runner_args(sim_matches));
+        }
+
+        Some(("exp_replay_tui", matches)) => {
+            let default_name_key = "default".to_string();
+            let name_key = matches
+                .get_one::<String>("name_key")
+                .unwrap_or(&default_name_key);
+            let genome_filename = matches
+                .get_one::<String>("genome_filename")
+                .expect("genome file required");
+
+            return RunMode::ExperimentSimReplayTerminal(
+                ExperimentSimReplayGuiArgs {
+                    experiment_name_key: name_key.clone(),
+                    genome_filename: genome_filename.clone(),
+                },
+                parse_sim_ui_runner_args(matches),
+            );
+        }
+
+        Some(("run_experiment", matches)) => {
+            let config_path = matches
+                .get_one::<String>("config_path")
+                .expect("Config file required");
+
+            return RunMode::ConfiguredExperiment(ConfiguredExperimentArgs {
+                config_path: config_path.clone(),
+                experiment_name_key: matches.get_one::<String>("name_key").cloned(),
+            });
+        }
+
+        Some(("sweep", matches)) => {
+            let spec_path = matches
+                .get_one::<String>("spec_path")
+                .expect("Sweep spec required");
+
+            return RunMode::Sweep(SweepArgs {
+                spec_path: spec_path.clone(),
+                max_parallel_runs: matches
+                    .get_one::<String>("max_parallel_runs")
+                    .map(|x| x.parse::<usize>().expect("invalid number of jobs")),
+            });
+        }
+
+        Some(("tournament", matches)) => {
+            let genome_files = matches
+                .get_many::<String>("genomes")
+                .expect("At least one genome file required")
+                .map(|spec| {
+                    let (exp_key, filename) = spec
+                        .split_once(":")
+                        .expect("Genome files are given as <experiment>:<file>");
+                    (exp_key.to_string(), filename.to_string())
+                })
+                .collect::<Vec<_>>();
+
+            let num_rounds = matches
+                .get_one::<String>("rounds")
+                .map(|x| x.parse::<usize>().expect("invalid number of rounds"))
+                .unwrap_or(5);
+            let format = match matches.get_one::<String>("format").map(|x| x.as_str()) {
+                None | Some("round_robin") => TournamentFormat::RoundRobin,
+                Some("swiss") => TournamentFormat::Swiss { num_rounds },
+                Some(format) => panic!("Unknown tournament format: {}", format),
+            };
+
+            return RunMode::Tournament(TournamentArgs {
+                genome_files,
+                settings_experiment_name_key: matches.get_one::<String>("settings_from").cloned(),
+                format,
+                num_trials: matches
+                    .get_one::<String>("trials")
+                    .map(|x| x.parse::<usize>().expect("invalid number of trials"))
+                    .unwrap_or(5),
+                seed: matches
+                    .get_one::<String>("seed")
+                    .map(|x| x.parse::<u64>().expect("invalid seed"))
+                    .unwrap_or(0),
+                fitness_calculation_key: matches.get_one::<String>("fitness_key").cloned(),
+            });
+        }
+
+        Some(("export_lineage", matches)) => {
+            let default_name_key = "default".to_string();
+            let name_key = matches
+                .get_one::<String>("name_key")
+                .unwrap_or(&default_name_key);
+            let gene_pool_id = matches
+                .get_one::<String>("gene_pool_id")
+                .map(|x| x.parse::<usize>().expect("invalid gene pool id"))
+                .unwrap_or(0);
+            let format = match matches.get_one::<String>("format").map(|x| x.as_str()) {
+                None | Some("newick") => LineageExportFormat::Newick,
+                Some("graphml") => LineageExportFormat::GraphMl,
+                Some(format) => panic!("Unknown lineage export format: {}", format),
+            };
+
+            return RunMode::ExportLineage(LineageExportArgs {
+                experiment_name_key: name_key.clone(),
+                gene_pool_id,
+                format,
+            });
+        }
+
         _ => unreachable!(), // If all subcommands are defined above, anything else is unreachable
     }
 }
+
+fn parse_sim_ui_runner_args(matches: &ArgMatches) -> SimulationUiRunnerArgs {
+    let sim_ticks_per_second = matches
+        .get_one::<String>("sim_ticks_per_second")
+        .map(|x| x.parse::<u32>().unwrap());
+    let ui_frame_rate = matches
+        .get_one::<String>("ui_frame_rate")
+        .map(|x| x.parse::<u32>().unwrap());
+
+    SimulationUiRunnerArgs {
+        // the tps should be at least the frame rate
+        max_ticks_per_second: sim_ticks_per_second.or(ui_frame_rate),
+        max_view_updates_per_second: ui_frame_rate,
+    }
+}
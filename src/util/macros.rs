@@ -245,6 +245,24 @@ macro_rules! chemistry_arg {
 
 #[macro_export]
 macro_rules! constant_arg {
+    (UnitResourceKey, $val:expr) => {
+        ActionParam::UnitResourceKey($val.to_string())
+    };
+    (UnitAttributeKey, $val:expr) => {
+        ActionParam::UnitAttributeKey($val.to_string())
+    };
+    (UnitEntryAttributeKey, $val:expr) => {
+        ActionParam::UnitEntryAttributeKey($val.to_string())
+    };
+    (PositionResourceKey, $val:expr) => {
+        ActionParam::PositionResourceKey($val.to_string())
+    };
+    (PositionAttributeKey, $val:expr) => {
+        ActionParam::PositionAttributeKey($val.to_string())
+    };
+    (SimulationAttributeKey, $val:expr) => {
+        ActionParam::SimulationAttributeKey($val.to_string())
+    };
     ($x:ident, $val:expr) => {
         ActionParam::$x($val)
     };
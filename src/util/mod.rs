@@ -41,6 +41,20 @@ impl RateCounter {
     pub fn increment(&mut self) {
         self.count = self.count + 1;
     }
+
+    /**
+     * The rate in events per second, recalculated at most once a second.
+     */
+    pub fn per_second(&mut self) -> f64 {
+        let elapsed_ms = self.last_update.elapsed().as_millis();
+        if elapsed_ms >= 1000 {
+            self.last_rate = self.count as f64 / elapsed_ms as f64;
+            self.count = 0;
+            self.last_update = Instant::now();
+        }
+
+        self.last_rate * 1000.0
+    }
 }
 
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
@@ -49,6 +63,10 @@ pub enum GridDirection {
     Right,
     Down,
     Left,
+    UpRight,
+    DownRight,
+    DownLeft,
+    UpLeft,
 }
 
 use std::fmt::{Debug, Formatter, Result};
@@ -60,6 +78,10 @@ impl Debug for GridDirection {
             GridDirection::Down => write!(f, "(DOWN)"),
             GridDirection::Right => write!(f, "(RIGHT)"),
             GridDirection::Left => write!(f, "(LEFT)"),
+            GridDirection::UpRight => write!(f, "(UP_RIGHT)"),
+            GridDirection::DownRight => write!(f, "(DOWN_RIGHT)"),
+            GridDirection::DownLeft => write!(f, "(DOWN_LEFT)"),
+            GridDirection::UpLeft => write!(f, "(UP_LEFT)"),
         }
     }
 }
@@ -70,44 +92,201 @@ pub fn grid_direction_to_num(dir: GridDirection) -> u8 {
         GridDirection::Right => 1,
         GridDirection::Down => 2,
         GridDirection::Left => 3,
+        GridDirection::UpRight => 4,
+        GridDirection::DownRight => 5,
+        GridDirection::DownLeft => 6,
+        GridDirection::UpLeft => 7,
     }
 }
 
+/**
+ * Maps a number onto one of the eight directions, wrapping values that are out of range.
+ * Use GridNeighborhood::direction_from_num when the direction needs to be valid for a
+ * specific neighborhood.
+ */
 pub fn grid_direction_from_num(num: u8) -> GridDirection {
-    match num {
+    match num % 8 {
         0 => GridDirection::Up,
         1 => GridDirection::Right,
         2 => GridDirection::Down,
         3 => GridDirection::Left,
-        _ => GridDirection::Up,
+        4 => GridDirection::UpRight,
+        5 => GridDirection::DownRight,
+        6 => GridDirection::DownLeft,
+        _ => GridDirection::UpLeft,
     }
 }
 
 pub fn grid_direction_from_string(key: &str) -> Option<GridDirection> {
-    if key.to_lowercase() == "up" {
-        return Some(GridDirection::Up);
-    } else if key.to_lowercase() == "right" {
-        return Some(GridDirection::Right);
-    } else if key.to_lowercase() == "down" {
-        return Some(GridDirection::Down);
-    } else if key.to_lowercase() == "left" {
-        return Some(GridDirection::Left);
+    match key.to_lowercase().as_str() {
+        "up" => Some(GridDirection::Up),
+        "right" => Some(GridDirection::Right),
+        "down" => Some(GridDirection::Down),
+        "left" => Some(GridDirection::Left),
+        "up_right" => Some(GridDirection::UpRight),
+        "down_right" => Some(GridDirection::DownRight),
+        "down_left" => Some(GridDirection::DownLeft),
+        "up_left" => Some(GridDirection::UpLeft),
+        _ => None,
     }
+}
 
-    return None;
+/**
+ * The set of cells that count as adjacent to a given cell.  This determines which directions
+ * are available to actions like move_unit and new_unit.
+ *
+ * The hex neighborhood is laid out on the rectangular grid using axial coordinates, with
+ * each row offset by half a cell from the next.  A cell's six neighbors are (x-1, y) and
+ * (x+1, y), (x, y+1) and (x+1, y+1) on the row above, and (x-1, y-1) and (x, y-1) below.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum GridNeighborhood {
+    VonNeumann,
+    Moore,
+    Hex,
 }
 
+impl Default for GridNeighborhood {
+    fn default() -> Self {
+        GridNeighborhood::VonNeumann
+    }
+}
+
+const VON_NEUMANN_DIRECTIONS: [GridDirection; 4] = [
+    GridDirection::Up,
+    GridDirection::Right,
+    GridDirection::Down,
+    GridDirection::Left,
+];
+
+// the first four match the von neumann directions so that direction params keep
+// their meaning when switching a chemistry between the two
+const MOORE_DIRECTIONS: [GridDirection; 8] = [
+    GridDirection::Up,
+    GridDirection::Right,
+    GridDirection::Down,
+    GridDirection::Left,
+    GridDirection::UpRight,
+    GridDirection::DownRight,
+    GridDirection::DownLeft,
+    GridDirection::UpLeft,
+];
+
+const HEX_DIRECTIONS: [GridDirection; 6] = [
+    GridDirection::UpRight,
+    GridDirection::Right,
+    GridDirection::DownRight,
+    GridDirection::DownLeft,
+    GridDirection::Left,
+    GridDirection::UpLeft,
+];
+
+impl GridNeighborhood {
+    pub fn directions(&self) -> &'static [GridDirection] {
+        match self {
+            GridNeighborhood::VonNeumann => &VON_NEUMANN_DIRECTIONS,
+            GridNeighborhood::Moore => &MOORE_DIRECTIONS,
+            GridNeighborhood::Hex => &HEX_DIRECTIONS,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.directions().len()
+    }
+
+    /**
+     * Out of range values wrap around so that every raw value maps to a direction with
+     * the same likelihood.
+     */
+    pub fn direction_from_num(&self, num: u16) -> GridDirection {
+        let directions = self.directions();
+        directions[num as usize % directions.len()].clone()
+    }
+
+    pub fn direction_to_num(&self, dir: &GridDirection) -> Option<u16> {
+        self.directions()
+            .iter()
+            .position(|d| d == dir)
+            .map(|i| i as u16)
+    }
+
+    /**
+     * Returns None if the direction isn't part of this neighborhood
+     */
+    pub fn direction_offset(&self, direction: &GridDirection) -> Option<CoordOffset> {
+        match self {
+            GridNeighborhood::VonNeumann | GridNeighborhood::Moore => {
+                if self.directions().contains(direction) {
+                    Some(square_direction_offset(direction))
+                } else {
+                    None
+                }
+            }
+            GridNeighborhood::Hex => match direction {
+                GridDirection::UpRight => Some((1, 1)),
+                GridDirection::Right => Some((1, 0)),
+                GridDirection::DownRight => Some((0, -1)),
+                GridDirection::DownLeft => Some((-1, -1)),
+                GridDirection::Left => Some((-1, 0)),
+                GridDirection::UpLeft => Some((0, 1)),
+                _ => None,
+            },
+        }
+    }
+
+    pub fn offsets(&self) -> Vec<(CoordOffset, GridDirection)> {
+        self.directions()
+            .iter()
+            .map(|dir| (self.direction_offset(dir).unwrap(), dir.clone()))
+            .collect::<Vec<_>>()
+    }
+
+    pub fn coord_by_direction_offset(
+        &self,
+        coord: &Coord,
+        direction: &GridDirection,
+        size: GridSize2D,
+    ) -> Option<Coord> {
+        self.direction_offset(direction)
+            .and_then(|offset| coord_by_coord_offset(coord, offset, size))
+    }
+
+    /**
+     * The number of steps needed to reach the offset when moving only between neighbors.
+     */
+    pub fn distance(&self, offset: CoordOffset) -> u32 {
+        let (x, y) = (offset.0.abs() as u32, offset.1.abs() as u32);
+        match self {
+            GridNeighborhood::VonNeumann => x + y,
+            GridNeighborhood::Moore => x.max(y),
+            GridNeighborhood::Hex => x.max(y).max((offset.0 - offset.1).abs() as u32),
+        }
+    }
+}
+
+fn square_direction_offset(direction: &GridDirection) -> CoordOffset {
+    match direction {
+        GridDirection::Up => (0, 1),
+        GridDirection::Right => (1, 0),
+        GridDirection::Down => (0, -1),
+        GridDirection::Left => (-1, 0),
+        GridDirection::UpRight => (1, 1),
+        GridDirection::DownRight => (1, -1),
+        GridDirection::DownLeft => (-1, -1),
+        GridDirection::UpLeft => (-1, 1),
+    }
+}
+
+/**
+ * Offsets by direction on a square grid.  Use GridNeighborhood::coord_by_direction_offset
+ * when the chemistry's neighborhood might be hex.
+ */
 pub fn coord_by_direction_offset(
     coord: &Coord,
     direction: &GridDirection,
     size: GridSize2D,
 ) -> Option<Coord> {
-    let offset = match direction {
-        GridDirection::Up => (0, 1),
-        GridDirection::Right => (1, 0),
-        GridDirection::Down => (0, -1),
-        GridDirection::Left => (-1, 0),
-    };
+    let offset = square_direction_offset(direction);
 
     //assert_coords_valid_for_size(
     coord_by_coord_offset(coord, offset, size)
@@ -14,30 +14,50 @@ pub fn render_into_grid(
     renderer: &Box<dyn GridCellRenderer>,
     world: &World,
 ) -> String {
+    render_cells_into_grid(&options, world.size, |coord| renderer.render(coord, world))
+}
+
+/**
+ * Lays out the text of each cell, with the highest row on top.  Cells are padded to the cell
+ * width by their visible characters, so that they can carry ANSI escape codes.
+ */
+pub fn render_cells_into_grid<F>(
+    options: &TextGridOptions,
+    size: GridSize2D,
+    render_cell: F,
+) -> String
+where
+    F: Fn(&Coord) -> String,
+{
     let v_border_char = '|';
     let h_border_char = '-';
 
-    let size_x = world.size.0;
-    let size_y = world.size.1;
+    let size_x = size.0;
+    let size_y = size.1;
 
     let total_width: usize = if options.has_border {
         (options.cell_width + 1) * size_x + 1
     } else {
-        options.cell_width * size_y
+        options.cell_width * size_x
+    };
+
+    let alignment = match options.alignment {
+        CellTextAlignment::Center => pad::Alignment::Middle,
+        CellTextAlignment::Left => pad::Alignment::Left,
+        CellTextAlignment::Right => pad::Alignment::Right,
     };
 
     let mut buffer = String::new();
 
     if (options.has_border) {
-        buffer += &("".pad(total_width, '-', pad::Alignment::Left, true) + "\n");
+        buffer += &("".pad(total_width, h_border_char, pad::Alignment::Left, true) + "\n");
     }
 
     for y_off in 0..size_y {
         let mut lines: Vec<Vec<String>> = vec![];
         for x in 0..size_x {
             let y = size_y - 1 - y_off;
-            println!("{}, {}, {}", x, y, y_off);
-            let cell_str = renderer.render(&(x, y), world);
+            let cell_str = render_cell(&(x, y));
             let a = cell_str
                 .split("\n")
                 .map(|x| -> String { x.to_string() })
@@ -47,7 +67,7 @@ pub fn render_into_grid(
 
         for cell_line in 0..options.cell_height {
             if (options.has_border) {
-                buffer += "|";
+                buffer.push(v_border_char);
             }
 
             for x in 0..size_x {
@@ -56,19 +76,11 @@ pub fn render_into_grid(
                 } else {
                     "".to_string()
                 };
-                //println!("what: {}", options.cell_width);
-                //println!("here: {}", s);
-
-                let alignment = match options.alignment {
-                    CellTextAlignment::Center => pad::Alignment::Middle,
-                    CellTextAlignment::Left => pad::Alignment::Left,
-                    CellTextAlignment::Right => pad::Alignment::Right,
-                };
 
-                buffer += &s.pad(options.cell_width, ' ', alignment, true);
+                buffer += &pad_cell(&s, options.cell_width, alignment);
 
                 if (options.has_border) {
-                    buffer += "|";
+                    buffer.push(v_border_char);
                 }
             }
 
@@ -83,6 +95,42 @@ pub fn render_into_grid(
     buffer
 }
 
+/**
+ * Plain text is cut to the width, styled text is only ever padded since cutting into an escape
+ * code would garble the rest of the line.
+ */
+fn pad_cell(s: &str, width: usize, alignment: pad::Alignment) -> String {
+    if !s.contains('\x1b') {
+        return s.pad(width, ' ', alignment, true);
+    }
+
+    let missing = width.saturating_sub(visible_width(s));
+    let (left, right) = match alignment {
+        pad::Alignment::Left => (0, missing),
+        pad::Alignment::Right => (missing, 0),
+        _ => (missing / 2, missing - missing / 2),
+    };
+    format!("{}{}{}", " ".repeat(left), s, " ".repeat(right))
+}
+
+/**
+ * The number of characters that show up on screen, ie. leaving out ANSI escape codes.
+ */
+pub fn visible_width(s: &str) -> usize {
+    let mut width = 0;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // skip to the letter ending the sequence
+            chars.find(|c| c.is_ascii_alphabetic());
+        } else {
+            width += 1;
+        }
+    }
+
+    width
+}
+
 pub trait GridCellRenderer {
     fn render(&self, coord: &Coord, world: &World) -> String;
 }
@@ -170,4 +218,21 @@ mod tests {
 
         assert_eq!(s.trim().to_string(), e.to_string());
     }
+
+    #[test]
+    fn render_styled_cells() {
+        let options = TextGridOptions {
+            cell_width: 4,
+            cell_height: 1,
+            has_border: false,
+            alignment: CellTextAlignment::Left,
+        };
+
+        let s = render_cells_into_grid(&options, (2, 1), |coord| {
+            format!("\x1b[1m{}\x1b[0m", coord.0)
+        });
+
+        assert_eq!(s, "\x1b[1m0\x1b[0m   \x1b[1m1\x1b[0m   \n");
+        assert_eq!(visible_width(&s), 9);
+    }
 }